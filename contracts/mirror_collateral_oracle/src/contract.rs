@@ -1,17 +1,19 @@
+use crate::math::decimal_division;
 use crate::migration::{migrate_collateral_infos, migrate_config};
 use crate::querier::query_price;
 use crate::state::{
-    read_collateral_info, read_collateral_infos, read_config, store_collateral_info, store_config,
-    CollateralAssetInfo, Config,
+    read_collateral_info, read_collateral_infos, read_collateral_infos_paginated, read_config,
+    store_collateral_info, store_config, CollateralAssetInfo, Config,
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     to_binary, Binary, CanonicalAddr, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError,
-    StdResult,
+    StdResult, Uint128,
 };
 use mirror_protocol::collateral_oracle::{
-    CollateralInfoResponse, CollateralInfosResponse, CollateralPriceResponse, ConfigResponse,
+    CollateralInfoResponse, CollateralInfosResponse, CollateralPriceResponse,
+    CollateralStatusResponse, CollateralValueResponse, CollateralsResponse, ConfigResponse,
     ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SourceType,
 };
 use terraswap::asset::AssetInfo;
@@ -61,6 +63,12 @@ pub fn execute(
         ExecuteMsg::UpdateCollateralMultiplier { asset, multiplier } => {
             update_collateral_multiplier(deps, info, asset, multiplier)
         }
+        ExecuteMsg::SuspendCollateralAsset { asset } => {
+            update_collateral_suspension(deps, info, asset, true)
+        }
+        ExecuteMsg::ResumeCollateralAsset { asset } => {
+            update_collateral_suspension(deps, info, asset, false)
+        }
     }
 }
 
@@ -122,6 +130,7 @@ pub fn register_collateral(
             multiplier,
             price_source,
             is_revoked: false,
+            is_suspended: false,
         },
     )?;
 
@@ -211,6 +220,32 @@ pub fn update_collateral_multiplier(
     Ok(Response::default())
 }
 
+pub fn update_collateral_suspension(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: AssetInfo,
+    is_suspended: bool,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    let sender_address_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    // only contract owner can suspend or resume a collateral
+    if config.owner != sender_address_raw {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let mut collateral_info: CollateralAssetInfo =
+        if let Ok(collateral) = read_collateral_info(deps.storage, &asset.to_string()) {
+            collateral
+        } else {
+            return Err(StdError::generic_err("Collateral not found"));
+        };
+
+    collateral_info.is_suspended = is_suspended;
+    store_collateral_info(deps.storage, &collateral_info)?;
+
+    Ok(Response::default())
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -220,6 +255,12 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         }
         QueryMsg::CollateralAssetInfo { asset } => to_binary(&query_collateral_info(deps, asset)?),
         QueryMsg::CollateralAssetInfos {} => to_binary(&query_collateral_infos(deps)?),
+        QueryMsg::CollateralValue { collateral, amount } => {
+            to_binary(&query_collateral_value(deps, env, collateral, amount)?)
+        }
+        QueryMsg::Collaterals { start_after, limit } => {
+            to_binary(&query_collaterals(deps, env, start_after, limit)?)
+        }
     }
 }
 
@@ -260,10 +301,40 @@ pub fn query_collateral_price(
 
     Ok(CollateralPriceResponse {
         asset: collateral.asset,
+        base_denom: config.base_denom,
         rate: price,
         last_updated,
         multiplier: collateral.multiplier,
         is_revoked: collateral.is_revoked,
+        is_suspended: collateral.is_suspended,
+    })
+}
+
+pub fn query_collateral_value(
+    deps: Deps,
+    env: Env,
+    collateral: AssetInfo,
+    amount: Uint128,
+) -> StdResult<CollateralValueResponse> {
+    let price_res: CollateralPriceResponse =
+        query_collateral_price(deps, env, collateral.to_string(), None)?;
+
+    // suspended collaterals still report their price, but can not be valued
+    if price_res.is_suspended {
+        return Err(StdError::generic_err("Collateral asset is suspended"));
+    }
+
+    let value = amount * price_res.rate;
+    let adjusted_value = value * decimal_division(Decimal::one(), price_res.multiplier);
+
+    Ok(CollateralValueResponse {
+        asset: price_res.asset,
+        base_denom: price_res.base_denom,
+        amount,
+        rate: price_res.rate,
+        multiplier: price_res.multiplier,
+        value,
+        adjusted_value,
     })
 }
 
@@ -280,6 +351,7 @@ pub fn query_collateral_info(deps: Deps, quote_asset: String) -> StdResult<Colla
         source_type: collateral.price_source.to_string(),
         multiplier: collateral.multiplier,
         is_revoked: collateral.is_revoked,
+        is_suspended: collateral.is_suspended,
     })
 }
 
@@ -289,6 +361,54 @@ pub fn query_collateral_infos(deps: Deps) -> StdResult<CollateralInfosResponse>
     Ok(CollateralInfosResponse { collaterals: infos })
 }
 
+pub fn query_collaterals(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CollateralsResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let infos: Vec<CollateralAssetInfo> =
+        read_collateral_infos_paginated(deps.storage, start_after, limit)?;
+
+    let collaterals: Vec<CollateralStatusResponse> = infos
+        .into_iter()
+        .map(|collateral| {
+            // a failing source should not hide the rest of the collaterals
+            let price = query_price(
+                deps,
+                env.clone(),
+                &config,
+                &collateral.asset,
+                None,
+                &collateral.price_source,
+            )
+            .ok();
+
+            let staleness = price.and_then(|(_, last_updated)| {
+                if last_updated == u64::MAX {
+                    None
+                } else {
+                    Some(env.block.time.seconds().saturating_sub(last_updated))
+                }
+            });
+
+            CollateralStatusResponse {
+                asset: collateral.asset,
+                source_type: collateral.price_source.to_string(),
+                multiplier: collateral.multiplier,
+                is_revoked: collateral.is_revoked,
+                is_suspended: collateral.is_suspended,
+                rate: price.map(|(rate, _)| rate),
+                last_updated: price.map(|(_, last_updated)| last_updated),
+                staleness,
+            }
+        })
+        .collect();
+
+    Ok(CollateralsResponse { collaterals })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
     migrate_config(deps.storage)?;
@@ -117,6 +117,7 @@ pub fn migrate_collateral_infos(
             multiplier: legacy_collateral_info.multiplier,
             price_source: new_price_source,
             is_revoked: legacy_collateral_info.is_revoked,
+            is_suspended: false,
         };
         new_pool_infos_bucket.save(new_collateral_info.asset.as_bytes(), new_collateral_info)?;
     }
@@ -192,6 +193,7 @@ mod migrate_tests {
                     oracle_addr: "mirrortefi0000".to_string(),
                 },
                 is_revoked: false,
+                is_suspended: false,
             }
         );
         assert_eq!(
@@ -204,6 +206,7 @@ mod migrate_tests {
                     intermediate_denom: None,
                 },
                 is_revoked: false,
+                is_suspended: false,
             }
         );
         assert_eq!(
@@ -215,6 +218,7 @@ mod migrate_tests {
                     oracle_addr: "mirrortefi0000".to_string(),
                 },
                 is_revoked: false,
+                is_suspended: false,
             }
         )
     }
@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 pub static PREFIX_COLLATERAL_ASSET_INFO: &[u8] = b"collateral_asset_info";
 pub static KEY_CONFIG: &[u8] = b"config";
 
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: CanonicalAddr,
@@ -28,6 +31,7 @@ pub struct CollateralAssetInfo {
     pub price_source: SourceType,
     pub multiplier: Decimal,
     pub is_revoked: bool,
+    pub is_suspended: bool,
 }
 
 pub fn store_collateral_info(
@@ -59,7 +63,38 @@ pub fn read_collateral_infos(storage: &dyn Storage) -> StdResult<Vec<CollateralI
                 source_type: v.price_source.to_string(),
                 multiplier: v.multiplier,
                 is_revoked: v.is_revoked,
+                is_suspended: v.is_suspended,
             })
         })
         .collect()
 }
+
+pub fn read_collateral_infos_paginated(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<CollateralAssetInfo>> {
+    let price_bucket: ReadonlyBucket<CollateralAssetInfo> =
+        ReadonlyBucket::new(storage, PREFIX_COLLATERAL_ASSET_INFO);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    price_bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, v) = item?;
+            Ok(v)
+        })
+        .collect()
+}
+
+// this will set the first key after the provided key, by appending a 1 byte
+fn calc_range_start(start_after: Option<String>) -> Option<Vec<u8>> {
+    start_after.map(|asset| {
+        let mut v = asset.as_bytes().to_vec();
+        v.push(1);
+        v
+    })
+}
@@ -1,11 +1,13 @@
 use crate::contract::{
-    execute, instantiate, query_collateral_info, query_collateral_price, query_config,
+    execute, instantiate, query_collateral_info, query_collateral_price, query_collateral_value,
+    query_collaterals, query_config,
 };
 use crate::testing::mock_querier::mock_dependencies;
 use cosmwasm_std::testing::{mock_env, mock_info};
 use cosmwasm_std::{Decimal, StdError, Uint128};
 use mirror_protocol::collateral_oracle::{
-    CollateralInfoResponse, CollateralPriceResponse, ExecuteMsg, InstantiateMsg, SourceType,
+    CollateralInfoResponse, CollateralPriceResponse, CollateralStatusResponse,
+    CollateralValueResponse, ExecuteMsg, InstantiateMsg, SourceType,
 };
 use terraswap::asset::AssetInfo;
 
@@ -123,6 +125,7 @@ fn register_collateral() {
             source_type: "tefi_oracle".to_string(),
             multiplier: Decimal::percent(100),
             is_revoked: false,
+            is_suspended: false,
         }
     )
 }
@@ -168,6 +171,7 @@ fn update_collateral() {
             source_type: "tefi_oracle".to_string(),
             multiplier: Decimal::percent(100),
             is_revoked: false,
+            is_suspended: false,
         }
     );
 
@@ -200,6 +204,7 @@ fn update_collateral() {
             source_type: "fixed_price".to_string(),
             multiplier: Decimal::percent(100),
             is_revoked: false,
+            is_suspended: false,
         }
     );
 
@@ -246,6 +251,7 @@ fn update_collateral() {
             source_type: "fixed_price".to_string(),
             multiplier: Decimal::percent(120),
             is_revoked: false,
+            is_suspended: false,
         }
     )
 }
@@ -286,10 +292,12 @@ fn get_oracle_price() {
         query_res,
         CollateralPriceResponse {
             asset: "mTSLA".to_string(),
+            base_denom: "uusd".to_string(),
             rate: Decimal::percent(100),
             last_updated: 1000u64,
             multiplier: Decimal::percent(100),
             is_revoked: false,
+            is_suspended: false,
         }
     );
 }
@@ -348,10 +356,12 @@ fn get_amm_pair_price() {
         query_res,
         CollateralPriceResponse {
             asset: "anc0000".to_string(),
+            base_denom: "uusd".to_string(),
             rate: Decimal::from_ratio(1u128, 100u128),
             last_updated: u64::MAX,
             multiplier: Decimal::percent(100),
             is_revoked: false,
+            is_suspended: false,
         }
     );
 
@@ -377,10 +387,12 @@ fn get_amm_pair_price() {
         query_res,
         CollateralPriceResponse {
             asset: "bluna0000".to_string(),
+            base_denom: "uusd".to_string(),
             rate: Decimal::from_ratio(45u128, 1u128), // 9 / 1 * 5 / 1
             last_updated: u64::MAX,
             multiplier: Decimal::percent(100),
             is_revoked: false,
+            is_suspended: false,
         }
     );
 }
@@ -418,14 +430,203 @@ fn get_fixed_price() {
         query_res,
         CollateralPriceResponse {
             asset: "aUST".to_string(),
+            base_denom: "uusd".to_string(),
             rate: Decimal::from_ratio(1u128, 2u128),
             last_updated: u64::MAX,
             multiplier: Decimal::percent(100),
             is_revoked: false,
+            is_suspended: false,
         }
     );
 }
 
+#[test]
+fn get_price_in_configured_base_denom() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        mint_contract: "mint0000".to_string(),
+        base_denom: "uusd".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        mint_contract: None,
+        base_denom: Some("uusdc".to_string()),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterCollateralAsset {
+        asset: AssetInfo::Token {
+            contract_addr: "aUST".to_string(),
+        },
+        multiplier: Decimal::percent(100),
+        price_source: SourceType::FixedPrice {
+            price: Decimal::from_ratio(1u128, 2u128),
+        },
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // prices are quoted in the configured base denom
+    let query_res =
+        query_collateral_price(deps.as_ref(), mock_env(), "aUST".to_string(), None).unwrap();
+    assert_eq!(query_res.base_denom, "uusdc".to_string());
+
+    let query_res = query_collateral_value(
+        deps.as_ref(),
+        mock_env(),
+        AssetInfo::Token {
+            contract_addr: "aUST".to_string(),
+        },
+        Uint128::from(100u128),
+    )
+    .unwrap();
+    assert_eq!(query_res.base_denom, "uusdc".to_string());
+    assert_eq!(query_res.value, Uint128::from(50u128));
+}
+
+#[test]
+fn get_collateral_value() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        mint_contract: "mint0000".to_string(),
+        base_denom: "uusd".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterCollateralAsset {
+        asset: AssetInfo::Token {
+            contract_addr: "aUST".to_string(),
+        },
+        multiplier: Decimal::percent(125),
+        price_source: SourceType::FixedPrice {
+            price: Decimal::from_ratio(1u128, 2u128),
+        },
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let query_res = query_collateral_value(
+        deps.as_ref(),
+        mock_env(),
+        AssetInfo::Token {
+            contract_addr: "aUST".to_string(),
+        },
+        Uint128::from(1000u128),
+    )
+    .unwrap();
+    assert_eq!(
+        query_res,
+        CollateralValueResponse {
+            asset: "aUST".to_string(),
+            base_denom: "uusd".to_string(),
+            amount: Uint128::from(1000u128),
+            rate: Decimal::from_ratio(1u128, 2u128),
+            multiplier: Decimal::percent(125),
+            value: Uint128::from(500u128),
+            adjusted_value: Uint128::from(400u128), // 500 / 1.25
+        }
+    );
+
+    // unregistered collateral
+    let err = query_collateral_value(
+        deps.as_ref(),
+        mock_env(),
+        AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        },
+        Uint128::from(1000u128),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("Collateral asset not found"));
+}
+
+#[test]
+fn query_collaterals_with_status() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"mTSLA".to_string(), &Decimal::percent(100)),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        mint_contract: "mint0000".to_string(),
+        base_denom: "uusd".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::RegisterCollateralAsset {
+        asset: AssetInfo::Token {
+            contract_addr: "mTSLA".to_string(),
+        },
+        multiplier: Decimal::percent(100),
+        price_source: SourceType::TefiOracle {
+            oracle_addr: "mirrorOracle0000".to_string(),
+        },
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterCollateralAsset {
+        asset: AssetInfo::Token {
+            contract_addr: "aUST".to_string(),
+        },
+        multiplier: Decimal::percent(120),
+        price_source: SourceType::FixedPrice {
+            price: Decimal::from_ratio(1u128, 2u128),
+        },
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let aust_status = CollateralStatusResponse {
+        asset: "aUST".to_string(),
+        source_type: "fixed_price".to_string(),
+        multiplier: Decimal::percent(120),
+        is_revoked: false,
+        is_suspended: false,
+        rate: Some(Decimal::from_ratio(1u128, 2u128)),
+        last_updated: Some(u64::MAX),
+        staleness: None,
+    };
+    let mtsla_status = CollateralStatusResponse {
+        asset: "mTSLA".to_string(),
+        source_type: "tefi_oracle".to_string(),
+        multiplier: Decimal::percent(100),
+        is_revoked: false,
+        is_suspended: false,
+        rate: Some(Decimal::percent(100)),
+        last_updated: Some(1000u64),
+        staleness: Some(mock_env().block.time.seconds() - 1000u64),
+    };
+
+    let res = query_collaterals(deps.as_ref(), mock_env(), None, None).unwrap();
+    assert_eq!(
+        res.collaterals,
+        vec![aust_status.clone(), mtsla_status.clone()]
+    );
+
+    // paginate
+    let res = query_collaterals(deps.as_ref(), mock_env(), None, Some(1)).unwrap();
+    assert_eq!(res.collaterals, vec![aust_status]);
+    let res =
+        query_collaterals(deps.as_ref(), mock_env(), Some("aUST".to_string()), Some(1)).unwrap();
+    assert_eq!(res.collaterals, vec![mtsla_status]);
+}
+
 #[test]
 fn get_anchor_market_price() {
     let mut deps = mock_dependencies(&[]);
@@ -459,10 +660,12 @@ fn get_anchor_market_price() {
         query_res,
         CollateralPriceResponse {
             asset: "aust0000".to_string(),
+            base_denom: "uusd".to_string(),
             rate: Decimal::from_ratio(10u128, 3u128),
             last_updated: u64::MAX,
             multiplier: Decimal::percent(100),
             is_revoked: false,
+            is_suspended: false,
         }
     );
 }
@@ -500,10 +703,12 @@ fn get_lunax_price() {
         query_res,
         CollateralPriceResponse {
             asset: "lunax0000".to_string(),
+            base_denom: "uusd".to_string(),
             rate: Decimal::from_ratio(55u128, 10u128), // exchange rate = 1.1 i.e 1 ulunax = 1.1 uluna and 1 uluna = 5 uusd
             last_updated: u64::MAX,
             multiplier: Decimal::percent(100),
             is_revoked: false,
+            is_suspended: false,
         }
     );
 }
@@ -541,10 +746,12 @@ fn get_native_price() {
         query_res,
         CollateralPriceResponse {
             asset: "uluna".to_string(),
+            base_denom: "uusd".to_string(),
             rate: Decimal::from_ratio(5u128, 1u128),
             last_updated: u64::MAX,
             multiplier: Decimal::percent(100),
             is_revoked: false,
+            is_suspended: false,
         }
     );
 }
@@ -582,10 +789,12 @@ fn revoke_collateral() {
         query_res,
         CollateralPriceResponse {
             asset: "aUST".to_string(),
+            base_denom: "uusd".to_string(),
             rate: Decimal::one(),
             last_updated: u64::MAX,
             multiplier: Decimal::percent(100),
             is_revoked: false,
+            is_suspended: false,
         }
     );
 
@@ -615,6 +824,7 @@ fn revoke_collateral() {
             source_type: "fixed_price".to_string(),
             multiplier: Decimal::percent(100),
             is_revoked: true,
+            is_suspended: false,
         }
     );
 
@@ -625,10 +835,123 @@ fn revoke_collateral() {
         query_res,
         CollateralPriceResponse {
             asset: "aUST".to_string(),
+            base_denom: "uusd".to_string(),
             rate: Decimal::one(),
             last_updated: u64::MAX,
             multiplier: Decimal::percent(100),
             is_revoked: true,
+            is_suspended: false,
+        }
+    );
+}
+
+#[test]
+fn suspend_collateral() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        mint_contract: "mint0000".to_string(),
+        base_denom: "uusd".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterCollateralAsset {
+        asset: AssetInfo::Token {
+            contract_addr: "aUST".to_string(),
+        },
+        multiplier: Decimal::percent(100),
+        price_source: SourceType::FixedPrice {
+            price: Decimal::one(),
+        },
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::SuspendCollateralAsset {
+        asset: AssetInfo::Token {
+            contract_addr: "aUST".to_string(),
+        },
+    };
+
+    // mint contract can not suspend
+    let info = mock_info("mint0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    // unregistered collateral
+    let info = mock_info("owner0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::SuspendCollateralAsset {
+            asset: AssetInfo::Token {
+                contract_addr: "bLUNA".to_string(),
+            },
+        },
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("Collateral not found"));
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the price is still reported, flagged as suspended
+    let query_res =
+        query_collateral_price(deps.as_ref(), mock_env(), "aUST".to_string(), None).unwrap();
+    assert!(query_res.is_suspended);
+    assert_eq!(query_res.rate, Decimal::one());
+
+    // new valuations are blocked
+    let err = query_collateral_value(
+        deps.as_ref(),
+        mock_env(),
+        AssetInfo::Token {
+            contract_addr: "aUST".to_string(),
+        },
+        Uint128::from(1000u128),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("Collateral asset is suspended"));
+
+    // collateral info is still readable
+    let query_res = query_collateral_info(deps.as_ref(), "aUST".to_string()).unwrap();
+    assert_eq!(
+        query_res,
+        CollateralInfoResponse {
+            asset: "aUST".to_string(),
+            source_type: "fixed_price".to_string(),
+            multiplier: Decimal::percent(100),
+            is_revoked: false,
+            is_suspended: true,
+        }
+    );
+
+    // resume the collateral
+    let msg = ExecuteMsg::ResumeCollateralAsset {
+        asset: AssetInfo::Token {
+            contract_addr: "aUST".to_string(),
+        },
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let query_res =
+        query_collateral_price(deps.as_ref(), mock_env(), "aUST".to_string(), None).unwrap();
+    assert_eq!(
+        query_res,
+        CollateralPriceResponse {
+            asset: "aUST".to_string(),
+            base_denom: "uusd".to_string(),
+            rate: Decimal::one(),
+            last_updated: u64::MAX,
+            multiplier: Decimal::percent(100),
+            is_revoked: false,
+            is_suspended: false,
         }
     );
 }
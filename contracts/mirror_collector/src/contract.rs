@@ -1,20 +1,26 @@
 use crate::errors::ContractError;
 use crate::migration::migrate_config;
-use crate::state::{read_config, store_config, Config};
+use crate::state::{
+    read_config, read_tmp_mir_balance, store_config, store_tmp_mir_balance, Config,
+};
 use crate::swap::{convert, luna_swap_hook};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
-    WasmMsg,
+    attr, to_binary, Api, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Reply, Response,
+    StdError, StdResult, SubMsg, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 use mirror_protocol::collector::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    ConfigResponse, ConvertibleBalancesResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
 };
+use mirror_protocol::common::OwnerInfo;
 use mirror_protocol::gov::Cw20HookMsg::DepositReward;
 use terra_cosmwasm::TerraMsgWrapper;
-use terraswap::querier::query_token_balance;
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::querier::{query_balance, query_token_balance};
+
+const CONVERT_AND_DISTRIBUTE_REPLY_ID: u64 = 1;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -23,24 +29,35 @@ pub fn instantiate(
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
+    if msg.max_convert_assets == 0 {
+        return Err(StdError::generic_err(
+            "max_convert_assets must be greater than 0",
+        ));
+    }
+
     let mir_ust_pair = if let Some(mir_ust_pair) = msg.mir_ust_pair {
         Some(deps.api.addr_canonicalize(&mir_ust_pair)?)
     } else {
         None
     };
+    let mirror_token = deps.api.addr_canonicalize(&msg.mirror_token)?;
     store_config(
         deps.storage,
         &Config {
             owner: deps.api.addr_canonicalize(&msg.owner)?,
+            pending_owner: None,
             distribution_contract: deps.api.addr_canonicalize(&msg.distribution_contract)?,
             terraswap_factory: deps.api.addr_canonicalize(&msg.terraswap_factory)?,
-            mirror_token: deps.api.addr_canonicalize(&msg.mirror_token)?,
+            target_token: mirror_token.clone(),
+            mirror_token,
             base_denom: msg.base_denom,
             aust_token: deps.api.addr_canonicalize(&msg.aust_token)?,
             anchor_market: deps.api.addr_canonicalize(&msg.anchor_market)?,
             bluna_token: deps.api.addr_canonicalize(&msg.bluna_token)?,
             lunax_token: deps.api.addr_canonicalize(&msg.lunax_token)?,
             mir_ust_pair,
+            min_distribute_amount: msg.min_distribute_amount,
+            max_convert_assets: msg.max_convert_assets,
         },
     )?;
 
@@ -60,12 +77,15 @@ pub fn execute(
             distribution_contract,
             terraswap_factory,
             mirror_token,
+            target_token,
             base_denom,
             aust_token,
             anchor_market,
             bluna_token,
             mir_ust_pair,
             lunax_token,
+            min_distribute_amount,
+            max_convert_assets,
         } => update_config(
             deps,
             info,
@@ -73,18 +93,27 @@ pub fn execute(
             distribution_contract,
             terraswap_factory,
             mirror_token,
+            target_token,
             base_denom,
             aust_token,
             anchor_market,
             bluna_token,
             mir_ust_pair,
             lunax_token,
+            min_distribute_amount,
+            max_convert_assets,
         ),
+        ExecuteMsg::ProposeOwner { owner } => propose_owner(deps, info, owner),
+        ExecuteMsg::AcceptOwnership {} => accept_ownership(deps, info),
         ExecuteMsg::Convert { asset_token } => {
             let asset_addr = deps.api.addr_validate(&asset_token)?;
             convert(deps, env, asset_addr)
         }
         ExecuteMsg::Distribute {} => distribute(deps, env),
+        ExecuteMsg::ConvertAndDistribute {
+            assets,
+            start_after,
+        } => convert_and_distribute(deps, env, assets, start_after),
         ExecuteMsg::LunaSwapHook {} => luna_swap_hook(deps, env),
     }
 }
@@ -97,20 +126,24 @@ pub fn update_config(
     distribution_contract: Option<String>,
     terraswap_factory: Option<String>,
     mirror_token: Option<String>,
+    target_token: Option<String>,
     base_denom: Option<String>,
     aust_token: Option<String>,
     anchor_market: Option<String>,
     bluna_token: Option<String>,
     mir_ust_pair: Option<String>,
     lunax_token: Option<String>,
+    min_distribute_amount: Option<Uint128>,
+    max_convert_assets: Option<u32>,
 ) -> Result<Response<TerraMsgWrapper>, ContractError> {
     let mut config: Config = read_config(deps.storage)?;
     if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
         return Err(ContractError::Unauthorized {});
     }
 
+    // the new owner still has to accept the ownership
     if let Some(owner) = owner {
-        config.owner = deps.api.addr_canonicalize(&owner)?;
+        config.pending_owner = Some(deps.api.addr_canonicalize(&owner)?);
     }
 
     if let Some(distribution_contract) = distribution_contract {
@@ -125,6 +158,10 @@ pub fn update_config(
         config.mirror_token = deps.api.addr_canonicalize(&mirror_token)?;
     }
 
+    if let Some(target_token) = target_token {
+        config.target_token = deps.api.addr_canonicalize(&target_token)?;
+    }
+
     if let Some(base_denom) = base_denom {
         config.base_denom = base_denom;
     }
@@ -150,50 +187,254 @@ pub fn update_config(
         config.lunax_token = deps.api.addr_canonicalize(&lunax_token)?;
     }
 
+    if let Some(min_distribute_amount) = min_distribute_amount {
+        config.min_distribute_amount = min_distribute_amount;
+    }
+
+    if let Some(max_convert_assets) = max_convert_assets {
+        if max_convert_assets == 0 {
+            return Err(ContractError::InvalidMaxConvertAssets {});
+        }
+        config.max_convert_assets = max_convert_assets;
+    }
+
     store_config(deps.storage, &config)?;
 
     Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
 }
 
+pub fn propose_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let mut config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.pending_owner = Some(deps.api.addr_canonicalize(&owner)?);
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_owner"),
+        attr("pending_owner", owner),
+    ]))
+}
+
+pub fn accept_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let mut config: Config = read_config(deps.storage)?;
+    let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if config.pending_owner != Some(sender_raw.clone()) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.owner = sender_raw;
+    config.pending_owner = None;
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "accept_ownership"),
+        attr("owner", info.sender.as_str()),
+    ]))
+}
+
 // Anyone can execute send function to receive staking token rewards
 pub fn distribute(deps: DepsMut, env: Env) -> Result<Response<TerraMsgWrapper>, ContractError> {
     let config: Config = read_config(deps.storage)?;
     let amount = query_token_balance(
         &deps.querier,
-        deps.api.addr_humanize(&config.mirror_token)?,
+        deps.api.addr_humanize(&config.target_token)?,
         env.contract.address,
     )?;
 
+    // not worth the gas to distribute dust
+    if amount < config.min_distribute_amount {
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "distribute"),
+            attr("amount", amount.to_string()),
+            attr("skipped", "true"),
+        ]));
+    }
+
     Ok(Response::new()
-        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: deps.api.addr_humanize(&config.mirror_token)?.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Send {
-                contract: deps
-                    .api
-                    .addr_humanize(&config.distribution_contract)?
-                    .to_string(),
-                amount,
-                msg: to_binary(&DepositReward {})?,
-            })?,
-            funds: vec![],
-        })])
+        .add_message(distribute_msg(deps.api, &config, amount)?)
         .add_attributes(vec![
             attr("action", "distribute"),
             attr("amount", amount.to_string()),
         ]))
 }
 
+/// gov only accepts MIR as reward deposit, any other target token is transferred
+fn distribute_msg(
+    api: &dyn Api,
+    config: &Config,
+    amount: Uint128,
+) -> StdResult<CosmosMsg<TerraMsgWrapper>> {
+    let distribution_contract = api
+        .addr_humanize(&config.distribution_contract)?
+        .to_string();
+    let msg = if config.target_token == config.mirror_token {
+        Cw20ExecuteMsg::Send {
+            contract: distribution_contract,
+            amount,
+            msg: to_binary(&DepositReward {})?,
+        }
+    } else {
+        Cw20ExecuteMsg::Transfer {
+            recipient: distribution_contract,
+            amount,
+        }
+    };
+
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: api.addr_humanize(&config.target_token)?.to_string(),
+        msg: to_binary(&msg)?,
+        funds: vec![],
+    }))
+}
+
+/// ConvertAndDistribute
+/// Converts up to max_convert_assets of the listed assets following start_after,
+/// then swaps the collected base_denom for the target token in a submessage, whose reply
+/// distributes only the newly converted amount
+pub fn convert_and_distribute(
+    mut deps: DepsMut,
+    env: Env,
+    assets: Vec<AssetInfo>,
+    start_after: Option<AssetInfo>,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let target_token = deps.api.addr_humanize(&config.target_token)?;
+
+    let start = match start_after {
+        Some(start_after) => {
+            assets
+                .iter()
+                .position(|asset| *asset == start_after)
+                .ok_or(ContractError::InvalidStartAfter {})?
+                + 1
+        }
+        None => 0,
+    };
+    let end = assets.len().min(start + config.max_convert_assets as usize);
+
+    let mut messages: Vec<SubMsg<TerraMsgWrapper>> = vec![];
+    for asset in assets[start..end].iter() {
+        match asset {
+            AssetInfo::Token { contract_addr } => {
+                let asset_token = deps.api.addr_validate(contract_addr)?;
+                // collateral => target token swap is always executed at the end
+                if asset_token == target_token {
+                    continue;
+                }
+
+                let res = convert(deps.branch(), env.clone(), asset_token)?;
+                messages.extend(res.messages);
+            }
+            AssetInfo::NativeToken { denom } => {
+                if *denom != config.base_denom {
+                    return Err(ContractError::UnsupportedAsset(denom.to_string()));
+                }
+            }
+        }
+    }
+
+    // store the current target token balance to only distribute the converted amount
+    let target_balance = query_token_balance(
+        &deps.querier,
+        target_token.clone(),
+        env.contract.address.clone(),
+    )?;
+    store_tmp_mir_balance(deps.storage, target_balance)?;
+
+    messages.push(SubMsg::reply_on_success(
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_binary(&ExecuteMsg::Convert {
+                asset_token: target_token.to_string(),
+            })?,
+            funds: vec![],
+        }),
+        CONVERT_AND_DISTRIBUTE_REPLY_ID,
+    ));
+
+    let mut attributes = vec![attr("action", "convert_and_distribute")];
+    if end < assets.len() {
+        attributes.push(attr("next_start_after", assets[end - 1].to_string()));
+    }
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attributes(attributes))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(
+    deps: DepsMut,
+    env: Env,
+    msg: Reply,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    match msg.id {
+        CONVERT_AND_DISTRIBUTE_REPLY_ID => {
+            let config: Config = read_config(deps.storage)?;
+            let prev_balance = read_tmp_mir_balance(deps.storage)?;
+            let balance = query_token_balance(
+                &deps.querier,
+                deps.api.addr_humanize(&config.target_token)?,
+                env.contract.address,
+            )?;
+            let amount = balance.checked_sub(prev_balance)?;
+
+            let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = vec![];
+            if !amount.is_zero() {
+                messages.push(distribute_msg(deps.api, &config, amount)?);
+            }
+
+            Ok(Response::new().add_messages(messages).add_attributes(vec![
+                attr("action", "distribute"),
+                attr("amount", amount.to_string()),
+            ]))
+        }
+        _ => Err(StdError::generic_err("reply id is invalid").into()),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Owner {} => to_binary(&query_owner(deps)?),
+        QueryMsg::ConvertibleBalances { assets } => {
+            to_binary(&query_convertible_balances(deps, env, assets)?)
+        }
     }
 }
 
+pub fn query_owner(deps: Deps) -> StdResult<OwnerInfo> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(OwnerInfo {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        pending_owner: config
+            .pending_owner
+            .map(|pending_owner| deps.api.addr_humanize(&pending_owner))
+            .transpose()?
+            .map(|pending_owner| pending_owner.to_string()),
+    })
+}
+
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let state = read_config(deps.storage)?;
     let resp = ConfigResponse {
         owner: deps.api.addr_humanize(&state.owner)?.to_string(),
+        pending_owner: state
+            .pending_owner
+            .map(|pending_owner| deps.api.addr_humanize(&pending_owner))
+            .transpose()?
+            .map(|pending_owner| pending_owner.to_string()),
         distribution_contract: deps
             .api
             .addr_humanize(&state.distribution_contract)?
@@ -203,6 +444,7 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
             .addr_humanize(&state.terraswap_factory)?
             .to_string(),
         mirror_token: deps.api.addr_humanize(&state.mirror_token)?.to_string(),
+        target_token: deps.api.addr_humanize(&state.target_token)?.to_string(),
         base_denom: state.base_denom,
         aust_token: deps.api.addr_humanize(&state.aust_token)?.to_string(),
         anchor_market: deps.api.addr_humanize(&state.anchor_market)?.to_string(),
@@ -211,11 +453,77 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
             .mir_ust_pair
             .map(|raw| deps.api.addr_humanize(&raw).unwrap().to_string()),
         lunax_token: deps.api.addr_humanize(&state.lunax_token)?.to_string(),
+        min_distribute_amount: state.min_distribute_amount,
+        max_convert_assets: state.max_convert_assets,
     };
 
     Ok(resp)
 }
 
+/// Balances of base_denom, of the tokens set in the config and of the given assets;
+/// asset tokens are not tracked by the collector, so the caller lists them
+pub fn query_convertible_balances(
+    deps: Deps,
+    env: Env,
+    assets: Vec<AssetInfo>,
+) -> StdResult<ConvertibleBalancesResponse> {
+    let config: Config = read_config(deps.storage)?;
+
+    let mut balances = vec![Asset {
+        info: AssetInfo::NativeToken {
+            denom: config.base_denom.clone(),
+        },
+        amount: query_balance(
+            &deps.querier,
+            env.contract.address.clone(),
+            config.base_denom,
+        )?,
+    }];
+    for token in [
+        config.mirror_token,
+        config.aust_token,
+        config.bluna_token,
+        config.lunax_token,
+    ] {
+        let token = deps.api.addr_humanize(&token)?;
+        balances.push(Asset {
+            amount: query_token_balance(
+                &deps.querier,
+                token.clone(),
+                env.contract.address.clone(),
+            )?,
+            info: AssetInfo::Token {
+                contract_addr: token.to_string(),
+            },
+        });
+    }
+
+    for asset in assets {
+        if balances.iter().any(|balance| balance.info == asset) {
+            continue;
+        }
+
+        let amount = match &asset {
+            AssetInfo::Token { contract_addr } => query_token_balance(
+                &deps.querier,
+                deps.api.addr_validate(contract_addr)?,
+                env.contract.address.clone(),
+            )?,
+            AssetInfo::NativeToken { denom } => query_balance(
+                &deps.querier,
+                env.contract.address.clone(),
+                denom.to_string(),
+            )?,
+        };
+        balances.push(Asset {
+            info: asset,
+            amount,
+        });
+    }
+
+    Ok(ConvertibleBalancesResponse { balances })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
     migrate_config(
@@ -11,4 +11,13 @@ pub enum ContractError {
 
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("Asset can not be converted: {0}")]
+    UnsupportedAsset(String),
+
+    #[error("max_convert_assets must be greater than 0")]
+    InvalidMaxConvertAssets {},
+
+    #[error("start_after is not in the asset list")]
+    InvalidStartAfter {},
 }
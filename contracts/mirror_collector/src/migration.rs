@@ -1,10 +1,12 @@
-use cosmwasm_std::{CanonicalAddr, StdResult, Storage};
+use cosmwasm_std::{CanonicalAddr, StdResult, Storage, Uint128};
 use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::state::{Config, KEY_CONFIG};
 
+pub const DEFAULT_MAX_CONVERT_ASSETS: u32 = 10;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct LegacyConfig {
     pub owner: CanonicalAddr,
@@ -24,8 +26,10 @@ pub fn migrate_config(storage: &mut dyn Storage, lunax_token: CanonicalAddr) ->
     let legacy_config: LegacyConfig = legacy_store.load()?;
     let config = Config {
         owner: legacy_config.owner,
+        pending_owner: None,
         distribution_contract: legacy_config.distribution_contract,
         terraswap_factory: legacy_config.terraswap_factory,
+        target_token: legacy_config.mirror_token.clone(),
         mirror_token: legacy_config.mirror_token,
         base_denom: legacy_config.base_denom,
         aust_token: legacy_config.aust_token,
@@ -33,6 +37,8 @@ pub fn migrate_config(storage: &mut dyn Storage, lunax_token: CanonicalAddr) ->
         bluna_token: legacy_config.bluna_token,
         lunax_token,
         mir_ust_pair: legacy_config.mir_ust_pair,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: DEFAULT_MAX_CONVERT_ASSETS,
     };
     let mut store: Singleton<Config> = singleton(storage, KEY_CONFIG);
     store.save(&config)?;
@@ -80,15 +86,19 @@ mod migrate_tests {
             config,
             Config {
                 owner: deps.api.addr_canonicalize("owner0000").unwrap(),
+                pending_owner: None,
                 terraswap_factory: deps.api.addr_canonicalize("terraswapfactory").unwrap(),
                 distribution_contract: deps.api.addr_canonicalize("gov0000").unwrap(),
                 mirror_token: deps.api.addr_canonicalize("mirror0000").unwrap(),
+                target_token: deps.api.addr_canonicalize("mirror0000").unwrap(),
                 base_denom: "uusd".to_string(),
                 aust_token: deps.api.addr_canonicalize("aust0000").unwrap(),
                 anchor_market: deps.api.addr_canonicalize("anchormarket0000").unwrap(),
                 bluna_token: deps.api.addr_canonicalize("bluna0000").unwrap(),
                 lunax_token: CanonicalAddr::from("lunax_token".as_bytes()),
                 mir_ust_pair: Some(deps.api.addr_canonicalize("astromirustpair0000").unwrap()),
+                min_distribute_amount: Uint128::zero(),
+                max_convert_assets: DEFAULT_MAX_CONVERT_ASSETS,
             }
         )
     }
@@ -1,17 +1,21 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{CanonicalAddr, StdResult, Storage};
+use cosmwasm_std::{CanonicalAddr, StdResult, Storage, Uint128};
 use cosmwasm_storage::{singleton, singleton_read};
 
 pub static KEY_CONFIG: &[u8] = b"config";
+static KEY_TMP_MIR_BALANCE: &[u8] = b"tmp_mir_balance";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: CanonicalAddr,
+    pub pending_owner: Option<CanonicalAddr>,
     pub distribution_contract: CanonicalAddr, // collected rewards receiver
     pub terraswap_factory: CanonicalAddr,     // terraswap factory contract
     pub mirror_token: CanonicalAddr,
+    // converted and distributed token, only MIR is deposited as gov reward
+    pub target_token: CanonicalAddr,
     pub base_denom: String,
     // aUST params
     pub aust_token: CanonicalAddr,
@@ -22,6 +26,10 @@ pub struct Config {
     pub lunax_token: CanonicalAddr,
     // when set, use this address instead of querying from terraswap
     pub mir_ust_pair: Option<CanonicalAddr>,
+    // Distribute is skipped below this target token balance
+    pub min_distribute_amount: Uint128,
+    // ConvertAndDistribute converts at most this many assets per call
+    pub max_convert_assets: u32,
 }
 
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -31,3 +39,11 @@ pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()>
 pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
     singleton_read(storage, KEY_CONFIG).load()
 }
+
+pub fn store_tmp_mir_balance(storage: &mut dyn Storage, balance: Uint128) -> StdResult<()> {
+    singleton(storage, KEY_TMP_MIR_BALANCE).save(&balance)
+}
+
+pub fn read_tmp_mir_balance(storage: &dyn Storage) -> StdResult<Uint128> {
+    singleton_read(storage, KEY_TMP_MIR_BALANCE).load()
+}
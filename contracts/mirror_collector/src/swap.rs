@@ -12,7 +12,7 @@ use serde::{Deserialize, Serialize};
 use terra_cosmwasm::{create_swap_msg, TerraMsgWrapper};
 use terraswap::asset::{Asset, AssetInfo, PairInfo};
 use terraswap::pair::{Cw20HookMsg as TerraswapCw20HookMsg, ExecuteMsg as TerraswapExecuteMsg};
-use terraswap::querier::{query_balance, query_pair_info, query_token_balance};
+use terraswap::querier::{query_balance, query_pair_info, query_token_balance, simulate};
 
 const LUNA_DENOM: &str = "uluna";
 const AMM_MAX_ALLOWED_SLIPPAGE: &str = "0.5";
@@ -26,7 +26,7 @@ pub enum MoneyMarketCw20HookMsg {
 /// Convert
 /// Anyone can execute convert function to swap
 /// asset token => collateral token
-/// collateral token => MIR token
+/// collateral token => target token
 pub fn convert(
     deps: DepsMut,
     env: Env,
@@ -55,6 +55,31 @@ fn direct_swap(
     let terraswap_factory_addr = deps.api.addr_humanize(&config.terraswap_factory)?;
     let asset_token_raw = deps.api.addr_canonicalize(asset_token.as_str())?;
 
+    // collateral token => target token, otherwise asset token => collateral token
+    let offer_asset: Asset = if config.target_token == asset_token_raw {
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: config.base_denom.clone(),
+            },
+            amount: query_balance(
+                &deps.querier,
+                env.contract.address,
+                config.base_denom.clone(),
+            )?,
+        }
+    } else {
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: asset_token.to_string(),
+            },
+            amount: query_token_balance(&deps.querier, asset_token.clone(), env.contract.address)?,
+        }
+    };
+
+    if offer_asset.amount.is_zero() {
+        return Ok(skipped_convert("direct", &asset_token));
+    }
+
     let pair_addr: String =
         if asset_token_raw == config.mirror_token && config.mir_ust_pair.is_some() {
             deps.api
@@ -77,70 +102,81 @@ fn direct_swap(
             pair_info.contract_addr
         };
 
-    let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = vec![];
-    if config.mirror_token == asset_token_raw {
-        // collateral token => MIR token
-        let amount = query_balance(
-            &deps.querier,
-            env.contract.address,
-            config.base_denom.clone(),
-        )?;
-
-        if !amount.is_zero() {
-            let swap_asset = Asset {
-                info: AssetInfo::NativeToken {
-                    denom: config.base_denom.clone(),
-                },
-                amount,
-            };
-
-            // deduct tax first
-            let amount = (swap_asset.deduct_tax(&deps.querier)?).amount;
-            messages = vec![CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: pair_addr,
-                msg: to_binary(&TerraswapExecuteMsg::Swap {
-                    offer_asset: Asset {
-                        amount,
-                        ..swap_asset
-                    },
-                    max_spread: Some(Decimal::from_str(AMM_MAX_ALLOWED_SLIPPAGE)?), // currently need to set max_allowed_slippage for Astroport
-                    belief_price: None,
-                    to: None,
-                })?,
-                funds: vec![Coin {
-                    denom: config.base_denom.clone(),
-                    amount,
-                }],
-            })];
+    let offer_asset = if offer_asset.is_native_token() {
+        // deduct tax first
+        Asset {
+            amount: offer_asset.deduct_tax(&deps.querier)?.amount,
+            ..offer_asset
         }
     } else {
-        // asset token => collateral token
-        let amount = query_token_balance(&deps.querier, asset_token.clone(), env.contract.address)?;
+        offer_asset
+    };
 
-        if !amount.is_zero() {
-            messages = vec![CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: asset_token.to_string(),
-                msg: to_binary(&Cw20ExecuteMsg::Send {
-                    contract: pair_addr,
-                    amount,
-                    msg: to_binary(&TerraswapCw20HookMsg::Swap {
-                        max_spread: None, // currently all mAsset swaps are on terraswap, so we set max_spread to None
-                        belief_price: None,
-                        to: None,
-                    })?,
+    // expected return, to be compared with the actual swap result by keepers
+    let simulation = simulate(
+        &deps.querier,
+        Addr::unchecked(pair_addr.clone()),
+        &offer_asset,
+    )?;
+    let return_denom = if offer_asset.is_native_token() {
+        asset_token.to_string()
+    } else {
+        config.base_denom.clone()
+    };
+
+    let message = if offer_asset.is_native_token() {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: pair_addr.clone(),
+            msg: to_binary(&TerraswapExecuteMsg::Swap {
+                offer_asset: offer_asset.clone(),
+                max_spread: Some(Decimal::from_str(AMM_MAX_ALLOWED_SLIPPAGE)?), // currently need to set max_allowed_slippage for Astroport
+                belief_price: None,
+                to: None,
+            })?,
+            funds: vec![Coin {
+                denom: config.base_denom.clone(),
+                amount: offer_asset.amount,
+            }],
+        })
+    } else {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: asset_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: pair_addr.clone(),
+                amount: offer_asset.amount,
+                msg: to_binary(&TerraswapCw20HookMsg::Swap {
+                    max_spread: None, // currently all mAsset swaps are on terraswap, so we set max_spread to None
+                    belief_price: None,
+                    to: None,
                 })?,
-                funds: vec![],
-            })];
-        }
-    }
+            })?,
+            funds: vec![],
+        })
+    };
 
     Ok(Response::new()
         .add_attributes(vec![
             attr("action", "convert"),
             attr("swap_type", "direct"),
             attr("asset_token", asset_token.as_str()),
+            attr("offer_amount", offer_asset.to_string()),
+            attr(
+                "simulated_return_amount",
+                simulation.return_amount.to_string() + &return_denom,
+            ),
+            attr("pair", pair_addr),
         ])
-        .add_messages(messages))
+        .add_message(message))
+}
+
+/// Convert response for an asset the collector holds none of
+fn skipped_convert(swap_type: &str, asset_token: &Addr) -> Response<TerraMsgWrapper> {
+    Response::new().add_attributes(vec![
+        attr("action", "convert"),
+        attr("swap_type", swap_type),
+        attr("asset_token", asset_token.as_str()),
+        attr("skipped", "true"),
+    ])
 }
 
 fn anchor_redeem(
@@ -150,24 +186,25 @@ fn anchor_redeem(
     asset_token: Addr,
 ) -> Result<Response<TerraMsgWrapper>, ContractError> {
     let amount = query_token_balance(&deps.querier, asset_token.clone(), env.contract.address)?;
-
-    let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = vec![];
-    if !amount.is_zero() {
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: asset_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Send {
-                contract: deps.api.addr_humanize(&config.anchor_market)?.to_string(),
-                amount,
-                msg: to_binary(&MoneyMarketCw20HookMsg::RedeemStable {})?,
-            })?,
-            funds: vec![],
-        }));
+    if amount.is_zero() {
+        return Ok(skipped_convert("anchor_redeem", &asset_token));
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
+    let message = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: asset_token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: deps.api.addr_humanize(&config.anchor_market)?.to_string(),
+            amount,
+            msg: to_binary(&MoneyMarketCw20HookMsg::RedeemStable {})?,
+        })?,
+        funds: vec![],
+    });
+
+    Ok(Response::new().add_message(message).add_attributes(vec![
         attr("action", "convert"),
         attr("swap_type", "anchor_redeem"),
         attr("asset_token", asset_token.as_str()),
+        attr("offer_amount", amount.to_string() + asset_token.as_str()),
     ]))
 }
 
@@ -177,6 +214,15 @@ fn lunax_swap(
     config: &Config,
     asset_token: Addr,
 ) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let amount = query_token_balance(
+        &deps.querier,
+        asset_token.clone(),
+        env.contract.address.clone(),
+    )?;
+    if amount.is_zero() {
+        return Ok(skipped_convert("lunax_swap", &asset_token));
+    }
+
     let terraswap_factory_addr = deps.api.addr_humanize(&config.terraswap_factory)?;
 
     let pair_info: PairInfo = query_pair_info(
@@ -191,19 +237,13 @@ fn lunax_swap(
             },
         ],
     )?;
+    let pair_addr = pair_info.contract_addr;
 
-    let amount = query_token_balance(
-        &deps.querier,
-        asset_token.clone(),
-        env.contract.address.clone(),
-    )?;
-
-    let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = vec![];
-    if !amount.is_zero() {
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+    let messages: Vec<CosmosMsg<TerraMsgWrapper>> = vec![
+        CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: asset_token.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Send {
-                contract: pair_info.contract_addr,
+                contract: pair_addr.clone(),
                 amount,
                 msg: to_binary(&TerraswapCw20HookMsg::Swap {
                     max_spread: None,
@@ -212,18 +252,20 @@ fn lunax_swap(
                 })?,
             })?,
             funds: vec![],
-        }));
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        }),
+        CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: env.contract.address.to_string(),
             msg: to_binary(&ExecuteMsg::LunaSwapHook {})?,
             funds: vec![],
-        }));
-    }
+        }),
+    ];
 
     Ok(Response::new().add_messages(messages).add_attributes(vec![
         attr("action", "convert"),
         attr("swap_type", "lunax_swap"),
         attr("asset_token", asset_token.as_str()),
+        attr("offer_amount", amount.to_string() + asset_token.as_str()),
+        attr("pair", pair_addr),
     ]))
 }
 
@@ -233,6 +275,15 @@ fn bluna_swap(
     config: &Config,
     asset_token: Addr,
 ) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let amount = query_token_balance(
+        &deps.querier,
+        asset_token.clone(),
+        env.contract.address.clone(),
+    )?;
+    if amount.is_zero() {
+        return Ok(skipped_convert("bluna_swap", &asset_token));
+    }
+
     let terraswap_factory_addr = deps.api.addr_humanize(&config.terraswap_factory)?;
 
     let pair_info: PairInfo = query_pair_info(
@@ -247,19 +298,13 @@ fn bluna_swap(
             },
         ],
     )?;
+    let pair_addr = pair_info.contract_addr;
 
-    let amount = query_token_balance(
-        &deps.querier,
-        asset_token.clone(),
-        env.contract.address.clone(),
-    )?;
-
-    let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = vec![];
-    if !amount.is_zero() {
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+    let messages: Vec<CosmosMsg<TerraMsgWrapper>> = vec![
+        CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: asset_token.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Send {
-                contract: pair_info.contract_addr,
+                contract: pair_addr.clone(),
                 amount,
                 msg: to_binary(&TerraswapCw20HookMsg::Swap {
                     max_spread: None,
@@ -268,18 +313,20 @@ fn bluna_swap(
                 })?,
             })?,
             funds: vec![],
-        }));
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        }),
+        CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: env.contract.address.to_string(),
             msg: to_binary(&ExecuteMsg::LunaSwapHook {})?,
             funds: vec![],
-        }));
-    }
+        }),
+    ];
 
     Ok(Response::new().add_messages(messages).add_attributes(vec![
         attr("action", "convert"),
         attr("swap_type", "bluna_swap"),
         attr("asset_token", asset_token.as_str()),
+        attr("offer_amount", amount.to_string() + asset_token.as_str()),
+        attr("pair", pair_addr),
     ]))
 }
 
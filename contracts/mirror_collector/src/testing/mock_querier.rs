@@ -9,7 +9,8 @@ use cosmwasm_std::{
 use cw20::BalanceResponse;
 use std::collections::HashMap;
 use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
-use terraswap::asset::{AssetInfo, PairInfo};
+use terraswap::asset::{Asset, AssetInfo, PairInfo};
+use terraswap::pair::SimulationResponse;
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
 /// this uses our CustomQuerier.
@@ -128,6 +129,7 @@ impl Querier for WasmMockQuerier {
 pub enum QueryMsg {
     Pair { asset_infos: [AssetInfo; 2] },
     Balance { address: String },
+    Simulation { offer_asset: Asset },
 }
 
 impl WasmMockQuerier {
@@ -213,6 +215,14 @@ impl WasmMockQuerier {
                         to_binary(&BalanceResponse { balance }).unwrap(),
                     ))
                 }
+                // every pair swaps at a 1:1 price
+                QueryMsg::Simulation { offer_asset } => {
+                    SystemResult::Ok(ContractResult::from(to_binary(&SimulationResponse {
+                        return_amount: offer_asset.amount,
+                        spread_amount: Uint128::zero(),
+                        commission_amount: Uint128::zero(),
+                    })))
+                }
             },
             _ => self.base.handle_query(request),
         }
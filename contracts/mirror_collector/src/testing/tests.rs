@@ -1,10 +1,16 @@
-use crate::contract::{execute, instantiate, query_config};
+use crate::contract::{execute, instantiate, query_config, query_convertible_balances, reply};
+use crate::errors::ContractError;
 use crate::swap::MoneyMarketCw20HookMsg;
 use crate::testing::mock_querier::mock_dependencies;
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{to_binary, Coin, CosmosMsg, Decimal, SubMsg, Uint128, WasmMsg};
+use cosmwasm_std::{
+    attr, to_binary, Coin, ContractResult, CosmosMsg, Decimal, Reply, SubMsg,
+    SubMsgExecutionResponse, Uint128, WasmMsg,
+};
 use cw20::Cw20ExecuteMsg;
-use mirror_protocol::collector::{ConfigResponse, ExecuteMsg, InstantiateMsg};
+use mirror_protocol::collector::{
+    ConfigResponse, ConvertibleBalancesResponse, ExecuteMsg, InstantiateMsg,
+};
 use mirror_protocol::gov::Cw20HookMsg::DepositReward;
 use terra_cosmwasm::{TerraMsg, TerraMsgWrapper, TerraRoute};
 use terraswap::asset::{Asset, AssetInfo};
@@ -25,6 +31,8 @@ fn proper_initialization() {
         bluna_token: "bluna0000".to_string(),
         lunax_token: "lunax0000".to_string(),
         mir_ust_pair: None,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: 10,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -38,6 +46,95 @@ fn proper_initialization() {
     assert_eq!("uusd", config.base_denom.as_str());
 }
 
+#[test]
+fn test_transfer_ownership() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        terraswap_factory: "terraswapfactory".to_string(),
+        distribution_contract: "gov0000".to_string(),
+        mirror_token: "mirror0000".to_string(),
+        base_denom: "uusd".to_string(),
+        aust_token: "aust0000".to_string(),
+        anchor_market: "anchormarket0000".to_string(),
+        bluna_token: "bluna0000".to_string(),
+        lunax_token: "lunax0000".to_string(),
+        mir_ust_pair: None,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: 10,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // only the owner can propose
+    let msg = ExecuteMsg::ProposeOwner {
+        owner: "owner0001".to_string(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "propose_owner"),
+            attr("pending_owner", "owner0001"),
+        ]
+    );
+
+    // the owner does not change until the proposal is accepted
+    let config: ConfigResponse = query_config(deps.as_ref()).unwrap();
+    assert_eq!("owner0000", config.owner.as_str());
+    assert_eq!(Some("owner0001".to_string()), config.pending_owner);
+
+    // only the pending owner can accept
+    let msg = ExecuteMsg::AcceptOwnership {};
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0001", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "accept_ownership"),
+            attr("owner", "owner0001"),
+        ]
+    );
+
+    let config: ConfigResponse = query_config(deps.as_ref()).unwrap();
+    assert_eq!("owner0001", config.owner.as_str());
+    assert_eq!(None, config.pending_owner);
+
+    // an owner given to UpdateConfig is only proposed as well
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: Some("owner0002".to_string()),
+        terraswap_factory: None,
+        distribution_contract: None,
+        mirror_token: None,
+        target_token: None,
+        base_denom: None,
+        aust_token: None,
+        anchor_market: None,
+        bluna_token: None,
+        mir_ust_pair: None,
+        lunax_token: None,
+        min_distribute_amount: None,
+        max_convert_assets: None,
+    };
+    let info = mock_info("owner0001", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let config: ConfigResponse = query_config(deps.as_ref()).unwrap();
+    assert_eq!("owner0001", config.owner.as_str());
+    assert_eq!(Some("owner0002".to_string()), config.pending_owner);
+}
+
 #[test]
 fn test_convert() {
     let mut deps = mock_dependencies(&[Coin {
@@ -70,6 +167,8 @@ fn test_convert() {
         bluna_token: "bluna0000".to_string(),
         lunax_token: "lunax0000".to_string(),
         mir_ust_pair: None,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: 10,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -100,6 +199,17 @@ fn test_convert() {
             funds: vec![],
         }))]
     );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "convert"),
+            attr("swap_type", "direct"),
+            attr("asset_token", "tokenAPPL"),
+            attr("offer_amount", "100tokenAPPL"),
+            attr("simulated_return_amount", "100uusd"),
+            attr("pair", "pairAPPL"),
+        ]
+    );
 
     let msg = ExecuteMsg::Convert {
         asset_token: "tokenMIRROR".to_string(),
@@ -131,6 +241,17 @@ fn test_convert() {
             }],
         }))]
     );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "convert"),
+            attr("swap_type", "direct"),
+            attr("asset_token", "tokenMIRROR"),
+            attr("offer_amount", "99uusd"),
+            attr("simulated_return_amount", "99tokenMIRROR"),
+            attr("pair", "pairMIRROR"),
+        ]
+    );
 }
 
 #[test]
@@ -155,6 +276,8 @@ fn test_convert_aust() {
         bluna_token: "bluna0000".to_string(),
         lunax_token: "lunax0000".to_string(),
         mir_ust_pair: None,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: 10,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -181,6 +304,62 @@ fn test_convert_aust() {
     );
 }
 
+#[test]
+fn test_convert_zero_balance() {
+    let mut deps = mock_dependencies(&[]);
+    let zero_balance = &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())];
+    deps.querier.with_token_balances(&[
+        (&"tokenAPPL".to_string(), zero_balance),
+        (&"aust0000".to_string(), zero_balance),
+        (&"bluna0000".to_string(), zero_balance),
+        (&"lunax0000".to_string(), zero_balance),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        terraswap_factory: "terraswapfactory".to_string(),
+        distribution_contract: "gov0000".to_string(),
+        mirror_token: "tokenMIRROR".to_string(),
+        base_denom: "uusd".to_string(),
+        aust_token: "aust0000".to_string(),
+        anchor_market: "anchormarket0000".to_string(),
+        bluna_token: "bluna0000".to_string(),
+        lunax_token: "lunax0000".to_string(),
+        mir_ust_pair: None,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: 10,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // no balance and no registered pair, convert is skipped
+    for (asset_token, swap_type) in [
+        ("tokenAPPL", "direct"),
+        ("tokenMIRROR", "direct"),
+        ("aust0000", "anchor_redeem"),
+        ("bluna0000", "bluna_swap"),
+        ("lunax0000", "lunax_swap"),
+    ] {
+        let msg = ExecuteMsg::Convert {
+            asset_token: asset_token.to_string(),
+        };
+
+        let info = mock_info("addr0000", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "convert"),
+                attr("swap_type", swap_type),
+                attr("asset_token", asset_token),
+                attr("skipped", "true"),
+            ]
+        );
+    }
+}
+
 #[test]
 fn test_convert_lunax() {
     let mut deps = mock_dependencies(&[Coin {
@@ -206,6 +385,8 @@ fn test_convert_lunax() {
         bluna_token: "bluna0000".to_string(),
         lunax_token: "lunax0000".to_string(),
         mir_ust_pair: None,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: 10,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -287,6 +468,8 @@ fn test_convert_bluna() {
         bluna_token: "bluna0000".to_string(),
         lunax_token: "lunax0000".to_string(),
         mir_ust_pair: None,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: 10,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -362,6 +545,8 @@ fn test_send() {
         bluna_token: "bluna0000".to_string(),
         lunax_token: "lunax0000".to_string(),
         mir_ust_pair: None,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: 10,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -386,6 +571,362 @@ fn test_send() {
     )
 }
 
+#[test]
+fn test_send_min_distribute_amount() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(
+        &"mirror0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        terraswap_factory: "terraswapfactory".to_string(),
+        distribution_contract: "gov0000".to_string(),
+        mirror_token: "mirror0000".to_string(),
+        base_denom: "uusd".to_string(),
+        aust_token: "aust0000".to_string(),
+        anchor_market: "anchormarket0000".to_string(),
+        bluna_token: "bluna0000".to_string(),
+        lunax_token: "lunax0000".to_string(),
+        mir_ust_pair: None,
+        min_distribute_amount: Uint128::from(101u128),
+        max_convert_assets: 10,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // below the floor, nothing is distributed
+    let msg = ExecuteMsg::Distribute {};
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "distribute"),
+            attr("amount", "100"),
+            attr("skipped", "true"),
+        ]
+    );
+
+    deps.querier.with_token_balances(&[(
+        &"mirror0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(101u128))],
+    )]);
+
+    let msg = ExecuteMsg::Distribute {};
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "mirror0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "gov0000".to_string(),
+                amount: Uint128::from(101u128),
+                msg: to_binary(&DepositReward {}).unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn test_convert_and_distribute() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(100u128),
+    }]);
+    deps.querier.with_token_balances(&[
+        (
+            &"tokenAPPL".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+        ),
+        (
+            &"tokenMIRROR".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(10u128))],
+        ),
+    ]);
+    deps.querier
+        .with_terraswap_pairs(&[(&"uusdtokenAPPL".to_string(), &"pairAPPL".to_string())]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        terraswap_factory: "terraswapfactory".to_string(),
+        distribution_contract: "gov0000".to_string(),
+        mirror_token: "tokenMIRROR".to_string(),
+        base_denom: "uusd".to_string(),
+        aust_token: "aust0000".to_string(),
+        anchor_market: "anchormarket0000".to_string(),
+        bluna_token: "bluna0000".to_string(),
+        lunax_token: "lunax0000".to_string(),
+        mir_ust_pair: None,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: 10,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::ConvertAndDistribute {
+        assets: vec![
+            AssetInfo::Token {
+                contract_addr: "tokenAPPL".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        ],
+        start_after: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "tokenAPPL".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "pairAPPL".to_string(),
+                    amount: Uint128::from(100u128),
+                    msg: to_binary(&TerraswapCw20HookMsg::Swap {
+                        max_spread: None,
+                        belief_price: None,
+                        to: None,
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::reply_on_success(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                    msg: to_binary(&ExecuteMsg::Convert {
+                        asset_token: "tokenMIRROR".to_string(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                1,
+            ),
+        ]
+    );
+
+    // unsupported native asset
+    let msg = ExecuteMsg::ConvertAndDistribute {
+        assets: vec![AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        }],
+        start_after: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::UnsupportedAsset("ukrw".to_string()));
+
+    // suppose the conversion yields 50 MIR
+    deps.querier.with_token_balances(&[(
+        &"tokenMIRROR".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(60u128))],
+    )]);
+
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "tokenMIRROR".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "gov0000".to_string(),
+                amount: Uint128::from(50u128),
+                msg: to_binary(&DepositReward {}).unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn test_convert_and_distribute_pages() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[
+        (
+            &"tokenAPPL".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+        ),
+        (
+            &"tokenGOOG".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(200u128))],
+        ),
+        (
+            &"tokenMSFT".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(300u128))],
+        ),
+        (
+            &"tokenMIRROR".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+        ),
+    ]);
+    deps.querier.with_terraswap_pairs(&[
+        (&"uusdtokenAPPL".to_string(), &"pairAPPL".to_string()),
+        (&"uusdtokenGOOG".to_string(), &"pairGOOG".to_string()),
+        (&"uusdtokenMSFT".to_string(), &"pairMSFT".to_string()),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        terraswap_factory: "terraswapfactory".to_string(),
+        distribution_contract: "gov0000".to_string(),
+        mirror_token: "tokenMIRROR".to_string(),
+        base_denom: "uusd".to_string(),
+        aust_token: "aust0000".to_string(),
+        anchor_market: "anchormarket0000".to_string(),
+        bluna_token: "bluna0000".to_string(),
+        lunax_token: "lunax0000".to_string(),
+        mir_ust_pair: None,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: 10,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        terraswap_factory: None,
+        distribution_contract: None,
+        mirror_token: None,
+        target_token: None,
+        base_denom: None,
+        aust_token: None,
+        anchor_market: None,
+        bluna_token: None,
+        mir_ust_pair: None,
+        lunax_token: None,
+        min_distribute_amount: None,
+        max_convert_assets: Some(0),
+    };
+    let info = mock_info("owner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidMaxConvertAssets {});
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        terraswap_factory: None,
+        distribution_contract: None,
+        mirror_token: None,
+        target_token: None,
+        base_denom: None,
+        aust_token: None,
+        anchor_market: None,
+        bluna_token: None,
+        mir_ust_pair: None,
+        lunax_token: None,
+        min_distribute_amount: None,
+        max_convert_assets: Some(2),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let assets = vec![
+        AssetInfo::Token {
+            contract_addr: "tokenAPPL".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "tokenGOOG".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "tokenMSFT".to_string(),
+        },
+    ];
+    let swap_msg = |asset_token: &str, pair: &str, amount: u128| {
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: asset_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: pair.to_string(),
+                amount: Uint128::from(amount),
+                msg: to_binary(&TerraswapCw20HookMsg::Swap {
+                    max_spread: None,
+                    belief_price: None,
+                    to: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))
+    };
+    let distribute_msg = SubMsg::reply_on_success(
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&ExecuteMsg::Convert {
+                asset_token: "tokenMIRROR".to_string(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }),
+        1,
+    );
+
+    // first page
+    let msg = ExecuteMsg::ConvertAndDistribute {
+        assets: assets.clone(),
+        start_after: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            swap_msg("tokenAPPL", "pairAPPL", 100),
+            swap_msg("tokenGOOG", "pairGOOG", 200),
+            distribute_msg.clone(),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "convert_and_distribute"),
+            attr("next_start_after", "tokenGOOG"),
+        ]
+    );
+
+    // second page
+    let msg = ExecuteMsg::ConvertAndDistribute {
+        assets: assets.clone(),
+        start_after: Some(AssetInfo::Token {
+            contract_addr: "tokenGOOG".to_string(),
+        }),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![swap_msg("tokenMSFT", "pairMSFT", 300), distribute_msg]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "convert_and_distribute")]
+    );
+
+    let msg = ExecuteMsg::ConvertAndDistribute {
+        assets,
+        start_after: Some(AssetInfo::Token {
+            contract_addr: "tokenMIRROR".to_string(),
+        }),
+    };
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidStartAfter {});
+}
+
 #[test]
 fn test_set_astroport_mir_pair() {
     let mut deps = mock_dependencies(&[Coin {
@@ -412,6 +953,8 @@ fn test_set_astroport_mir_pair() {
         bluna_token: "bluna0000".to_string(),
         lunax_token: "lunax0000".to_string(),
         mir_ust_pair: None,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: 10,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -455,12 +998,15 @@ fn test_set_astroport_mir_pair() {
         terraswap_factory: None,
         distribution_contract: None,
         mirror_token: None,
+        target_token: None,
         base_denom: None,
         aust_token: None,
         anchor_market: None,
         bluna_token: None,
         mir_ust_pair: Some("astroportPAIR".to_string()),
         lunax_token: None,
+        min_distribute_amount: None,
+        max_convert_assets: None,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -499,3 +1045,178 @@ fn test_set_astroport_mir_pair() {
         }))]
     );
 }
+
+#[test]
+fn test_query_convertible_balances() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(100u128),
+    }]);
+    deps.querier.with_token_balances(&[
+        (
+            &"aust0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(200u128))],
+        ),
+        (&"mirror0000".to_string(), &[]),
+        (&"bluna0000".to_string(), &[]),
+        (&"lunax0000".to_string(), &[]),
+        (
+            &"tokenAPPL".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(300u128))],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        terraswap_factory: "terraswapfactory".to_string(),
+        distribution_contract: "gov0000".to_string(),
+        mirror_token: "mirror0000".to_string(),
+        base_denom: "uusd".to_string(),
+        aust_token: "aust0000".to_string(),
+        anchor_market: "anchormarket0000".to_string(),
+        bluna_token: "bluna0000".to_string(),
+        lunax_token: "lunax0000".to_string(),
+        mir_ust_pair: None,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: 10,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let token = |contract_addr: &str, amount: u128| Asset {
+        info: AssetInfo::Token {
+            contract_addr: contract_addr.to_string(),
+        },
+        amount: Uint128::from(amount),
+    };
+    // listed assets already in the config are not repeated
+    let res: ConvertibleBalancesResponse = query_convertible_balances(
+        deps.as_ref(),
+        mock_env(),
+        vec![
+            AssetInfo::Token {
+                contract_addr: "tokenAPPL".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "aust0000".to_string(),
+            },
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        res.balances,
+        vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(100u128),
+            },
+            token("mirror0000", 0),
+            token("aust0000", 200),
+            token("bluna0000", 0),
+            token("lunax0000", 0),
+            token("tokenAPPL", 300),
+        ]
+    );
+}
+
+#[test]
+fn test_convert_to_other_target() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(100u128),
+    }]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier.with_token_balances(&[(
+        &"tokenOTHER".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(50u128))],
+    )]);
+    deps.querier
+        .with_terraswap_pairs(&[(&"uusdtokenOTHER".to_string(), &"pairOTHER".to_string())]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        terraswap_factory: "terraswapfactory".to_string(),
+        distribution_contract: "gov0000".to_string(),
+        mirror_token: "mirror0000".to_string(),
+        base_denom: "uusd".to_string(),
+        aust_token: "aust0000".to_string(),
+        anchor_market: "anchormarket0000".to_string(),
+        bluna_token: "bluna0000".to_string(),
+        lunax_token: "lunax0000".to_string(),
+        mir_ust_pair: None,
+        min_distribute_amount: Uint128::zero(),
+        max_convert_assets: 10,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // accumulate another token instead of MIR
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        terraswap_factory: None,
+        distribution_contract: None,
+        mirror_token: None,
+        target_token: Some("tokenOTHER".to_string()),
+        base_denom: None,
+        aust_token: None,
+        anchor_market: None,
+        bluna_token: None,
+        mir_ust_pair: None,
+        lunax_token: None,
+        min_distribute_amount: None,
+        max_convert_assets: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // base_denom is swapped for the target token
+    let msg = ExecuteMsg::Convert {
+        asset_token: "tokenOTHER".to_string(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "pairOTHER".to_string(),
+            msg: to_binary(&TerraswapExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string()
+                    },
+                    amount: Uint128::from(99u128),
+                },
+                max_spread: Some(Decimal::percent(50)),
+                belief_price: None,
+                to: None,
+            })
+            .unwrap(),
+            funds: vec![Coin {
+                amount: Uint128::from(99u128),
+                denom: "uusd".to_string(),
+            }],
+        }))]
+    );
+
+    // and the target token is transferred, as gov only takes MIR reward deposits
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Distribute {}).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "tokenOTHER".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "gov0000".to_string(),
+                amount: Uint128::from(50u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
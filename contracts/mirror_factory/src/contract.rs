@@ -2,10 +2,12 @@
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    attr, to_binary, Addr, Attribute, Binary, CanonicalAddr, CosmosMsg, Decimal, Deps, DepsMut,
-    Env, MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+    attr, to_binary, Addr, Attribute, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps,
+    DepsMut, Env, MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, SubMsg, Uint128,
+    WasmMsg,
 };
 
+use crate::migration::migrate_config;
 use crate::querier::{load_mint_asset_config, query_last_price};
 use crate::response::MsgInstantiateContractResponse;
 use crate::state::{
@@ -17,8 +19,8 @@ use crate::state::{
 };
 
 use mirror_protocol::factory::{
-    ConfigResponse, DistributionInfoResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, Params,
-    QueryMsg,
+    ConfigResponse, DistributionInfoResponse, DistributionScheduleResponse, ExecuteMsg,
+    InstantiateMsg, MigrateMsg, Params, QueryMsg,
 };
 use mirror_protocol::mint::{ExecuteMsg as MintExecuteMsg, IPOParams};
 use mirror_protocol::staking::Cw20HookMsg as StakingCw20HookMsg;
@@ -28,8 +30,9 @@ use tefi_oracle::hub::HubExecuteMsg as TeFiOracleExecuteMsg;
 use protobuf::Message;
 
 use cw20::{Cw20ExecuteMsg, MinterResponse};
-use terraswap::asset::{AssetInfo, PairInfo};
+use terraswap::asset::{Asset, AssetInfo, PairInfo};
 use terraswap::factory::ExecuteMsg as TerraswapFactoryExecuteMsg;
+use terraswap::pair::ExecuteMsg as TerraswapPairExecuteMsg;
 use terraswap::querier::query_pair_info;
 use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
 
@@ -44,10 +47,12 @@ pub fn instantiate(
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
+    assert_distribution_schedule(&msg.distribution_schedule)?;
     store_config(
         deps.storage,
         &Config {
             owner: CanonicalAddr::from(vec![]),
+            pending_owner: None,
             mirror_token: CanonicalAddr::from(vec![]),
             mint_contract: CanonicalAddr::from(vec![]),
             oracle_contract: CanonicalAddr::from(vec![]),
@@ -93,7 +98,20 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             owner,
             token_code_id,
             distribution_schedule,
-        } => update_config(deps, info, owner, token_code_id, distribution_schedule),
+            genesis_time,
+        } => update_config(
+            deps,
+            info,
+            owner,
+            token_code_id,
+            distribution_schedule,
+            genesis_time,
+        ),
+        ExecuteMsg::ProposeOwner { owner } => propose_owner(deps, info, owner),
+        ExecuteMsg::AcceptOwnership {} => accept_ownership(deps, info),
+        ExecuteMsg::UpdateScheduleTranche { index, schedule } => {
+            update_schedule_tranche(deps, info, index, schedule)
+        }
         ExecuteMsg::UpdateWeight {
             asset_token,
             weight,
@@ -109,6 +127,10 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             pass_command(deps, info, contract_addr, msg)
         }
         ExecuteMsg::RevokeAsset { asset_token } => revoke_asset(deps, info, asset_token),
+        ExecuteMsg::ProvideProtocolLiquidity {
+            asset_token,
+            amounts,
+        } => provide_protocol_liquidity(deps, info, asset_token, amounts),
         ExecuteMsg::MigrateAsset {
             name,
             symbol,
@@ -160,20 +182,35 @@ pub fn update_config(
     owner: Option<String>,
     token_code_id: Option<u64>,
     distribution_schedule: Option<Vec<(u64, u64, Uint128)>>,
+    genesis_time: Option<u64>,
 ) -> StdResult<Response> {
     let mut config: Config = read_config(deps.storage)?;
     if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
         return Err(StdError::generic_err("unauthorized"));
     }
 
+    // the new owner still has to accept the ownership
     if let Some(owner) = owner {
-        config.owner = deps.api.addr_canonicalize(&owner)?;
+        config.pending_owner = Some(deps.api.addr_canonicalize(&owner)?);
     }
 
     if let Some(distribution_schedule) = distribution_schedule {
+        assert_distribution_schedule(&distribution_schedule)?;
         config.distribution_schedule = distribution_schedule;
     }
 
+    if let Some(genesis_time) = genesis_time {
+        // the distribution math measures the elapsed time from genesis
+        let last_distributed = read_last_distributed(deps.storage)?;
+        if genesis_time > last_distributed {
+            return Err(StdError::generic_err(format!(
+                "genesis_time can not be after the last distribution time {}",
+                last_distributed
+            )));
+        }
+        config.genesis_time = genesis_time;
+    }
+
     if let Some(token_code_id) = token_code_id {
         config.token_code_id = token_code_id;
     }
@@ -183,6 +220,109 @@ pub fn update_config(
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
+pub fn propose_owner(deps: DepsMut, info: MessageInfo, owner: String) -> StdResult<Response> {
+    let mut config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    config.pending_owner = Some(deps.api.addr_canonicalize(&owner)?);
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_owner"),
+        attr("pending_owner", owner),
+    ]))
+}
+
+pub fn accept_ownership(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let mut config: Config = read_config(deps.storage)?;
+    let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if config.pending_owner != Some(sender_raw.clone()) {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    config.owner = sender_raw;
+    config.pending_owner = None;
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "accept_ownership"),
+        attr("owner", info.sender.as_str()),
+    ]))
+}
+
+/// Tranches must be sorted and must not overlap
+fn assert_distribution_schedule(distribution_schedule: &[(u64, u64, Uint128)]) -> StdResult<()> {
+    for (index, schedule) in distribution_schedule.iter().enumerate() {
+        if schedule.0 >= schedule.1 {
+            return Err(StdError::generic_err(format!(
+                "tranche {} start_time must be before end_time",
+                index
+            )));
+        }
+
+        if index > 0 && distribution_schedule[index - 1].1 > schedule.0 {
+            return Err(StdError::generic_err(format!(
+                "tranche {} overlaps with tranche {}",
+                index,
+                index - 1
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn update_schedule_tranche(
+    deps: DepsMut,
+    info: MessageInfo,
+    index: u32,
+    schedule: (u64, u64, Uint128),
+) -> StdResult<Response> {
+    let mut config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let index = index as usize;
+    if index >= config.distribution_schedule.len() {
+        return Err(StdError::generic_err("tranche index out of range"));
+    }
+
+    if schedule.0 >= schedule.1 {
+        return Err(StdError::generic_err(
+            "tranche start_time must be before end_time",
+        ));
+    }
+
+    // must not overlap with the neighbor tranches
+    if index > 0 && config.distribution_schedule[index - 1].1 > schedule.0 {
+        return Err(StdError::generic_err(
+            "tranche overlaps with the previous tranche",
+        ));
+    }
+
+    if index + 1 < config.distribution_schedule.len()
+        && config.distribution_schedule[index + 1].0 < schedule.1
+    {
+        return Err(StdError::generic_err(
+            "tranche overlaps with the next tranche",
+        ));
+    }
+
+    config.distribution_schedule[index] = schedule;
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_schedule_tranche"),
+        attr("index", index.to_string()),
+        attr("start_time", schedule.0.to_string()),
+        attr("end_time", schedule.1.to_string()),
+        attr("distribution_amount", schedule.2.to_string()),
+    ]))
+}
+
 pub fn update_weight(
     deps: DepsMut,
     info: MessageInfo,
@@ -632,6 +772,84 @@ pub fn revoke_asset(deps: DepsMut, info: MessageInfo, asset_token: String) -> St
         ]))
 }
 
+pub fn provide_protocol_liquidity(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_token: String,
+    amounts: (Uint128, Uint128),
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let (asset_amount, base_amount) = amounts;
+    if asset_amount.is_zero() || base_amount.is_zero() {
+        return Err(StdError::generic_err("liquidity amounts must not be zero"));
+    }
+
+    let asset_addr = deps.api.addr_validate(&asset_token)?;
+    let pair_info: PairInfo = query_pair_info(
+        &deps.querier,
+        deps.api.addr_humanize(&config.terraswap_factory)?,
+        &[
+            AssetInfo::NativeToken {
+                denom: config.base_denom.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: asset_addr.to_string(),
+            },
+        ],
+    )?;
+
+    // allow the pair to pull the asset tokens, then provide with the base denom attached
+    let messages: Vec<SubMsg> = vec![
+        SubMsg::new(WasmMsg::Execute {
+            contract_addr: asset_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pair_info.contract_addr.clone(),
+                amount: asset_amount,
+                expires: None,
+            })?,
+        }),
+        SubMsg::new(WasmMsg::Execute {
+            contract_addr: pair_info.contract_addr,
+            funds: vec![Coin {
+                denom: config.base_denom.clone(),
+                amount: base_amount,
+            }],
+            msg: to_binary(&TerraswapPairExecuteMsg::ProvideLiquidity {
+                assets: [
+                    Asset {
+                        info: AssetInfo::Token {
+                            contract_addr: asset_addr.to_string(),
+                        },
+                        amount: asset_amount,
+                    },
+                    Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: config.base_denom,
+                        },
+                        amount: base_amount,
+                    },
+                ],
+                slippage_tolerance: None,
+                receiver: None,
+            })?,
+        }),
+    ];
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attributes(vec![
+            attr("action", "provide_protocol_liquidity"),
+            attr("asset_token", asset_token),
+            attr("asset_amount", asset_amount.to_string()),
+            attr("base_amount", base_amount.to_string()),
+        ]))
+}
+
 pub fn migrate_asset(
     deps: DepsMut,
     info: MessageInfo,
@@ -726,10 +944,11 @@ pub fn migrate_asset(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::DistributionInfo {} => to_binary(&query_distribution_info(deps)?),
+        QueryMsg::DistributionSchedule {} => to_binary(&query_distribution_schedule(deps, env)?),
     }
 }
 
@@ -737,6 +956,11 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let state = read_config(deps.storage)?;
     let resp = ConfigResponse {
         owner: deps.api.addr_humanize(&state.owner)?.to_string(),
+        pending_owner: state
+            .pending_owner
+            .map(|pending_owner| deps.api.addr_humanize(&pending_owner))
+            .transpose()?
+            .map(|pending_owner| pending_owner.to_string()),
         mirror_token: deps.api.addr_humanize(&state.mirror_token)?.to_string(),
         mint_contract: deps.api.addr_humanize(&state.mint_contract)?.to_string(),
         oracle_contract: deps.api.addr_humanize(&state.oracle_contract)?.to_string(),
@@ -772,12 +996,40 @@ pub fn query_distribution_info(deps: Deps) -> StdResult<DistributionInfoResponse
     Ok(resp)
 }
 
+pub fn query_distribution_schedule(
+    deps: Deps,
+    env: Env,
+) -> StdResult<DistributionScheduleResponse> {
+    let config: Config = read_config(deps.storage)?;
+
+    // schedule times are relative to genesis_time, no tranche is active before it
+    let active_schedule = env
+        .block
+        .time
+        .seconds()
+        .checked_sub(config.genesis_time)
+        .and_then(|time_elapsed| {
+            config
+                .distribution_schedule
+                .iter()
+                .find(|s| s.0 <= time_elapsed && time_elapsed < s.1)
+                .cloned()
+        });
+
+    Ok(DistributionScheduleResponse {
+        genesis_time: config.genesis_time,
+        distribution_schedule: config.distribution_schedule,
+        active_schedule,
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
     // change oracle address to point to new tefi hub
-    let mut config: Config = read_config(deps.storage)?;
-    config.oracle_contract = deps.api.addr_canonicalize(&msg.tefi_oracle_contract)?;
-    store_config(deps.storage, &config)?;
+    migrate_config(
+        deps.storage,
+        deps.api.addr_canonicalize(&msg.tefi_oracle_contract)?,
+    )?;
 
     Ok(Response::default())
 }
@@ -1,5 +1,6 @@
 pub mod contract;
 pub mod math;
+mod migration;
 pub mod querier;
 mod response;
 pub mod state;
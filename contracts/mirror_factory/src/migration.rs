@@ -0,0 +1,83 @@
+use cosmwasm_std::{CanonicalAddr, StdResult, Storage, Uint128};
+use cosmwasm_storage::{singleton_read, ReadonlySingleton};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::{store_config, Config, KEY_CONFIG};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub owner: CanonicalAddr,
+    pub mirror_token: CanonicalAddr,
+    pub mint_contract: CanonicalAddr,
+    pub oracle_contract: CanonicalAddr,
+    pub terraswap_factory: CanonicalAddr,
+    pub staking_contract: CanonicalAddr,
+    pub commission_collector: CanonicalAddr,
+    pub token_code_id: u64,
+    pub base_denom: String,
+    pub genesis_time: u64,
+    pub distribution_schedule: Vec<(u64, u64, Uint128)>,
+}
+
+pub fn migrate_config(storage: &mut dyn Storage, oracle_contract: CanonicalAddr) -> StdResult<()> {
+    let legacy_store: ReadonlySingleton<LegacyConfig> = singleton_read(storage, KEY_CONFIG);
+    let legacy_config: LegacyConfig = legacy_store.load()?;
+    let config = Config {
+        owner: legacy_config.owner,
+        pending_owner: None,
+        mirror_token: legacy_config.mirror_token,
+        mint_contract: legacy_config.mint_contract,
+        oracle_contract,
+        terraswap_factory: legacy_config.terraswap_factory,
+        staking_contract: legacy_config.staking_contract,
+        commission_collector: legacy_config.commission_collector,
+        token_code_id: legacy_config.token_code_id,
+        base_denom: legacy_config.base_denom,
+        genesis_time: legacy_config.genesis_time,
+        distribution_schedule: legacy_config.distribution_schedule,
+    };
+    store_config(storage, &config)
+}
+
+#[cfg(test)]
+mod migration_tests {
+    use super::*;
+    use crate::state::read_config;
+    use cosmwasm_std::testing::mock_dependencies;
+    use cosmwasm_std::Api;
+    use cosmwasm_storage::singleton;
+
+    #[test]
+    fn test_config_migration() {
+        let mut deps = mock_dependencies(&[]);
+        let legacy_config = LegacyConfig {
+            owner: deps.api.addr_canonicalize("owner").unwrap(),
+            mirror_token: deps.api.addr_canonicalize("mirror").unwrap(),
+            mint_contract: deps.api.addr_canonicalize("mint").unwrap(),
+            oracle_contract: deps.api.addr_canonicalize("oracle").unwrap(),
+            terraswap_factory: deps.api.addr_canonicalize("terraswap_factory").unwrap(),
+            staking_contract: deps.api.addr_canonicalize("staking").unwrap(),
+            commission_collector: deps.api.addr_canonicalize("collector").unwrap(),
+            token_code_id: 10u64,
+            base_denom: "uusd".to_string(),
+            genesis_time: 100u64,
+            distribution_schedule: vec![(0, 100, Uint128::from(100u128))],
+        };
+        singleton(&mut deps.storage, KEY_CONFIG)
+            .save(&legacy_config)
+            .unwrap();
+
+        let tefi_oracle = deps.api.addr_canonicalize("tefi_oracle").unwrap();
+        migrate_config(&mut deps.storage, tefi_oracle.clone()).unwrap();
+
+        let config = read_config(&deps.storage).unwrap();
+        assert_eq!(config.owner, legacy_config.owner);
+        assert_eq!(config.pending_owner, None);
+        assert_eq!(config.oracle_contract, tefi_oracle);
+        assert_eq!(
+            config.distribution_schedule,
+            legacy_config.distribution_schedule
+        );
+    }
+}
@@ -6,7 +6,7 @@ use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket, Single
 
 use mirror_protocol::factory::Params;
 
-static KEY_CONFIG: &[u8] = b"config";
+pub static KEY_CONFIG: &[u8] = b"config";
 static KEY_TOTAL_WEIGHT: &[u8] = b"total_weight";
 static KEY_LAST_DISTRIBUTED: &[u8] = b"last_distributed";
 static KEY_WHITELIST_TMP_INFO: &[u8] = b"tmp_whitelist_info";
@@ -17,6 +17,7 @@ static PREFIX_WEIGHT: &[u8] = b"weight";
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: CanonicalAddr,
+    pub pending_owner: Option<CanonicalAddr>,
     pub mirror_token: CanonicalAddr,
     pub mint_contract: CanonicalAddr,
     pub oracle_contract: CanonicalAddr,
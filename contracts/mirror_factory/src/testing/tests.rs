@@ -8,14 +8,15 @@ use crate::state::{
 };
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
 use cosmwasm_std::{
-    attr, from_binary, to_binary, CanonicalAddr, ContractResult, CosmosMsg, Decimal, Env,
+    attr, from_binary, to_binary, CanonicalAddr, Coin, ContractResult, CosmosMsg, Decimal, Env,
     OwnedDeps, Reply, ReplyOn, StdError, SubMsg, Timestamp, Uint128, WasmMsg,
 };
 use cosmwasm_std::{Api, SubMsgExecutionResponse};
 use cw20::{Cw20ExecuteMsg, MinterResponse};
 
 use mirror_protocol::factory::{
-    ConfigResponse, DistributionInfoResponse, ExecuteMsg, InstantiateMsg, Params, QueryMsg,
+    ConfigResponse, DistributionInfoResponse, DistributionScheduleResponse, ExecuteMsg,
+    InstantiateMsg, Params, QueryMsg,
 };
 use mirror_protocol::mint::{ExecuteMsg as MintExecuteMsg, IPOParams};
 use mirror_protocol::staking::Cw20HookMsg as StakingCw20HookMsg;
@@ -24,8 +25,9 @@ use tefi_oracle::hub::HubExecuteMsg as TeFiOracleExecuteMsg;
 
 use protobuf::Message;
 
-use terraswap::asset::AssetInfo;
+use terraswap::asset::{Asset, AssetInfo};
 use terraswap::factory::ExecuteMsg as TerraswapFactoryExecuteMsg;
+use terraswap::pair::ExecuteMsg as TerraswapPairExecuteMsg;
 use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
 
 fn mock_env_time(time: u64) -> Env {
@@ -82,6 +84,7 @@ fn proper_initialization() {
         config,
         ConfigResponse {
             owner: "owner0000".to_string(),
+            pending_owner: None,
             mirror_token: "mirror0000".to_string(),
             mint_contract: "mint0000".to_string(),
             staking_contract: "staking0000".to_string(),
@@ -122,21 +125,103 @@ fn test_update_config() {
     };
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    // upate owner
+    // upate owner, which is only proposed
     let msg = ExecuteMsg::UpdateConfig {
         owner: Some("owner0001".to_string()),
         distribution_schedule: None,
         token_code_id: None,
+        genesis_time: None,
     };
 
     let info = mock_info("owner0000", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
     let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            owner: "owner0000".to_string(),
+            pending_owner: Some("owner0001".to_string()),
+            mirror_token: "mirror0000".to_string(),
+            mint_contract: "mint0000".to_string(),
+            staking_contract: "staking0000".to_string(),
+            commission_collector: "collector0000".to_string(),
+            oracle_contract: "oracle0000".to_string(),
+            terraswap_factory: "terraswapfactory".to_string(),
+            base_denom: BASE_DENOM.to_string(),
+            token_code_id: TOKEN_CODE_ID,
+            genesis_time: 1_571_797_419,
+            distribution_schedule: vec![],
+        }
+    );
+
+    // propose a new owner, the current owner stays in charge until it accepts
+    let msg = ExecuteMsg::ProposeOwner {
+        owner: "owner0001".to_string(),
+    };
+    let info = mock_info("owner0001", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "propose_owner"),
+            attr("pending_owner", "owner0001"),
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.owner, "owner0000".to_string());
+    assert_eq!(config.pending_owner, Some("owner0001".to_string()));
+
+    // the proposed owner can not act as the owner yet
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        distribution_schedule: None,
+        token_code_id: Some(TOKEN_CODE_ID + 1),
+        genesis_time: None,
+    };
+    let info = mock_info("owner0001", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    // only the proposed owner can accept
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    let info = mock_info("owner0001", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "accept_ownership"),
+            attr("owner", "owner0001"),
+        ]
+    );
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
     assert_eq!(
         config,
         ConfigResponse {
             owner: "owner0001".to_string(),
+            pending_owner: None,
             mirror_token: "mirror0000".to_string(),
             mint_contract: "mint0000".to_string(),
             staking_contract: "staking0000".to_string(),
@@ -155,6 +240,7 @@ fn test_update_config() {
         owner: None,
         distribution_schedule: Some(vec![(1, 2, Uint128::from(123u128))]),
         token_code_id: Some(TOKEN_CODE_ID + 1),
+        genesis_time: None,
     };
 
     let info = mock_info("owner0001", &[]);
@@ -165,6 +251,7 @@ fn test_update_config() {
         config,
         ConfigResponse {
             owner: "owner0001".to_string(),
+            pending_owner: None,
             mirror_token: "mirror0000".to_string(),
             mint_contract: "mint0000".to_string(),
             staking_contract: "staking0000".to_string(),
@@ -183,6 +270,7 @@ fn test_update_config() {
         owner: None,
         distribution_schedule: None,
         token_code_id: Some(TOKEN_CODE_ID + 1),
+        genesis_time: None,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -193,6 +281,258 @@ fn test_update_config() {
     }
 }
 
+#[test]
+fn test_update_config_schedule_validation() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_terraswap_pairs(&[(&"uusdmirror0000".to_string(), &"MIRLP0000".to_string())]);
+
+    // schedules are validated on instantiation
+    let msg = InstantiateMsg {
+        base_denom: BASE_DENOM.to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        distribution_schedule: vec![(1000, 1000, Uint128::from(1000u128))],
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("tranche 0 start_time must be before end_time")
+    );
+
+    let msg = InstantiateMsg {
+        base_denom: BASE_DENOM.to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        distribution_schedule: vec![],
+    };
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::PostInitialize {
+        owner: "owner0000".to_string(),
+        mirror_token: "mirror0000".to_string(),
+        mint_contract: "mint0000".to_string(),
+        staking_contract: "staking0000".to_string(),
+        commission_collector: "collector0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        distribution_schedule: Some(vec![
+            (0, 1000, Uint128::from(1000u128)),
+            (1000, 2000, Uint128::from(1000u128)),
+            (1500, 3000, Uint128::from(1000u128)),
+        ]),
+        token_code_id: None,
+        genesis_time: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("tranche 2 overlaps with tranche 1")
+    );
+
+    // genesis can not be moved past the time already distributed
+    let last_distributed = mock_env().block.time.seconds();
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        distribution_schedule: None,
+        token_code_id: None,
+        genesis_time: Some(last_distributed + 1),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err(format!(
+            "genesis_time can not be after the last distribution time {}",
+            last_distributed
+        ))
+    );
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        distribution_schedule: None,
+        token_code_id: None,
+        genesis_time: Some(last_distributed - 100),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.genesis_time, last_distributed - 100);
+}
+
+#[test]
+fn test_query_distribution_schedule() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        base_denom: BASE_DENOM.to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        distribution_schedule: vec![
+            (1800, 3600, Uint128::from(3600u128)),
+            (3600, 3600 + 3600, Uint128::from(7200u128)),
+        ],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let env = mock_env();
+    let genesis_time = env.block.time.seconds();
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // before the first tranche
+    let res = query(
+        deps.as_ref(),
+        mock_env_time(genesis_time + 100),
+        QueryMsg::DistributionSchedule {},
+    )
+    .unwrap();
+    let schedule: DistributionScheduleResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        schedule,
+        DistributionScheduleResponse {
+            genesis_time,
+            distribution_schedule: vec![
+                (1800, 3600, Uint128::from(3600u128)),
+                (3600, 3600 + 3600, Uint128::from(7200u128)),
+            ],
+            active_schedule: None,
+        }
+    );
+
+    // no tranche is active before genesis
+    let res = query(
+        deps.as_ref(),
+        mock_env_time(genesis_time - 100),
+        QueryMsg::DistributionSchedule {},
+    )
+    .unwrap();
+    let schedule: DistributionScheduleResponse = from_binary(&res).unwrap();
+    assert_eq!(schedule.active_schedule, None);
+
+    // first tranche is active
+    let res = query(
+        deps.as_ref(),
+        mock_env_time(genesis_time + 2000),
+        QueryMsg::DistributionSchedule {},
+    )
+    .unwrap();
+    let schedule: DistributionScheduleResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        schedule.active_schedule,
+        Some((1800, 3600, Uint128::from(3600u128)))
+    );
+
+    // second tranche is active from its start time
+    let res = query(
+        deps.as_ref(),
+        mock_env_time(genesis_time + 3600),
+        QueryMsg::DistributionSchedule {},
+    )
+    .unwrap();
+    let schedule: DistributionScheduleResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        schedule.active_schedule,
+        Some((3600, 3600 + 3600, Uint128::from(7200u128)))
+    );
+}
+
+#[test]
+fn test_update_schedule_tranche() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_terraswap_pairs(&[(&"uusdmirror0000".to_string(), &"MIRLP0000".to_string())]);
+
+    let msg = InstantiateMsg {
+        base_denom: BASE_DENOM.to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        distribution_schedule: vec![
+            (0, 1000, Uint128::from(1000u128)),
+            (1000, 2000, Uint128::from(2000u128)),
+            (2000, 3000, Uint128::from(3000u128)),
+        ],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::PostInitialize {
+        owner: "owner0000".to_string(),
+        mirror_token: "mirror0000".to_string(),
+        mint_contract: "mint0000".to_string(),
+        staking_contract: "staking0000".to_string(),
+        commission_collector: "collector0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // unauthorized
+    let msg = ExecuteMsg::UpdateScheduleTranche {
+        index: 1,
+        schedule: (1000, 1500, Uint128::from(500u128)),
+    };
+    let info = mock_info("addr0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+
+    // edit the middle tranche
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_schedule_tranche"),
+            attr("index", "1"),
+            attr("start_time", "1000"),
+            attr("end_time", "1500"),
+            attr("distribution_amount", "500"),
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        config.distribution_schedule,
+        vec![
+            (0, 1000, Uint128::from(1000u128)),
+            (1000, 1500, Uint128::from(500u128)),
+            (2000, 3000, Uint128::from(3000u128)),
+        ]
+    );
+
+    // overlapping edits are rejected
+    let msg = ExecuteMsg::UpdateScheduleTranche {
+        index: 1,
+        schedule: (900, 1500, Uint128::from(500u128)),
+    };
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("tranche overlaps with the previous tranche")
+    );
+
+    let msg = ExecuteMsg::UpdateScheduleTranche {
+        index: 1,
+        schedule: (1000, 2100, Uint128::from(500u128)),
+    };
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("tranche overlaps with the next tranche")
+    );
+
+    let msg = ExecuteMsg::UpdateScheduleTranche {
+        index: 3,
+        schedule: (3000, 4000, Uint128::from(500u128)),
+    };
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, StdError::generic_err("tranche index out of range"));
+}
+
 #[test]
 fn test_update_weight() {
     let mut deps = mock_dependencies(&[]);
@@ -1574,3 +1914,94 @@ fn test_whitelist_pre_ipo_asset() {
         ]
     );
 }
+
+#[test]
+fn test_provide_protocol_liquidity() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_terraswap_pairs(&[
+        (&"uusdmirror0000".to_string(), &"MIRLP0000".to_string()),
+        (&"uusdasset0000".to_string(), &"LP0000".to_string()),
+    ]);
+
+    let msg = InstantiateMsg {
+        base_denom: BASE_DENOM.to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        distribution_schedule: vec![],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::PostInitialize {
+        owner: "owner0000".to_string(),
+        mirror_token: "mirror0000".to_string(),
+        mint_contract: "mint0000".to_string(),
+        staking_contract: "staking0000".to_string(),
+        commission_collector: "collector0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::ProvideProtocolLiquidity {
+        asset_token: "asset0000".to_string(),
+        amounts: (Uint128::from(100u128), Uint128::from(500u128)),
+    };
+
+    // unauthorized
+    let info = mock_info("addr0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: "pair".to_string(),
+                    amount: Uint128::from(100u128),
+                    expires: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "pair".to_string(),
+                funds: vec![Coin {
+                    denom: BASE_DENOM.to_string(),
+                    amount: Uint128::from(500u128),
+                }],
+                msg: to_binary(&TerraswapPairExecuteMsg::ProvideLiquidity {
+                    assets: [
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: "asset0000".to_string(),
+                            },
+                            amount: Uint128::from(100u128),
+                        },
+                        Asset {
+                            info: AssetInfo::NativeToken {
+                                denom: BASE_DENOM.to_string(),
+                            },
+                            amount: Uint128::from(500u128),
+                        },
+                    ],
+                    slippage_tolerance: None,
+                    receiver: None,
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+
+    // pair does not exist
+    let msg = ExecuteMsg::ProvideProtocolLiquidity {
+        asset_token: "asset0001".to_string(),
+        amounts: (Uint128::from(100u128), Uint128::from(500u128)),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+}
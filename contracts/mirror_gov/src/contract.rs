@@ -1,17 +1,19 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
-use crate::migrate::migrate_config;
+use crate::error::ContractError;
+use crate::migrate::{migrate_config, migrate_polls, migrate_state};
 use crate::querier::load_token_balance;
 use crate::staking::{
-    deposit_reward, query_shares, query_staker, stake_voting_rewards, stake_voting_tokens,
-    withdraw_voting_rewards, withdraw_voting_tokens,
+    deposit_reward, query_shares, query_staker, query_staker_reward_debug, stake_voting_rewards,
+    stake_voting_tokens, withdraw_voting_rewards, withdraw_voting_tokens,
 };
 use crate::state::{
     bank_read, bank_store, config_read, config_store, poll_additional_params_read,
     poll_additional_params_store, poll_indexer_store, poll_read, poll_store, poll_voter_read,
     poll_voter_store, read_poll_voters, read_polls, read_tmp_poll_id, state_read, state_store,
-    store_tmp_poll_id, Config, ExecuteData, Poll, PollAdditionalParams, State,
+    store_poll_migration_cursor, store_tmp_poll_id, Config, ExecuteData, Poll,
+    PollAdditionalParams, State,
 };
 
 use cosmwasm_std::{
@@ -19,12 +21,14 @@ use cosmwasm_std::{
     Reply, ReplyOn, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use sha2::{Digest, Sha256};
 
-use mirror_protocol::common::OrderBy;
+use mirror_protocol::common::{OrderBy, OwnerInfo};
 use mirror_protocol::gov::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PollAdminAction,
-    PollConfig, PollExecuteMsg, PollResponse, PollStatus, PollsResponse, QueryMsg, StateResponse,
-    VoteOption, VoterInfo, VotersResponse, VotersResponseItem,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, HasVotedResponse, InstantiateMsg, MigrateMsg,
+    PollAdminAction, PollConfig, PollEndHookMsg, PollExecuteMsg, PollResponse,
+    PollRewardStatsResponse, PollStatus, PollTimelineResponse, PollsResponse, QueryMsg,
+    StateResponse, VoteOption, VoterInfo, VotersResponse, VotersResponseItem,
 };
 
 const MIN_TITLE_LENGTH: usize = 4;
@@ -33,9 +37,10 @@ const MIN_DESC_LENGTH: usize = 4;
 const MAX_DESC_LENGTH: usize = 256;
 const MIN_LINK_LENGTH: usize = 12;
 const MAX_LINK_LENGTH: usize = 128;
-const MAX_POLLS_IN_PROGRESS: usize = 50;
+pub const DEFAULT_MAX_ACTIVE_POLLS: u64 = 50;
 
 const POLL_EXECUTE_REPLY_ID: u64 = 1;
+const POLL_END_HOOK_REPLY_ID: u64 = 2;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -43,14 +48,31 @@ pub fn instantiate(
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     validate_poll_config(&msg.default_poll_config)?;
     validate_poll_config(&msg.migration_poll_config)?;
     validate_poll_config(&msg.auth_admin_poll_config)?;
     validate_voter_weight(msg.voter_weight)?;
+    if let Some(snapshot_ratio) = msg.snapshot_ratio {
+        if msg.snapshot_period != 0 {
+            return Err(ContractError::SnapshotParamsConflict {});
+        }
+        validate_snapshot_ratio(snapshot_ratio)?;
+    }
+
+    let mirror_token = deps.api.addr_canonicalize(&msg.mirror_token)?;
+    let deposit_token = match msg.deposit_token {
+        Some(deposit_token) => deps.api.addr_canonicalize(&deposit_token)?,
+        None => mirror_token.clone(),
+    };
+    let reward_distributor = match msg.reward_distributor {
+        Some(reward_distributor) => Some(deps.api.addr_canonicalize(&reward_distributor)?),
+        None => None,
+    };
 
     let config = Config {
-        mirror_token: deps.api.addr_canonicalize(&msg.mirror_token)?,
+        mirror_token,
+        deposit_token,
         owner: deps.api.addr_canonicalize(info.sender.as_str())?,
         effective_delay: msg.effective_delay,
         default_poll_config: msg.default_poll_config,
@@ -58,9 +80,18 @@ pub fn instantiate(
         auth_admin_poll_config: msg.auth_admin_poll_config,
         voter_weight: msg.voter_weight,
         snapshot_period: msg.snapshot_period,
+        snapshot_ratio: msg.snapshot_ratio,
         admin_manager: deps.api.addr_canonicalize(&msg.admin_manager)?,
         poll_gas_limit: msg.poll_gas_limit,
+        min_vote_amount: msg.min_vote_amount,
+        reward_distributor,
+        abstain_in_quorum: msg.abstain_in_quorum.unwrap_or(true),
+        poll_creation_fee: msg.poll_creation_fee,
+        execution_window: msg.execution_window,
+        max_active_polls: msg.max_active_polls.unwrap_or(DEFAULT_MAX_ACTIVE_POLLS),
     };
+    validate_poll_creation_fee(&config)?;
+    validate_deposit_token(&config)?;
 
     let state = State {
         contract_addr: deps.api.addr_canonicalize(env.contract.address.as_str())?,
@@ -68,6 +99,7 @@ pub fn instantiate(
         total_share: Uint128::zero(),
         total_deposit: Uint128::zero(),
         pending_voting_rewards: Uint128::zero(),
+        reward_dust: Uint128::zero(),
     };
 
     config_store(deps.storage).save(&config)?;
@@ -77,7 +109,12 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::UpdateConfig {
@@ -88,8 +125,15 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             auth_admin_poll_config,
             voter_weight,
             snapshot_period,
+            snapshot_ratio,
             admin_manager,
             poll_gas_limit,
+            min_vote_amount,
+            reward_distributor,
+            abstain_in_quorum,
+            poll_creation_fee,
+            execution_window,
+            max_active_polls,
         } => update_config(
             deps,
             info,
@@ -100,9 +144,17 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             auth_admin_poll_config,
             voter_weight,
             snapshot_period,
+            snapshot_ratio,
             admin_manager,
             poll_gas_limit,
+            min_vote_amount,
+            reward_distributor,
+            abstain_in_quorum,
+            poll_creation_fee,
+            execution_window,
+            max_active_polls,
         ),
+        ExecuteMsg::MigratePolls { limit } => migrate_legacy_polls(deps, info, limit),
         ExecuteMsg::WithdrawVotingTokens { amount } => withdraw_voting_tokens(deps, info, amount),
         ExecuteMsg::WithdrawVotingRewards { poll_id } => {
             withdraw_voting_rewards(deps, info, poll_id)
@@ -124,16 +176,17 @@ pub fn receive_cw20(
     env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     // only asset contract can execute this message
     let config: Config = config_read(deps.storage).load()?;
-    if config.mirror_token != deps.api.addr_canonicalize(info.sender.as_str())? {
-        return Err(StdError::generic_err("unauthorized"));
+    let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if config.mirror_token != sender_raw && config.deposit_token != sender_raw {
+        return Err(ContractError::Unauthorized {});
     }
 
     match from_binary(&cw20_msg.msg) {
-        Ok(Cw20HookMsg::StakeVotingTokens {}) => {
-            stake_voting_tokens(deps, cw20_msg.sender, cw20_msg.amount)
+        Ok(Cw20HookMsg::CreatePoll { .. }) if config.deposit_token != sender_raw => {
+            Err(ContractError::Unauthorized {})
         }
         Ok(Cw20HookMsg::CreatePoll {
             title,
@@ -141,6 +194,7 @@ pub fn receive_cw20(
             link,
             execute_msg,
             admin_action,
+            end_hook,
         }) => create_poll(
             deps,
             env,
@@ -151,20 +205,35 @@ pub fn receive_cw20(
             link,
             execute_msg,
             admin_action,
+            end_hook,
         ),
-        Ok(Cw20HookMsg::DepositReward {}) => deposit_reward(deps, cw20_msg.amount),
-        Err(_) => Err(StdError::generic_err("invalid cw20 hook message")),
+        Ok(_) if config.mirror_token != sender_raw => Err(ContractError::Unauthorized {}),
+        Ok(Cw20HookMsg::StakeVotingTokens {}) => {
+            stake_voting_tokens(deps, cw20_msg.sender, cw20_msg.amount)
+        }
+        Ok(Cw20HookMsg::DepositReward {}) => {
+            if let Some(reward_distributor) = config.reward_distributor {
+                if reward_distributor != deps.api.addr_canonicalize(&cw20_msg.sender)? {
+                    return Err(ContractError::NotRewardDistributor {});
+                }
+            }
+            deposit_reward(deps, cw20_msg.amount)
+        }
+        Err(_) => Err(ContractError::InvalidCw20HookMsg {}),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg.id {
         POLL_EXECUTE_REPLY_ID => {
             let poll_id: u64 = read_tmp_poll_id(deps.storage)?;
             failed_poll(deps, poll_id)
         }
-        _ => Err(StdError::generic_err("reply id is invalid")),
+        // a failing end hook must not prevent the poll from ending, the error is swallowed
+        // and only the hook's own state changes are reverted
+        POLL_END_HOOK_REPLY_ID => Ok(Response::new().add_attribute("end_hook_failed", "true")),
+        _ => Err(ContractError::InvalidReplyId {}),
     }
 }
 
@@ -179,13 +248,20 @@ pub fn update_config(
     auth_admin_poll_config: Option<PollConfig>,
     voter_weight: Option<Decimal>,
     snapshot_period: Option<u64>,
+    snapshot_ratio: Option<Decimal>,
     admin_manager: Option<String>,
     poll_gas_limit: Option<u64>,
-) -> StdResult<Response> {
+    min_vote_amount: Option<Uint128>,
+    reward_distributor: Option<String>,
+    abstain_in_quorum: Option<bool>,
+    poll_creation_fee: Option<Uint128>,
+    execution_window: Option<u64>,
+    max_active_polls: Option<u64>,
+) -> Result<Response, ContractError> {
     let api = deps.api;
-    config_store(deps.storage).update(|mut config| {
+    config_store(deps.storage).update(|mut config| -> Result<_, ContractError> {
         if config.owner != api.addr_canonicalize(info.sender.as_str())? {
-            return Err(StdError::generic_err("unauthorized"));
+            return Err(ContractError::Unauthorized {});
         }
 
         if let Some(owner) = owner {
@@ -216,8 +292,20 @@ pub fn update_config(
             config.voter_weight = voter_weight;
         }
 
-        if let Some(snapshot_period) = snapshot_period {
-            config.snapshot_period = snapshot_period;
+        match (snapshot_period, snapshot_ratio) {
+            (Some(_), Some(_)) => {
+                return Err(ContractError::SnapshotParamsConflict {});
+            }
+            (Some(snapshot_period), None) => {
+                config.snapshot_period = snapshot_period;
+                config.snapshot_ratio = None;
+            }
+            (None, Some(snapshot_ratio)) => {
+                validate_snapshot_ratio(snapshot_ratio)?;
+                config.snapshot_period = 0;
+                config.snapshot_ratio = Some(snapshot_ratio);
+            }
+            (None, None) => {}
         }
 
         if let Some(admin_manager) = admin_manager {
@@ -228,40 +316,85 @@ pub fn update_config(
             config.poll_gas_limit = poll_gas_limit;
         }
 
+        if let Some(min_vote_amount) = min_vote_amount {
+            config.min_vote_amount = min_vote_amount;
+        }
+
+        if let Some(reward_distributor) = reward_distributor {
+            config.reward_distributor = Some(api.addr_canonicalize(&reward_distributor)?);
+        }
+
+        if let Some(abstain_in_quorum) = abstain_in_quorum {
+            config.abstain_in_quorum = abstain_in_quorum;
+        }
+
+        if let Some(poll_creation_fee) = poll_creation_fee {
+            config.poll_creation_fee = poll_creation_fee;
+        }
+
+        if let Some(execution_window) = execution_window {
+            config.execution_window = execution_window;
+        }
+
+        if let Some(max_active_polls) = max_active_polls {
+            config.max_active_polls = max_active_polls;
+        }
+
+        validate_poll_creation_fee(&config)?;
+
         Ok(config)
     })?;
     Ok(Response::default())
 }
 
+pub fn migrate_legacy_polls(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let (migrated_count, has_more) = migrate_polls(deps.storage, limit)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate_polls"),
+        attr("migrated_count", migrated_count.to_string()),
+        attr("has_more", has_more.to_string()),
+    ]))
+}
+
 /// validate_title returns an error if the title is invalid
-fn validate_title(title: &str) -> StdResult<()> {
+fn validate_title(title: &str) -> Result<(), ContractError> {
     if title.len() < MIN_TITLE_LENGTH {
-        Err(StdError::generic_err("Title too short"))
+        Err(ContractError::TitleTooShort {})
     } else if title.len() > MAX_TITLE_LENGTH {
-        Err(StdError::generic_err("Title too long"))
+        Err(ContractError::TitleTooLong {})
     } else {
         Ok(())
     }
 }
 
 /// validate_description returns an error if the description is invalid
-fn validate_description(description: &str) -> StdResult<()> {
+fn validate_description(description: &str) -> Result<(), ContractError> {
     if description.len() < MIN_DESC_LENGTH {
-        Err(StdError::generic_err("Description too short"))
+        Err(ContractError::DescriptionTooShort {})
     } else if description.len() > MAX_DESC_LENGTH {
-        Err(StdError::generic_err("Description too long"))
+        Err(ContractError::DescriptionTooLong {})
     } else {
         Ok(())
     }
 }
 
 /// validate_link returns an error if the link is invalid
-fn validate_link(link: &Option<String>) -> StdResult<()> {
+fn validate_link(link: &Option<String>) -> Result<(), ContractError> {
     if let Some(link) = link {
         if link.len() < MIN_LINK_LENGTH {
-            Err(StdError::generic_err("Link too short"))
+            Err(ContractError::LinkTooShort {})
         } else if link.len() > MAX_LINK_LENGTH {
-            Err(StdError::generic_err("Link too long"))
+            Err(ContractError::LinkTooLong {})
         } else {
             Ok(())
         }
@@ -270,7 +403,7 @@ fn validate_link(link: &Option<String>) -> StdResult<()> {
     }
 }
 
-fn validate_poll_config(poll_config: &PollConfig) -> StdResult<()> {
+fn validate_poll_config(poll_config: &PollConfig) -> Result<(), ContractError> {
     validate_quorum(poll_config.quorum)?;
     validate_threshold(poll_config.threshold)?;
 
@@ -279,9 +412,9 @@ fn validate_poll_config(poll_config: &PollConfig) -> StdResult<()> {
 
 /// validate_quorum returns an error if the quorum is invalid
 /// (we require 0-1)
-fn validate_quorum(quorum: Decimal) -> StdResult<()> {
+fn validate_quorum(quorum: Decimal) -> Result<(), ContractError> {
     if quorum > Decimal::one() {
-        Err(StdError::generic_err("quorum must be 0 to 1"))
+        Err(ContractError::InvalidQuorum {})
     } else {
         Ok(())
     }
@@ -289,22 +422,64 @@ fn validate_quorum(quorum: Decimal) -> StdResult<()> {
 
 /// validate_threshold returns an error if the threshold is invalid
 /// (we require 0-1)
-fn validate_threshold(threshold: Decimal) -> StdResult<()> {
+fn validate_threshold(threshold: Decimal) -> Result<(), ContractError> {
     if threshold > Decimal::one() {
-        Err(StdError::generic_err("threshold must be 0 to 1"))
+        Err(ContractError::InvalidThreshold {})
     } else {
         Ok(())
     }
 }
 
-pub fn validate_voter_weight(voter_weight: Decimal) -> StdResult<()> {
+pub fn validate_voter_weight(voter_weight: Decimal) -> Result<(), ContractError> {
     if voter_weight >= Decimal::one() {
-        Err(StdError::generic_err("voter_weight must be smaller than 1"))
+        Err(ContractError::InvalidVoterWeight {})
     } else {
         Ok(())
     }
 }
 
+/// validate_snapshot_ratio returns an error if the snapshot ratio is invalid
+/// (we require 0-1)
+fn validate_snapshot_ratio(snapshot_ratio: Decimal) -> Result<(), ContractError> {
+    if snapshot_ratio > Decimal::one() {
+        Err(ContractError::InvalidSnapshotRatio {})
+    } else {
+        Ok(())
+    }
+}
+
+/// validate_poll_creation_fee returns an error if the fee has no recipient
+fn validate_poll_creation_fee(config: &Config) -> Result<(), ContractError> {
+    if !config.poll_creation_fee.is_zero() && config.reward_distributor.is_none() {
+        Err(ContractError::PollCreationFeeWithoutDistributor {})
+    } else {
+        Ok(())
+    }
+}
+
+/// validate_deposit_token returns an error if a deposit_token other than
+/// mirror_token has no recipient for the deposits of polls that fail quorum
+fn validate_deposit_token(config: &Config) -> Result<(), ContractError> {
+    if config.deposit_token != config.mirror_token && config.reward_distributor.is_none() {
+        Err(ContractError::DepositTokenWithoutDistributor {})
+    } else {
+        Ok(())
+    }
+}
+
+/// snapshot_window returns the period before the poll's end_time in which
+/// a snapshot of the staked amount can be taken, a snapshot_ratio applies to
+/// the poll's own voting period, unaffected by later config updates
+fn snapshot_window(config: &Config, poll: &Poll) -> u64 {
+    match config.snapshot_ratio {
+        Some(snapshot_ratio) => {
+            let voting_period = poll.end_time - poll.start_time;
+            (Uint128::from(voting_period) * snapshot_ratio).u128() as u64
+        }
+        None => config.snapshot_period,
+    }
+}
+
 pub fn validate_migrations(api: &dyn Api, migrations: &[(String, u64, Binary)]) -> StdResult<()> {
     for (addr, _, _) in migrations.iter() {
         api.addr_validate(addr)?;
@@ -326,7 +501,8 @@ pub fn create_poll(
     link: Option<String>,
     poll_execute_msg: Option<PollExecuteMsg>,
     poll_admin_action: Option<PollAdminAction>,
-) -> StdResult<Response> {
+    poll_end_hook: Option<String>,
+) -> Result<Response, ContractError> {
     validate_title(&title)?;
     validate_description(&description)?;
     validate_link(&link)?;
@@ -337,7 +513,7 @@ pub fn create_poll(
         None => (
             config.default_poll_config.proposal_deposit,
             current_seconds + config.default_poll_config.voting_period,
-            MAX_POLLS_IN_PROGRESS,
+            config.max_active_polls,
         ),
         Some(PollAdminAction::ExecuteMigrations { migrations }) => {
             // check that contract addresses are valid
@@ -346,25 +522,40 @@ pub fn create_poll(
             (
                 config.migration_poll_config.proposal_deposit,
                 current_seconds + config.migration_poll_config.voting_period,
-                MAX_POLLS_IN_PROGRESS + 10usize, // increase maximum to prevent mailcious users from authorizing migrations
+                config.max_active_polls + 10u64, // increase maximum to prevent mailcious users from authorizing migrations
             )
         }
         // all other admin actions have the most restrictive parameters
         _ => (
             config.auth_admin_poll_config.proposal_deposit,
             current_seconds + config.auth_admin_poll_config.voting_period,
-            MAX_POLLS_IN_PROGRESS + 10usize,
+            config.max_active_polls + 10u64,
         ),
     };
 
-    if deposit_amount < proposal_deposit {
-        return Err(StdError::generic_err(format!(
-            "Must deposit more than {} token",
-            proposal_deposit
-        )));
+    let required_amount = proposal_deposit + config.poll_creation_fee;
+    if deposit_amount < required_amount {
+        return Err(ContractError::InsufficientProposalDeposit(required_amount));
     }
 
-    let polls_in_progress: usize = read_polls(
+    // the creation fee is not part of the refundable deposit
+    let deposit_amount = deposit_amount.checked_sub(config.poll_creation_fee)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if let Some(reward_distributor) = &config.reward_distributor {
+        if !config.poll_creation_fee.is_zero() {
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: deps.api.addr_humanize(&config.deposit_token)?.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: deps.api.addr_humanize(reward_distributor)?.to_string(),
+                    amount: config.poll_creation_fee,
+                })?,
+            }));
+        }
+    }
+
+    // bounds the polls DepositReward iterates over
+    let polls_in_progress = read_polls(
         deps.storage,
         Some(PollStatus::InProgress),
         None,
@@ -372,9 +563,9 @@ pub fn create_poll(
         None,
         Some(true),
     )?
-    .len();
-    if polls_in_progress.gt(&max_polls_in_progress) {
-        return Err(StdError::generic_err("Too many polls in progress"));
+    .len() as u64;
+    if polls_in_progress > max_polls_in_progress {
+        return Err(ContractError::TooManyPollsInProgress {});
     }
 
     let mut state: State = state_store(deps.storage).load()?;
@@ -382,20 +573,18 @@ pub fn create_poll(
 
     // Increase poll count & total deposit amount
     state.poll_count += 1;
-    state.total_deposit += deposit_amount;
+    if config.deposit_token == config.mirror_token {
+        state.total_deposit += deposit_amount;
+    }
 
+    let contract_raw = deps.api.addr_canonicalize(env.contract.address.as_str())?;
     let poll_execute_data = if let Some(poll_execute_msg) = poll_execute_msg {
         if poll_admin_action.is_some() {
-            return Err(StdError::generic_err(
-                "Can not make a poll with normal action and admin action",
-            ));
+            return Err(ContractError::PollWithMixedActions {});
         }
         let target_contract = deps.api.addr_canonicalize(&poll_execute_msg.contract)?;
-        let contract_raw = deps.api.addr_canonicalize(env.contract.address.as_str())?;
         if target_contract.eq(&config.admin_manager) || target_contract.eq(&contract_raw) {
-            return Err(StdError::generic_err(
-                "Can not make a normal pool targeting the admin_manager or gov contract",
-            ));
+            return Err(ContractError::InvalidExecuteTarget {});
         }
         Some(ExecuteData {
             contract: target_contract,
@@ -405,6 +594,21 @@ pub fn create_poll(
         None
     };
 
+    // the hook only receives a fixed notification, but gov is still the sender
+    let poll_end_hook = if let Some(poll_end_hook) = poll_end_hook {
+        let target_contract = deps.api.addr_canonicalize(&poll_end_hook)?;
+        if target_contract.eq(&config.admin_manager)
+            || target_contract.eq(&contract_raw)
+            || target_contract.eq(&config.mirror_token)
+            || target_contract.eq(&config.deposit_token)
+        {
+            return Err(ContractError::InvalidEndHookTarget {});
+        }
+        Some(target_contract)
+    } else {
+        None
+    };
+
     let sender_address_raw = deps.api.addr_canonicalize(&proposer)?;
     let new_poll = Poll {
         id: poll_id,
@@ -413,6 +617,7 @@ pub fn create_poll(
         yes_votes: Uint128::zero(),
         no_votes: Uint128::zero(),
         abstain_votes: Uint128::zero(),
+        start_time: current_seconds,
         end_time,
         title,
         description,
@@ -422,6 +627,8 @@ pub fn create_poll(
         total_balance_at_end_poll: None,
         voters_reward: Uint128::zero(),
         staked_amount: None,
+        end_hook: poll_end_hook,
+        withdrawn_reward: Uint128::zero(),
     };
 
     poll_store(deps.storage).save(&poll_id.to_be_bytes(), &new_poll)?;
@@ -439,7 +646,7 @@ pub fn create_poll(
 
     state_store(deps.storage).save(&state)?;
 
-    let r = Response::new().add_attributes(vec![
+    let r = Response::new().add_messages(messages).add_attributes(vec![
         attr("action", "create_poll"),
         attr(
             "creator",
@@ -454,7 +661,7 @@ pub fn create_poll(
 /*
  * Ends a poll.
  */
-pub fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> StdResult<Response> {
+pub fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, ContractError> {
     let config: Config = config_store(deps.storage).load()?;
     let mut a_poll: Poll = poll_store(deps.storage).load(&poll_id.to_be_bytes())?;
     let (target_quorum, target_threshold, is_fast_track) =
@@ -479,19 +686,23 @@ pub fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> StdResult<Response> {
         };
 
     if a_poll.status != PollStatus::InProgress {
-        return Err(StdError::generic_err("Poll is not in progress"));
+        return Err(ContractError::PollNotInProgress {});
     }
 
     let current_seconds = env.block.time.seconds();
     if a_poll.end_time > current_seconds && !is_fast_track {
-        return Err(StdError::generic_err("Voting period has not expired"));
+        return Err(ContractError::VotingPeriodNotExpired {});
     }
 
     let no = a_poll.no_votes.u128();
     let yes = a_poll.yes_votes.u128();
     let abstain = a_poll.abstain_votes.u128();
 
-    let tallied_weight = yes + no + abstain;
+    let tallied_weight = if config.abstain_in_quorum {
+        yes + no + abstain
+    } else {
+        yes + no
+    };
 
     let mut poll_status = PollStatus::Rejected;
     let mut rejected_reason = "";
@@ -525,6 +736,21 @@ pub fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> StdResult<Response> {
         // Quorum: More than quorum of the total staked tokens at the end of the voting
         // period need to have participated in the vote.
         rejected_reason = "Quorum not reached";
+
+        // deposits in mirror_token stay with the stakers, other deposit tokens
+        // can not be staked and are sent to the reward_distributor instead
+        if config.deposit_token != config.mirror_token && !a_poll.deposit_amount.is_zero() {
+            if let Some(reward_distributor) = &config.reward_distributor {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: deps.api.addr_humanize(&config.deposit_token)?.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: deps.api.addr_humanize(reward_distributor)?.to_string(),
+                        amount: a_poll.deposit_amount,
+                    })?,
+                }))
+            }
+        }
     } else {
         if yes != 0u128 && Decimal::from_ratio(yes, yes + no) > target_threshold {
             //Threshold: More than 50% of the tokens that participated in the vote
@@ -538,7 +764,7 @@ pub fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> StdResult<Response> {
         // Refunds deposit only when quorum is reached
         if !a_poll.deposit_amount.is_zero() {
             messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: deps.api.addr_humanize(&config.mirror_token)?.to_string(),
+                contract_addr: deps.api.addr_humanize(&config.deposit_token)?.to_string(),
                 funds: vec![],
                 msg: to_binary(&Cw20ExecuteMsg::Transfer {
                     recipient: deps.api.addr_humanize(&a_poll.creator)?.to_string(),
@@ -551,13 +777,13 @@ pub fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> StdResult<Response> {
     // if the poll is fast track and is rejected, we return error instead of updating state
     // the poll can still pass until the poll end_time
     if poll_status.eq(&PollStatus::Rejected) && is_fast_track && a_poll.end_time > current_seconds {
-        return Err(StdError::generic_err(
-            "Fastrack poll has not reached the target quorum or threshold",
-        ));
+        return Err(ContractError::FastTrackNotPassed {});
     }
 
     // Decrease total deposit amount
-    state.total_deposit = state.total_deposit.checked_sub(a_poll.deposit_amount)?;
+    if config.deposit_token == config.mirror_token {
+        state.total_deposit = state.total_deposit.checked_sub(a_poll.deposit_amount)?;
+    }
     state_store(deps.storage).save(&state)?;
 
     // Update poll indexer
@@ -569,18 +795,39 @@ pub fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> StdResult<Response> {
     a_poll.total_balance_at_end_poll = Some(staked_weight);
     poll_store(deps.storage).save(&poll_id.to_be_bytes(), &a_poll)?;
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "end_poll"),
-        attr("poll_id", &poll_id.to_string()),
-        attr("rejected_reason", rejected_reason),
-        attr("passed", &passed.to_string()),
-    ]))
+    // the end hook replies on failure so it can not block the poll from ending
+    let end_hook_submsgs: Vec<SubMsg> = match &a_poll.end_hook {
+        Some(end_hook) => vec![SubMsg {
+            msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: deps.api.addr_humanize(end_hook)?.to_string(),
+                msg: to_binary(&PollEndHookMsg::PollEnded {
+                    poll_id,
+                    status: a_poll.status.clone(),
+                })?,
+                funds: vec![],
+            }),
+            gas_limit: Some(config.poll_gas_limit),
+            id: POLL_END_HOOK_REPLY_ID,
+            reply_on: ReplyOn::Error,
+        }],
+        None => vec![],
+    };
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(end_hook_submsgs)
+        .add_attributes(vec![
+            attr("action", "end_poll"),
+            attr("poll_id", &poll_id.to_string()),
+            attr("rejected_reason", rejected_reason),
+            attr("passed", &passed.to_string()),
+        ]))
 }
 
 /*
  * Execute a msg of passed poll.
  */
-pub fn execute_poll(deps: DepsMut, env: Env, poll_id: u64) -> StdResult<Response> {
+pub fn execute_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, ContractError> {
     let config: Config = config_read(deps.storage).load()?;
     let mut a_poll: Poll = poll_store(deps.storage).load(&poll_id.to_be_bytes())?;
 
@@ -594,12 +841,33 @@ pub fn execute_poll(deps: DepsMut, env: Env, poll_id: u64) -> StdResult<Response
         };
 
     if a_poll.status != PollStatus::Passed {
-        return Err(StdError::generic_err("Poll is not in passed status"));
+        return Err(ContractError::PollNotPassed {});
     }
 
     let current_seconds = env.block.time.seconds();
-    if !is_fast_track && a_poll.end_time + config.effective_delay > current_seconds {
-        return Err(StdError::generic_err("Effective delay has not expired"));
+    let effective_at = if is_fast_track {
+        a_poll.end_time
+    } else {
+        a_poll.end_time + config.effective_delay
+    };
+    if !is_fast_track && effective_at > current_seconds {
+        return Err(ContractError::EffectiveDelayNotExpired {});
+    }
+
+    // a passed poll not executed within the execution window expires instead,
+    // its deposit was already refunded by end_poll since the poll reached quorum
+    if config.execution_window != 0 && effective_at + config.execution_window < current_seconds {
+        poll_indexer_store(deps.storage, &PollStatus::Passed).remove(&poll_id.to_be_bytes());
+        poll_indexer_store(deps.storage, &PollStatus::Expired)
+            .save(&poll_id.to_be_bytes(), &true)?;
+
+        a_poll.status = PollStatus::Expired;
+        poll_store(deps.storage).save(&poll_id.to_be_bytes(), &a_poll)?;
+
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "expire_poll"),
+            attr("poll_id", poll_id.to_string()),
+        ]));
     }
 
     poll_indexer_store(deps.storage, &PollStatus::Passed).remove(&poll_id.to_be_bytes());
@@ -629,7 +897,7 @@ pub fn execute_poll(deps: DepsMut, env: Env, poll_id: u64) -> StdResult<Response
             }),
         }
     } else {
-        return Err(StdError::generic_err("The poll does not have execute_data"));
+        return Err(ContractError::NoExecuteData {});
     };
 
     // the execution will reply in case of failure, to mark the poll as failed
@@ -653,7 +921,7 @@ pub fn execute_poll(deps: DepsMut, env: Env, poll_id: u64) -> StdResult<Response
 /*
  * If the executed message of a passed poll fails, it is marked as failed
  */
-pub fn failed_poll(deps: DepsMut, poll_id: u64) -> StdResult<Response> {
+pub fn failed_poll(deps: DepsMut, poll_id: u64) -> Result<Response, ContractError> {
     let mut a_poll: Poll = poll_store(deps.storage).load(&poll_id.to_be_bytes())?;
 
     poll_indexer_store(deps.storage, &PollStatus::Executed).remove(&poll_id.to_be_bytes());
@@ -675,18 +943,24 @@ pub fn cast_vote(
     poll_id: u64,
     vote: VoteOption,
     amount: Uint128,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let sender_address_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
     let config = config_read(deps.storage).load()?;
     let state = state_read(deps.storage).load()?;
     if poll_id == 0 || state.poll_count < poll_id {
-        return Err(StdError::generic_err("Poll does not exist"));
+        return Err(ContractError::PollNotFound {});
     }
 
     let mut a_poll: Poll = poll_store(deps.storage).load(&poll_id.to_be_bytes())?;
     let current_seconds = env.block.time.seconds();
     if a_poll.status != PollStatus::InProgress || current_seconds > a_poll.end_time {
-        return Err(StdError::generic_err("Poll is not in progress"));
+        return Err(ContractError::PollNotInProgress {});
+    }
+
+    if amount < config.min_vote_amount {
+        return Err(ContractError::InsufficientVoteAmount(
+            config.min_vote_amount,
+        ));
     }
 
     // Check the voter already has a vote on the poll
@@ -694,7 +968,7 @@ pub fn cast_vote(
         .load(sender_address_raw.as_slice())
         .is_ok()
     {
-        return Err(StdError::generic_err("User has already voted."));
+        return Err(ContractError::AlreadyVoted {});
     }
 
     let key = &sender_address_raw.as_slice();
@@ -715,9 +989,7 @@ pub fn cast_vote(
         .multiply_ratio(total_balance, total_share)
         < amount
     {
-        return Err(StdError::generic_err(
-            "User does not have enough staked tokens.",
-        ));
+        return Err(ContractError::NotEnoughStaked {});
     }
 
     // update tally info
@@ -742,7 +1014,7 @@ pub fn cast_vote(
 
     // processing snapshot
     let time_to_end = a_poll.end_time - current_seconds;
-    if time_to_end < config.snapshot_period && a_poll.staked_amount.is_none() {
+    if time_to_end < snapshot_window(&config, &a_poll) && a_poll.staked_amount.is_none() {
         a_poll.staked_amount = Some(total_balance);
     }
 
@@ -754,29 +1026,51 @@ pub fn cast_vote(
         attr("amount", &amount.to_string()),
         attr("voter", &info.sender.to_string()),
         attr("vote_option", vote_info.vote.to_string()),
+        attr(
+            "vote_receipt",
+            vote_receipt(
+                poll_id,
+                info.sender.as_str(),
+                &vote_info.vote,
+                amount,
+                env.block.height,
+            ),
+        ),
     ]))
 }
 
+/// vote_receipt returns a deterministic hex encoded proof-of-vote hash
+pub fn vote_receipt(
+    poll_id: u64,
+    voter: &str,
+    vote: &VoteOption,
+    amount: Uint128,
+    height: u64,
+) -> String {
+    let receipt = format!("{}:{}:{}:{}:{}", poll_id, voter, vote, amount, height);
+    hex::encode(Sha256::digest(receipt.as_bytes()))
+}
+
 /*
  * SnapshotPoll is used to take a snapshot of the staked amount for quorum calculation
  */
-pub fn snapshot_poll(deps: DepsMut, env: Env, poll_id: u64) -> StdResult<Response> {
+pub fn snapshot_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, ContractError> {
     let config: Config = config_read(deps.storage).load()?;
     let mut a_poll: Poll = poll_store(deps.storage).load(&poll_id.to_be_bytes())?;
 
     if a_poll.status != PollStatus::InProgress {
-        return Err(StdError::generic_err("Poll is not in progress"));
+        return Err(ContractError::PollNotInProgress {});
     }
 
     let current_seconds = env.block.time.seconds();
     let time_to_end = a_poll.end_time - current_seconds;
 
-    if time_to_end > config.snapshot_period {
-        return Err(StdError::generic_err("Cannot snapshot at this time"));
+    if time_to_end > snapshot_window(&config, &a_poll) {
+        return Err(ContractError::SnapshotNotAllowed {});
     }
 
     if a_poll.staked_amount.is_some() {
-        return Err(StdError::generic_err("Snapshot has already occurred"));
+        return Err(ContractError::SnapshotAlreadyOccurred {});
     }
 
     // store the current staked amount for quorum calculation
@@ -805,9 +1099,17 @@ pub fn snapshot_poll(deps: DepsMut, env: Env, poll_id: u64) -> StdResult<Respons
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Owner {} => to_binary(&query_owner(deps)?),
         QueryMsg::State {} => to_binary(&query_state(deps)?),
         QueryMsg::Staker { address } => to_binary(&query_staker(deps, address)?),
+        QueryMsg::StakerRewardDebug { address } => {
+            to_binary(&query_staker_reward_debug(deps, address)?)
+        }
         QueryMsg::Poll { poll_id } => to_binary(&query_poll(deps, poll_id)?),
+        QueryMsg::PollTimeline { poll_id } => to_binary(&query_poll_timeline(deps, poll_id)?),
+        QueryMsg::PollRewardStats { poll_id } => {
+            to_binary(&query_poll_reward_stats(deps, poll_id)?)
+        }
         QueryMsg::Polls {
             filter,
             start_after,
@@ -815,12 +1117,23 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             order_by,
         } => to_binary(&query_polls(deps, filter, start_after, limit, order_by)?),
         QueryMsg::Voter { poll_id, address } => to_binary(&query_voter(deps, poll_id, address)?),
+        QueryMsg::HasVoted { poll_id, address } => {
+            to_binary(&query_has_voted(deps, poll_id, address)?)
+        }
         QueryMsg::Voters {
             poll_id,
+            vote,
             start_after,
             limit,
             order_by,
-        } => to_binary(&query_voters(deps, poll_id, start_after, limit, order_by)?),
+        } => to_binary(&query_voters(
+            deps,
+            poll_id,
+            vote,
+            start_after,
+            limit,
+            order_by,
+        )?),
         QueryMsg::Shares {
             start_after,
             limit,
@@ -829,19 +1142,39 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     }
 }
 
+fn query_owner(deps: Deps) -> StdResult<OwnerInfo> {
+    let config: Config = config_read(deps.storage).load()?;
+    Ok(OwnerInfo {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        pending_owner: None,
+    })
+}
+
 fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config: Config = config_read(deps.storage).load()?;
     Ok(ConfigResponse {
         owner: deps.api.addr_humanize(&config.owner)?.to_string(),
         mirror_token: deps.api.addr_humanize(&config.mirror_token)?.to_string(),
+        deposit_token: deps.api.addr_humanize(&config.deposit_token)?.to_string(),
         effective_delay: config.effective_delay,
         default_poll_config: config.default_poll_config,
         migration_poll_config: config.migration_poll_config,
         auth_admin_poll_config: config.auth_admin_poll_config,
         voter_weight: config.voter_weight,
         snapshot_period: config.snapshot_period,
+        snapshot_ratio: config.snapshot_ratio,
         admin_manager: deps.api.addr_humanize(&config.admin_manager)?.to_string(),
         poll_gas_limit: config.poll_gas_limit,
+        min_vote_amount: config.min_vote_amount,
+        reward_distributor: config
+            .reward_distributor
+            .map(|raw| deps.api.addr_humanize(&raw))
+            .transpose()?
+            .map(|addr| addr.to_string()),
+        abstain_in_quorum: config.abstain_in_quorum,
+        poll_creation_fee: config.poll_creation_fee,
+        execution_window: config.execution_window,
+        max_active_polls: config.max_active_polls,
     })
 }
 
@@ -852,6 +1185,7 @@ fn query_state(deps: Deps) -> StdResult<StateResponse> {
         total_share: state.total_share,
         total_deposit: state.total_deposit,
         pending_voting_rewards: state.pending_voting_rewards,
+        reward_dust: state.reward_dust,
     })
 }
 
@@ -889,6 +1223,57 @@ fn query_poll(deps: Deps, poll_id: u64) -> StdResult<PollResponse> {
         voters_reward: poll.voters_reward,
         staked_amount: poll.staked_amount,
         admin_action,
+        end_hook: if let Some(end_hook) = poll.end_hook {
+            Some(deps.api.addr_humanize(&end_hook)?.to_string())
+        } else {
+            None
+        },
+    })
+}
+
+fn query_poll_timeline(deps: Deps, poll_id: u64) -> StdResult<PollTimelineResponse> {
+    let config: Config = config_read(deps.storage).load()?;
+    let poll = match poll_read(deps.storage).may_load(&poll_id.to_be_bytes())? {
+        Some(poll) => poll,
+        None => return Err(StdError::generic_err("Poll does not exist")),
+    };
+
+    let is_fast_track = matches!(
+        poll_additional_params_read(deps.storage).may_load(&poll_id.to_be_bytes())?,
+        Some(PollAdditionalParams {
+            admin_action: PollAdminAction::ExecuteMigrations { .. },
+        })
+    );
+    let effective_at = if is_fast_track {
+        poll.end_time
+    } else {
+        poll.end_time + config.effective_delay
+    };
+
+    let expires_at = if config.execution_window != 0 {
+        Some(effective_at + config.execution_window)
+    } else {
+        None
+    };
+
+    Ok(PollTimelineResponse {
+        voting_ends_at: poll.end_time,
+        effective_at,
+        expires_at,
+    })
+}
+
+fn query_poll_reward_stats(deps: Deps, poll_id: u64) -> StdResult<PollRewardStatsResponse> {
+    let poll = match poll_read(deps.storage).may_load(&poll_id.to_be_bytes())? {
+        Some(poll) => poll,
+        None => return Err(StdError::generic_err("Poll does not exist")),
+    };
+
+    Ok(PollRewardStatsResponse {
+        poll_id,
+        voters_reward: poll.voters_reward,
+        withdrawn_reward: poll.withdrawn_reward,
+        remaining_reward: poll.voters_reward.checked_sub(poll.withdrawn_reward)?,
     })
 }
 
@@ -931,6 +1316,11 @@ fn query_polls(
                 voters_reward: poll.voters_reward,
                 staked_amount: poll.staked_amount,
                 admin_action,
+                end_hook: if let Some(end_hook) = &poll.end_hook {
+                    Some(deps.api.addr_humanize(end_hook)?.to_string())
+                } else {
+                    None
+                },
             })
         })
         .collect();
@@ -950,9 +1340,16 @@ fn query_voter(deps: Deps, poll_id: u64, address: String) -> StdResult<VotersRes
     })
 }
 
+fn query_has_voted(deps: Deps, poll_id: u64, address: String) -> StdResult<HasVotedResponse> {
+    let voter_info: Option<VoterInfo> = poll_voter_read(deps.storage, poll_id)
+        .may_load(deps.api.addr_canonicalize(&address)?.as_slice())?;
+    Ok(HasVotedResponse { voter_info })
+}
+
 fn query_voters(
     deps: Deps,
     poll_id: u64,
+    vote: Option<VoteOption>,
     start_after: Option<String>,
     limit: Option<u32>,
     order_by: Option<OrderBy>,
@@ -961,12 +1358,13 @@ fn query_voters(
         read_poll_voters(
             deps.storage,
             poll_id,
+            vote,
             Some(deps.api.addr_canonicalize(&start_after)?),
             limit,
             order_by,
         )?
     } else {
-        read_poll_voters(deps.storage, poll_id, None, limit, order_by)?
+        read_poll_voters(deps.storage, poll_id, vote, None, limit, order_by)?
     };
 
     let voters_response: StdResult<Vec<VotersResponseItem>> = voters
@@ -986,14 +1384,18 @@ fn query_voters(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(mut deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    migrate_state(deps.storage)?;
     migrate_config(
-        deps,
+        deps.branch(),
         msg.migration_poll_config,
         msg.auth_admin_poll_config,
         msg.admin_manager,
         msg.poll_gas_limit,
     )?;
 
+    // existing polls are migrated in batches with MigratePolls
+    store_poll_migration_cursor(deps.storage, 0u64)?;
+
     Ok(Response::default())
 }
@@ -0,0 +1,138 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+/// Messages keep the "Generic error: " prefix of the `StdError::generic_err` strings
+/// the contract returned before, so clients matching on the error text are unaffected
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Generic error: unauthorized")]
+    Unauthorized {},
+
+    #[error("Generic error: invalid cw20 hook message")]
+    InvalidCw20HookMsg {},
+
+    #[error("Generic error: reply id is invalid")]
+    InvalidReplyId {},
+
+    #[error("Generic error: only the reward distributor can deposit rewards")]
+    NotRewardDistributor {},
+
+    #[error("Generic error: Only one of snapshot_period and snapshot_ratio can be set")]
+    SnapshotParamsConflict {},
+
+    #[error("Generic error: quorum must be 0 to 1")]
+    InvalidQuorum {},
+
+    #[error("Generic error: threshold must be 0 to 1")]
+    InvalidThreshold {},
+
+    #[error("Generic error: voter_weight must be smaller than 1")]
+    InvalidVoterWeight {},
+
+    #[error("Generic error: snapshot_ratio must be 0 to 1")]
+    InvalidSnapshotRatio {},
+
+    #[error("Generic error: poll_creation_fee requires a reward_distributor")]
+    PollCreationFeeWithoutDistributor {},
+
+    #[error("Generic error: deposit_token other than mirror_token requires a reward_distributor")]
+    DepositTokenWithoutDistributor {},
+
+    #[error("Generic error: Title too short")]
+    TitleTooShort {},
+
+    #[error("Generic error: Title too long")]
+    TitleTooLong {},
+
+    #[error("Generic error: Description too short")]
+    DescriptionTooShort {},
+
+    #[error("Generic error: Description too long")]
+    DescriptionTooLong {},
+
+    #[error("Generic error: Link too short")]
+    LinkTooShort {},
+
+    #[error("Generic error: Link too long")]
+    LinkTooLong {},
+
+    #[error("Generic error: Must deposit more than {0} token")]
+    InsufficientProposalDeposit(Uint128),
+
+    #[error("Generic error: Too many polls in progress")]
+    TooManyPollsInProgress {},
+
+    #[error("Generic error: Can not make a poll with normal action and admin action")]
+    PollWithMixedActions {},
+
+    #[error(
+        "Generic error: Can not make a normal pool targeting the admin_manager or gov contract"
+    )]
+    InvalidExecuteTarget {},
+
+    #[error("Generic error: Can not make an end hook targeting the admin_manager, gov or token contracts")]
+    InvalidEndHookTarget {},
+
+    #[error("Generic error: Poll does not exist")]
+    PollNotFound {},
+
+    #[error("Generic error: Poll is not in progress")]
+    PollNotInProgress {},
+
+    #[error("Generic error: Voting period has not expired")]
+    VotingPeriodNotExpired {},
+
+    #[error("Generic error: Fastrack poll has not reached the target quorum or threshold")]
+    FastTrackNotPassed {},
+
+    #[error("Generic error: Poll is not in passed status")]
+    PollNotPassed {},
+
+    #[error("Generic error: Effective delay has not expired")]
+    EffectiveDelayNotExpired {},
+
+    #[error("Generic error: The poll does not have execute_data")]
+    NoExecuteData {},
+
+    #[error("Generic error: Must vote at least {0} token")]
+    InsufficientVoteAmount(Uint128),
+
+    #[error("Generic error: User has already voted.")]
+    AlreadyVoted {},
+
+    #[error("Generic error: User does not have enough staked tokens.")]
+    NotEnoughStaked {},
+
+    #[error("Generic error: Cannot snapshot at this time")]
+    SnapshotNotAllowed {},
+
+    #[error("Generic error: Snapshot has already occurred")]
+    SnapshotAlreadyOccurred {},
+
+    #[error("Generic error: Insufficient funds sent")]
+    InsufficientFunds {},
+
+    #[error("Generic error: User is trying to withdraw too many tokens.")]
+    WithdrawTooMany {},
+
+    #[error("Generic error: Nothing staked")]
+    NothingStaked {},
+
+    #[error("Generic error: Nothing to withdraw")]
+    NothingToWithdraw {},
+
+    #[error("Generic error: Reward deposited is too small")]
+    RewardTooSmall {},
+
+    #[error("Generic error: This poll is still in progress")]
+    PollInProgress {},
+
+    #[error("Generic error: This poll has no voting rewards")]
+    NoVotingRewards {},
+}
@@ -1,4 +1,5 @@
 pub mod contract;
+mod error;
 mod migrate;
 mod querier;
 mod staking;
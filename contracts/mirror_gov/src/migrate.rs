@@ -1,10 +1,20 @@
-use cosmwasm_std::{CanonicalAddr, Decimal, DepsMut, StdResult, Uint128};
-use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
-use mirror_protocol::gov::PollConfig;
+use cosmwasm_std::{CanonicalAddr, Decimal, DepsMut, Order, StdError, StdResult, Storage, Uint128};
+use cosmwasm_storage::{
+    bucket_read, singleton, singleton_read, ReadonlyBucket, ReadonlySingleton, Singleton,
+};
+use mirror_protocol::gov::{PollConfig, PollStatus};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::state::{Config, KEY_CONFIG};
+use crate::contract::DEFAULT_MAX_ACTIVE_POLLS;
+use crate::state::{
+    config_read, poll_store, read_poll_migration_cursor, remove_poll_migration_cursor,
+    store_poll_migration_cursor, Config, ExecuteData, Poll, State, KEY_CONFIG, KEY_STATE,
+    PREFIX_POLL,
+};
+
+const MAX_MIGRATION_LIMIT: u32 = 100;
+const DEFAULT_MIGRATION_LIMIT: u32 = 30;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct LegacyConfig {
@@ -20,6 +30,34 @@ pub struct LegacyConfig {
     pub snapshot_period: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyState {
+    pub contract_addr: CanonicalAddr,
+    pub poll_count: u64,
+    pub total_share: Uint128,
+    pub total_deposit: Uint128,
+    pub pending_voting_rewards: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyPoll {
+    pub id: u64,
+    pub creator: CanonicalAddr,
+    pub status: PollStatus,
+    pub yes_votes: Uint128,
+    pub no_votes: Uint128,
+    pub abstain_votes: Uint128,
+    pub end_time: u64,
+    pub title: String,
+    pub description: String,
+    pub link: Option<String>,
+    pub execute_data: Option<ExecuteData>,
+    pub deposit_amount: Uint128,
+    pub total_balance_at_end_poll: Option<Uint128>,
+    pub voters_reward: Uint128,
+    pub staked_amount: Option<Uint128>,
+}
+
 pub fn migrate_config(
     deps: DepsMut,
     migration_poll_config: PollConfig,
@@ -30,11 +68,13 @@ pub fn migrate_config(
     let legacty_store: ReadonlySingleton<LegacyConfig> = singleton_read(deps.storage, KEY_CONFIG);
     let legacy_config: LegacyConfig = legacty_store.load()?;
     let config = Config {
+        deposit_token: legacy_config.mirror_token.clone(),
         mirror_token: legacy_config.mirror_token,
         owner: legacy_config.owner,
         effective_delay: legacy_config.effective_delay,
         voter_weight: legacy_config.voter_weight,
         snapshot_period: legacy_config.snapshot_period,
+        snapshot_ratio: None,
         default_poll_config: PollConfig {
             proposal_deposit: legacy_config.proposal_deposit,
             voting_period: legacy_config.voting_period,
@@ -45,23 +85,112 @@ pub fn migrate_config(
         auth_admin_poll_config,
         admin_manager: deps.api.addr_canonicalize(&admin_manager)?,
         poll_gas_limit,
+        min_vote_amount: Uint128::zero(),
+        reward_distributor: None,
+        abstain_in_quorum: true,
+        poll_creation_fee: Uint128::zero(),
+        execution_window: 0u64,
+        max_active_polls: DEFAULT_MAX_ACTIVE_POLLS,
     };
     let mut store: Singleton<Config> = singleton(deps.storage, KEY_CONFIG);
     store.save(&config)?;
     Ok(())
 }
 
+pub fn migrate_state(storage: &mut dyn Storage) -> StdResult<()> {
+    let legacy_store: ReadonlySingleton<LegacyState> = singleton_read(storage, KEY_STATE);
+    let legacy_state: LegacyState = legacy_store.load()?;
+    let state = State {
+        contract_addr: legacy_state.contract_addr,
+        poll_count: legacy_state.poll_count,
+        total_share: legacy_state.total_share,
+        total_deposit: legacy_state.total_deposit,
+        pending_voting_rewards: legacy_state.pending_voting_rewards,
+        reward_dust: Uint128::zero(),
+    };
+    let mut store: Singleton<State> = singleton(storage, KEY_STATE);
+    store.save(&state)?;
+    Ok(())
+}
+
+/// Migrates up to `limit` legacy polls, following the cursor stored by the contract
+/// migration. Rewards withdrawn before the migration are not tracked, legacy polls all
+/// ran for the default voting period. Returns the number of migrated polls and whether
+/// polls are left for the next call.
+pub fn migrate_polls(storage: &mut dyn Storage, limit: Option<u32>) -> StdResult<(u32, bool)> {
+    let limit = limit
+        .unwrap_or(DEFAULT_MIGRATION_LIMIT)
+        .min(MAX_MIGRATION_LIMIT) as usize;
+    let last_poll_id = read_poll_migration_cursor(storage)?
+        .ok_or_else(|| StdError::generic_err("Polls are already migrated"))?;
+
+    let start = if last_poll_id == 0 {
+        None
+    } else {
+        Some((last_poll_id + 1).to_be_bytes().to_vec())
+    };
+    let voting_period = config_read(storage)
+        .load()?
+        .default_poll_config
+        .voting_period;
+    let legacy_polls_bucket: ReadonlyBucket<LegacyPoll> = bucket_read(storage, PREFIX_POLL);
+    let legacy_polls: Vec<LegacyPoll> = legacy_polls_bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| Ok(item?.1))
+        .collect::<StdResult<Vec<LegacyPoll>>>()?;
+
+    let migrated_count = legacy_polls.len();
+    let last_poll_id = legacy_polls.last().map(|poll| poll.id);
+    for legacy_poll in legacy_polls {
+        let poll = Poll {
+            id: legacy_poll.id,
+            creator: legacy_poll.creator,
+            status: legacy_poll.status,
+            yes_votes: legacy_poll.yes_votes,
+            no_votes: legacy_poll.no_votes,
+            abstain_votes: legacy_poll.abstain_votes,
+            start_time: legacy_poll.end_time.saturating_sub(voting_period),
+            end_time: legacy_poll.end_time,
+            title: legacy_poll.title,
+            description: legacy_poll.description,
+            link: legacy_poll.link,
+            execute_data: legacy_poll.execute_data,
+            deposit_amount: legacy_poll.deposit_amount,
+            total_balance_at_end_poll: legacy_poll.total_balance_at_end_poll,
+            voters_reward: legacy_poll.voters_reward,
+            staked_amount: legacy_poll.staked_amount,
+            end_hook: None,
+            withdrawn_reward: Uint128::zero(),
+        };
+        poll_store(storage).save(&poll.id.to_be_bytes(), &poll)?;
+    }
+
+    let has_more = migrated_count == limit;
+    match last_poll_id {
+        Some(last_poll_id) if has_more => store_poll_migration_cursor(storage, last_poll_id)?,
+        _ => remove_poll_migration_cursor(storage),
+    }
+
+    Ok((migrated_count as u32, has_more))
+}
+
 #[cfg(test)]
 mod migrate_tests {
-    use crate::state::config_read;
+    use crate::state::{poll_read, state_read};
 
     use super::*;
     use cosmwasm_std::{testing::mock_dependencies, Api, Storage};
+    use cosmwasm_storage::Bucket;
 
     pub fn config_old_store(storage: &mut dyn Storage) -> Singleton<LegacyConfig> {
         Singleton::new(storage, KEY_CONFIG)
     }
 
+    pub fn state_old_store(storage: &mut dyn Storage) -> Singleton<'_, LegacyState> {
+        Singleton::new(storage, KEY_STATE)
+    }
+
     #[test]
     fn test_config_migration() {
         let mut deps = mock_dependencies(&[]);
@@ -107,6 +236,7 @@ mod migrate_tests {
             config,
             Config {
                 mirror_token: deps.api.addr_canonicalize("mir0000").unwrap(),
+                deposit_token: deps.api.addr_canonicalize("mir0000").unwrap(),
                 owner: deps.api.addr_canonicalize("owner0000").unwrap(),
                 default_poll_config: PollConfig {
                     quorum: Decimal::one(),
@@ -119,9 +249,130 @@ mod migrate_tests {
                 effective_delay: 100u64,
                 voter_weight: Decimal::percent(50u64),
                 snapshot_period: 20u64,
+                snapshot_ratio: None,
                 admin_manager: deps.api.addr_canonicalize("admin_manager").unwrap(),
                 poll_gas_limit: 4_000_000u64,
+                min_vote_amount: Uint128::zero(),
+                reward_distributor: None,
+                abstain_in_quorum: true,
+                poll_creation_fee: Uint128::zero(),
+                execution_window: 0u64,
+                max_active_polls: DEFAULT_MAX_ACTIVE_POLLS,
             }
         )
     }
+
+    #[test]
+    fn test_state_migration() {
+        let mut deps = mock_dependencies(&[]);
+        let mut legacy_state_store = state_old_store(&mut deps.storage);
+        legacy_state_store
+            .save(&LegacyState {
+                contract_addr: deps.api.addr_canonicalize("gov0000").unwrap(),
+                poll_count: 3u64,
+                total_share: Uint128::from(1000u128),
+                total_deposit: Uint128::from(200u128),
+                pending_voting_rewards: Uint128::from(50u128),
+            })
+            .unwrap();
+
+        migrate_state(deps.as_mut().storage).unwrap();
+
+        let state: State = state_read(&deps.storage).load().unwrap();
+        assert_eq!(
+            state,
+            State {
+                contract_addr: deps.api.addr_canonicalize("gov0000").unwrap(),
+                poll_count: 3u64,
+                total_share: Uint128::from(1000u128),
+                total_deposit: Uint128::from(200u128),
+                pending_voting_rewards: Uint128::from(50u128),
+                reward_dust: Uint128::zero(),
+            }
+        )
+    }
+
+    #[test]
+    fn test_polls_migration() {
+        let mut deps = mock_dependencies(&[]);
+        config_old_store(&mut deps.storage)
+            .save(&LegacyConfig {
+                mirror_token: deps.api.addr_canonicalize("mir0000").unwrap(),
+                owner: deps.api.addr_canonicalize("owner0000").unwrap(),
+                quorum: Decimal::one(),
+                threshold: Decimal::one(),
+                voting_period: 100u64,
+                effective_delay: 100u64,
+                expiration_period: 100u64,
+                proposal_deposit: Uint128::from(100000u128),
+                voter_weight: Decimal::percent(50),
+                snapshot_period: 20u64,
+            })
+            .unwrap();
+        let poll_config = PollConfig {
+            quorum: Decimal::percent(60),
+            threshold: Decimal::percent(60),
+            proposal_deposit: Uint128::from(99999u128),
+            voting_period: 888u64,
+        };
+        migrate_config(
+            deps.as_mut(),
+            poll_config.clone(),
+            poll_config,
+            "admin_manager".to_string(),
+            4_000_000u64,
+        )
+        .unwrap();
+
+        for id in 1u64..=3u64 {
+            let legacy_poll = LegacyPoll {
+                id,
+                creator: deps.api.addr_canonicalize("creator0000").unwrap(),
+                status: PollStatus::Passed,
+                yes_votes: Uint128::from(100u128),
+                no_votes: Uint128::zero(),
+                abstain_votes: Uint128::zero(),
+                end_time: 1000u64,
+                title: "test".to_string(),
+                description: "test".to_string(),
+                link: None,
+                execute_data: None,
+                deposit_amount: Uint128::from(10u128),
+                total_balance_at_end_poll: Some(Uint128::from(100u128)),
+                voters_reward: Uint128::from(50u128),
+                staked_amount: None,
+            };
+            Bucket::new(&mut deps.storage, PREFIX_POLL)
+                .save(&id.to_be_bytes(), &legacy_poll)
+                .unwrap();
+        }
+        store_poll_migration_cursor(deps.as_mut().storage, 0u64).unwrap();
+
+        assert_eq!(
+            migrate_polls(deps.as_mut().storage, Some(2u32)).unwrap(),
+            (2u32, true)
+        );
+        assert_eq!(
+            read_poll_migration_cursor(&deps.storage).unwrap(),
+            Some(2u64)
+        );
+        assert_eq!(
+            migrate_polls(deps.as_mut().storage, Some(2u32)).unwrap(),
+            (1u32, false)
+        );
+        assert_eq!(read_poll_migration_cursor(&deps.storage).unwrap(), None);
+        assert_eq!(
+            migrate_polls(deps.as_mut().storage, None).unwrap_err(),
+            StdError::generic_err("Polls are already migrated")
+        );
+
+        for id in 1u64..=3u64 {
+            let poll: Poll = poll_read(&deps.storage).load(&id.to_be_bytes()).unwrap();
+            assert_eq!(poll.voters_reward, Uint128::from(50u128));
+            // legacy polls ran for the legacy voting period
+            assert_eq!(poll.start_time, 900u64);
+            assert_eq!(poll.end_hook, None);
+            assert_eq!(poll.withdrawn_reward, Uint128::zero());
+        }
+    }
 }
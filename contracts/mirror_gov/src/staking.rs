@@ -1,3 +1,4 @@
+use crate::error::ContractError;
 use crate::querier::load_token_balance;
 use crate::state::{
     bank_read, bank_store, config_read, config_store, poll_read, poll_store, poll_voter_read,
@@ -6,18 +7,23 @@ use crate::state::{
 };
 
 use cosmwasm_std::{
-    attr, to_binary, CanonicalAddr, CosmosMsg, Deps, DepsMut, MessageInfo, Response, StdError,
-    StdResult, Storage, Uint128, WasmMsg,
+    attr, to_binary, CanonicalAddr, CosmosMsg, Deps, DepsMut, MessageInfo, Response, StdResult,
+    Storage, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 use mirror_protocol::common::OrderBy;
 use mirror_protocol::gov::{
-    PollStatus, SharesResponse, SharesResponseItem, StakerResponse, VoterInfo,
+    PollRewardDebugItem, PollStatus, SharesResponse, SharesResponseItem, StakerResponse,
+    StakerRewardDebugResponse, VoterInfo,
 };
 
-pub fn stake_voting_tokens(deps: DepsMut, sender: String, amount: Uint128) -> StdResult<Response> {
+pub fn stake_voting_tokens(
+    deps: DepsMut,
+    sender: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
     if amount.is_zero() {
-        return Err(StdError::generic_err("Insufficient funds sent"));
+        return Err(ContractError::InsufficientFunds {});
     }
 
     let sender_address_raw = deps.api.addr_canonicalize(&sender)?;
@@ -62,7 +68,7 @@ pub fn withdraw_voting_tokens(
     deps: DepsMut,
     info: MessageInfo,
     amount: Option<Uint128>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let sender_address_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
     let key = sender_address_raw.as_slice();
 
@@ -94,9 +100,7 @@ pub fn withdraw_voting_tokens(
             .unwrap_or_else(|| withdraw_share * total_balance / total_share);
 
         if user_locked_share + withdraw_share > user_share {
-            Err(StdError::generic_err(
-                "User is trying to withdraw too many tokens.",
-            ))
+            Err(ContractError::WithdrawTooMany {})
         } else {
             let share = user_share - withdraw_share;
             token_manager.share = Uint128::from(share);
@@ -115,7 +119,7 @@ pub fn withdraw_voting_tokens(
             )
         }
     } else {
-        Err(StdError::generic_err("Nothing staked"))
+        Err(ContractError::NothingStaked {})
     }
 }
 
@@ -153,7 +157,7 @@ fn compute_locked_balance(
     Ok(max_locked)
 }
 
-pub fn deposit_reward(deps: DepsMut, amount: Uint128) -> StdResult<Response> {
+pub fn deposit_reward(deps: DepsMut, amount: Uint128) -> Result<Response, ContractError> {
     let config = config_read(deps.storage).load()?;
 
     let mut polls_in_progress = read_polls(
@@ -172,11 +176,15 @@ pub fn deposit_reward(deps: DepsMut, amount: Uint128) -> StdResult<Response> {
         ]));
     }
 
+    let mut state: State = state_read(deps.storage).load()?;
     let voter_rewards = amount * config.voter_weight;
-    let rewards_per_poll =
-        voter_rewards.multiply_ratio(Uint128::new(1), polls_in_progress.len() as u128);
+
+    // rounding dust from previous deposits is rolled into this distribution
+    let distributable_rewards = voter_rewards + state.reward_dust;
+    let n_polls = polls_in_progress.len() as u128;
+    let rewards_per_poll = distributable_rewards.multiply_ratio(Uint128::new(1), n_polls);
     if rewards_per_poll.is_zero() {
-        return Err(StdError::generic_err("Reward deposited is too small"));
+        return Err(ContractError::RewardTooSmall {});
     }
     for poll in polls_in_progress.iter_mut() {
         poll.voters_reward += rewards_per_poll;
@@ -185,10 +193,10 @@ pub fn deposit_reward(deps: DepsMut, amount: Uint128) -> StdResult<Response> {
             .unwrap()
     }
 
-    state_store(deps.storage).update(|mut state| -> StdResult<_> {
-        state.pending_voting_rewards += voter_rewards;
-        Ok(state)
-    })?;
+    state.reward_dust =
+        distributable_rewards.checked_sub(rewards_per_poll * Uint128::new(n_polls))?;
+    state.pending_voting_rewards += voter_rewards;
+    state_store(deps.storage).save(&state)?;
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "deposit_reward"),
@@ -200,19 +208,19 @@ pub fn withdraw_voting_rewards(
     deps: DepsMut,
     info: MessageInfo,
     poll_id: Option<u64>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = config_store(deps.storage).load()?;
     let sender_address_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
     let key = sender_address_raw.as_slice();
 
     let mut token_manager = bank_read(deps.storage)
         .load(key)
-        .map_err(|_| StdError::generic_err("Nothing staked"))?;
+        .map_err(|_| ContractError::NothingStaked {})?;
 
     let (user_reward_amount, w_polls) =
         withdraw_user_voting_rewards(deps.storage, &sender_address_raw, &token_manager, poll_id)?;
     if user_reward_amount.eq(&0u128) {
-        return Err(StdError::generic_err("Nothing to withdraw"));
+        return Err(ContractError::NothingToWithdraw {});
     }
 
     // cleanup, remove from locked_balance the polls from which we withdrew the rewards
@@ -241,7 +249,7 @@ pub fn stake_voting_rewards(
     deps: DepsMut,
     info: MessageInfo,
     poll_id: Option<u64>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config: Config = config_store(deps.storage).load()?;
     let mut state: State = state_store(deps.storage).load()?;
     let sender_address_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
@@ -249,12 +257,12 @@ pub fn stake_voting_rewards(
 
     let mut token_manager = bank_read(deps.storage)
         .load(key)
-        .map_err(|_| StdError::generic_err("Nothing staked"))?;
+        .map_err(|_| ContractError::NothingStaked {})?;
 
     let (user_reward_amount, w_polls) =
         withdraw_user_voting_rewards(deps.storage, &sender_address_raw, &token_manager, poll_id)?;
     if user_reward_amount.eq(&0u128) {
-        return Err(StdError::generic_err("Nothing to withdraw"));
+        return Err(ContractError::NothingToWithdraw {});
     }
 
     // add the withdrawn rewards to stake pool and calculate share
@@ -300,36 +308,37 @@ fn withdraw_user_voting_rewards(
     user_address: &CanonicalAddr,
     token_manager: &TokenManager,
     poll_id: Option<u64>,
-) -> StdResult<(u128, Vec<u64>)> {
+) -> Result<(u128, Vec<u64>), ContractError> {
     let w_polls: Vec<(Poll, VoterInfo)> = match poll_id {
         Some(poll_id) => {
             let poll: Poll = poll_read(storage).load(&poll_id.to_be_bytes())?;
             let voter_info = poll_voter_read(storage, poll_id).load(user_address.as_slice())?;
             if poll.status == PollStatus::InProgress {
-                return Err(StdError::generic_err("This poll is still in progress"));
+                return Err(ContractError::PollInProgress {});
             }
             if poll.voters_reward.is_zero() {
-                return Err(StdError::generic_err("This poll has no voting rewards"));
+                return Err(ContractError::NoVotingRewards {});
             }
             vec![(poll, voter_info)]
         }
         None => get_withdrawable_polls(storage, token_manager, user_address),
     };
-    let user_reward_amount: u128 = w_polls
-        .iter()
-        .map(|(poll, voting_info)| {
-            // remove voter info from the poll
-            poll_voter_store(storage, poll.id).remove(user_address.as_slice());
-
-            // calculate reward share
-            let total_votes =
-                poll.no_votes.u128() + poll.yes_votes.u128() + poll.abstain_votes.u128();
-            let poll_voting_reward = poll
-                .voters_reward
-                .multiply_ratio(voting_info.balance, total_votes);
-            poll_voting_reward.u128()
-        })
-        .sum();
+    let mut user_reward_amount: u128 = 0u128;
+    for (poll, voting_info) in w_polls.iter() {
+        // remove voter info from the poll
+        poll_voter_store(storage, poll.id).remove(user_address.as_slice());
+
+        // calculate reward share
+        let total_votes = poll.no_votes.u128() + poll.yes_votes.u128() + poll.abstain_votes.u128();
+        let poll_voting_reward = poll
+            .voters_reward
+            .multiply_ratio(voting_info.balance, total_votes);
+        user_reward_amount += poll_voting_reward.u128();
+
+        let mut poll = poll.clone();
+        poll.withdrawn_reward += poll_voting_reward;
+        poll_store(storage).save(&poll.id.to_be_bytes(), &poll)?;
+    }
     Ok((
         user_reward_amount,
         w_polls.iter().map(|(poll, _)| poll.id).collect(),
@@ -366,7 +375,7 @@ fn send_tokens(
     recipient: &CanonicalAddr,
     amount: u128,
     action: &str,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let contract_human = deps.api.addr_humanize(asset_token)?.to_string();
     let recipient_human = deps.api.addr_humanize(recipient)?.to_string();
     let attributes = vec![
@@ -447,6 +456,74 @@ pub fn query_staker(deps: Deps, address: String) -> StdResult<StakerResponse> {
     })
 }
 
+pub fn query_staker_reward_debug(
+    deps: Deps,
+    address: String,
+) -> StdResult<StakerRewardDebugResponse> {
+    let addr_raw = deps.api.addr_canonicalize(&address)?;
+    let config: Config = config_read(deps.storage).load()?;
+    let state: State = state_read(deps.storage).load()?;
+    let token_manager = bank_read(deps.storage)
+        .may_load(addr_raw.as_slice())?
+        .unwrap_or_default();
+
+    let mut pending_voting_rewards = Uint128::zero();
+    let poll_rewards: Vec<PollRewardDebugItem> = token_manager
+        .locked_balance
+        .iter()
+        .map(|(poll_id, voter_info)| {
+            let poll: Poll = poll_read(deps.storage).load(&poll_id.to_be_bytes())?;
+            let total_votes = poll.no_votes + poll.yes_votes + poll.abstain_votes;
+            let reward_amount = if total_votes.is_zero() {
+                Uint128::zero()
+            } else {
+                poll.voters_reward
+                    .multiply_ratio(voter_info.balance, total_votes)
+            };
+
+            // same conditions as get_withdrawable_polls
+            let withdrawable =
+                poll.status != PollStatus::InProgress && !poll.voters_reward.is_zero();
+            if withdrawable {
+                pending_voting_rewards += reward_amount;
+            }
+
+            Ok(PollRewardDebugItem {
+                poll_id: *poll_id,
+                status: poll.status,
+                vote_balance: voter_info.balance,
+                total_votes,
+                voters_reward: poll.voters_reward,
+                reward_amount,
+                withdrawable,
+            })
+        })
+        .collect::<StdResult<Vec<PollRewardDebugItem>>>()?;
+
+    let total_locked_balance = state.total_deposit + state.pending_voting_rewards;
+    let total_balance = load_token_balance(
+        &deps.querier,
+        deps.api.addr_humanize(&config.mirror_token)?.to_string(),
+        &state.contract_addr,
+    )?
+    .checked_sub(total_locked_balance)?;
+
+    Ok(StakerRewardDebugResponse {
+        share: token_manager.share,
+        total_share: state.total_share,
+        total_balance,
+        balance: if !state.total_share.is_zero() {
+            token_manager
+                .share
+                .multiply_ratio(total_balance, state.total_share)
+        } else {
+            Uint128::zero()
+        },
+        pending_voting_rewards,
+        poll_rewards,
+    })
+}
+
 pub fn query_shares(
     deps: Deps,
     start_after: Option<String>,
@@ -6,34 +6,40 @@ use cosmwasm_storage::{
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use mirror_protocol::common::OrderBy;
-use mirror_protocol::gov::{PollAdminAction, PollConfig, PollStatus, VoterInfo};
+use mirror_protocol::common::{calc_limit, calc_range_end, calc_range_start, OrderBy};
+use mirror_protocol::gov::{PollAdminAction, PollConfig, PollStatus, VoteOption, VoterInfo};
 
 pub static KEY_CONFIG: &[u8] = b"config";
-static KEY_STATE: &[u8] = b"state";
+pub static KEY_STATE: &[u8] = b"state";
 static KEY_TMP_POLL_ID: &[u8] = b"tmp_poll_id";
+static KEY_POLL_MIGRATION_CURSOR: &[u8] = b"poll_migration_cursor";
 
 static PREFIX_POLL_INDEXER: &[u8] = b"poll_indexer";
 static PREFIX_POLL_VOTER: &[u8] = b"poll_voter";
-static PREFIX_POLL: &[u8] = b"poll";
+pub static PREFIX_POLL: &[u8] = b"poll";
 static PREFIX_BANK: &[u8] = b"bank";
 static PREFIX_POLL_ADDITIONAL_PARAMS: &[u8] = b"poll_additional_params";
 
-const MAX_LIMIT: u32 = 30;
-const DEFAULT_LIMIT: u32 = 10;
-
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: CanonicalAddr,
     pub mirror_token: CanonicalAddr,
+    pub deposit_token: CanonicalAddr,
     pub effective_delay: u64,
     pub default_poll_config: PollConfig,
     pub migration_poll_config: PollConfig,
     pub auth_admin_poll_config: PollConfig,
     pub voter_weight: Decimal,
     pub snapshot_period: u64,
+    pub snapshot_ratio: Option<Decimal>,
     pub admin_manager: CanonicalAddr,
     pub poll_gas_limit: u64,
+    pub min_vote_amount: Uint128,
+    pub reward_distributor: Option<CanonicalAddr>,
+    pub abstain_in_quorum: bool,
+    pub poll_creation_fee: Uint128,
+    pub execution_window: u64,
+    pub max_active_polls: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -41,8 +47,9 @@ pub struct State {
     pub contract_addr: CanonicalAddr,
     pub poll_count: u64,
     pub total_share: Uint128,
-    pub total_deposit: Uint128,
+    pub total_deposit: Uint128, // proposal deposits held in mirror_token
     pub pending_voting_rewards: Uint128,
+    pub reward_dust: Uint128, // undistributed voter rewards, already included in pending_voting_rewards
 }
 
 #[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -60,6 +67,7 @@ pub struct Poll {
     pub yes_votes: Uint128,
     pub no_votes: Uint128,
     pub abstain_votes: Uint128,
+    pub start_time: u64,
     pub end_time: u64,
     pub title: String,
     pub description: String,
@@ -70,6 +78,8 @@ pub struct Poll {
     pub total_balance_at_end_poll: Option<Uint128>,
     pub voters_reward: Uint128,
     pub staked_amount: Option<Uint128>,
+    pub end_hook: Option<CanonicalAddr>,
+    pub withdrawn_reward: Uint128, // voters_reward already claimed by voters
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -91,6 +101,19 @@ pub fn read_tmp_poll_id(storage: &dyn Storage) -> StdResult<u64> {
     singleton_read(storage, KEY_TMP_POLL_ID).load()
 }
 
+/// last migrated poll id, zero before the first batch; removed once all polls are migrated
+pub fn store_poll_migration_cursor(storage: &mut dyn Storage, last_poll_id: u64) -> StdResult<()> {
+    singleton(storage, KEY_POLL_MIGRATION_CURSOR).save(&last_poll_id)
+}
+
+pub fn remove_poll_migration_cursor(storage: &mut dyn Storage) {
+    singleton::<u64>(storage, KEY_POLL_MIGRATION_CURSOR).remove()
+}
+
+pub fn read_poll_migration_cursor(storage: &dyn Storage) -> StdResult<Option<u64>> {
+    singleton_read(storage, KEY_POLL_MIGRATION_CURSOR).may_load()
+}
+
 pub fn config_store(storage: &mut dyn Storage) -> Singleton<Config> {
     singleton(storage, KEY_CONFIG)
 }
@@ -144,20 +167,25 @@ pub fn poll_voter_read(storage: &dyn Storage, poll_id: u64) -> ReadonlyBucket<Vo
 pub fn read_poll_voters<'a>(
     storage: &'a dyn Storage,
     poll_id: u64,
+    filter: Option<VoteOption>,
     start_after: Option<CanonicalAddr>,
     limit: Option<u32>,
     order_by: Option<OrderBy>,
 ) -> StdResult<Vec<(CanonicalAddr, VoterInfo)>> {
-    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let (start, end, order_by) = match order_by {
-        Some(OrderBy::Asc) => (calc_range_start_addr(start_after), None, OrderBy::Asc),
-        _ => (None, calc_range_end_addr(start_after), OrderBy::Desc),
+    let limit = calc_limit(limit);
+    let (start, end, order_by) = match OrderBy::from_opt(order_by) {
+        OrderBy::Asc => (calc_range_start(start_after.as_deref()), None, OrderBy::Asc),
+        OrderBy::Desc => (None, calc_range_end(start_after.as_deref()), OrderBy::Desc),
     };
 
     let voters: ReadonlyBucket<'a, VoterInfo> =
         ReadonlyBucket::multilevel(storage, &[PREFIX_POLL_VOTER, &poll_id.to_be_bytes()]);
     voters
         .range(start.as_deref(), end.as_deref(), order_by.into())
+        .filter(|item| match (&filter, item) {
+            (Some(vote), Ok((_, v))) => v.vote == *vote,
+            _ => true,
+        })
         .take(limit)
         .map(|item| {
             let (k, v) = item?;
@@ -174,15 +202,16 @@ pub fn read_polls<'a>(
     order_by: Option<OrderBy>,
     remove_hard_cap: Option<bool>,
 ) -> StdResult<Vec<Poll>> {
-    let mut limit: usize = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let mut limit: usize = calc_limit(limit);
     if let Some(remove_hard_cap) = remove_hard_cap {
         if remove_hard_cap {
             limit = usize::MAX;
         }
     }
-    let (start, end, order_by) = match order_by {
-        Some(OrderBy::Asc) => (calc_range_start(start_after), None, OrderBy::Asc),
-        _ => (None, calc_range_end(start_after), OrderBy::Desc),
+    let start_after = start_after.map(u64::to_be_bytes);
+    let (start, end, order_by) = match OrderBy::from_opt(order_by) {
+        OrderBy::Asc => (calc_range_start(start_after), None, OrderBy::Asc),
+        OrderBy::Desc => (None, calc_range_end(start_after), OrderBy::Desc),
     };
 
     if let Some(status) = filter {
@@ -226,10 +255,10 @@ pub fn read_bank_stakers<'a>(
     limit: Option<u32>,
     order_by: Option<OrderBy>,
 ) -> StdResult<Vec<(CanonicalAddr, TokenManager)>> {
-    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let (start, end, order_by) = match order_by {
-        Some(OrderBy::Asc) => (calc_range_start_addr(start_after), None, OrderBy::Asc),
-        _ => (None, calc_range_end_addr(start_after), OrderBy::Desc),
+    let limit = calc_limit(limit);
+    let (start, end, order_by) = match OrderBy::from_opt(order_by) {
+        OrderBy::Asc => (calc_range_start(start_after.as_deref()), None, OrderBy::Asc),
+        OrderBy::Desc => (None, calc_range_end(start_after.as_deref()), OrderBy::Desc),
     };
 
     let stakers: ReadonlyBucket<'a, TokenManager> = ReadonlyBucket::new(storage, PREFIX_BANK);
@@ -242,31 +271,3 @@ pub fn read_bank_stakers<'a>(
         })
         .collect()
 }
-
-// this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_start(start_after: Option<u64>) -> Option<Vec<u8>> {
-    start_after.map(|id| {
-        let mut v = id.to_be_bytes().to_vec();
-        v.push(1);
-        v
-    })
-}
-
-// this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_end(start_after: Option<u64>) -> Option<Vec<u8>> {
-    start_after.map(|id| id.to_be_bytes().to_vec())
-}
-
-// this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_start_addr(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>> {
-    start_after.map(|addr| {
-        let mut v = addr.as_slice().to_vec();
-        v.push(1);
-        v
-    })
-}
-
-// this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_end_addr(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>> {
-    start_after.map(|addr| addr.as_slice().to_vec())
-}
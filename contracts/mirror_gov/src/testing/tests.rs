@@ -1,7 +1,8 @@
-use crate::contract::{execute, instantiate, query, reply};
+use crate::contract::{execute, instantiate, query, reply, vote_receipt};
+use crate::error::ContractError;
 use crate::querier::load_token_balance;
 use crate::state::{
-    bank_read, bank_store, config_read, poll_indexer_store, poll_store, poll_voter_read,
+    bank_read, bank_store, config_read, poll_indexer_store, poll_read, poll_store, poll_voter_read,
     poll_voter_store, state_read, Config, Poll, State, TokenManager,
 };
 use crate::testing::mock_querier::mock_dependencies;
@@ -17,10 +18,11 @@ use mirror_protocol::admin_manager::ExecuteMsg as ManagerExecuteMsg;
 use mirror_protocol::common::OrderBy;
 use mirror_protocol::community::MigrateMsg;
 use mirror_protocol::gov::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PollAdminAction, PollConfig,
-    PollExecuteMsg, PollResponse, PollStatus, PollsResponse, QueryMsg, SharesResponse,
-    SharesResponseItem, StakerResponse, StateResponse, VoteOption, VoterInfo, VotersResponse,
-    VotersResponseItem,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, HasVotedResponse, InstantiateMsg, PollAdminAction,
+    PollConfig, PollEndHookMsg, PollExecuteMsg, PollResponse, PollRewardDebugItem,
+    PollRewardStatsResponse, PollStatus, PollTimelineResponse, PollsResponse, QueryMsg,
+    SharesResponse, SharesResponseItem, StakerResponse, StakerRewardDebugResponse, StateResponse,
+    VoteOption, VoterInfo, VotersResponse, VotersResponseItem,
 };
 
 const VOTING_TOKEN: &str = "voting_token";
@@ -65,6 +67,7 @@ fn mock_env_height(height: u64, time: u64) -> Env {
 fn init_msg() -> InstantiateMsg {
     InstantiateMsg {
         mirror_token: VOTING_TOKEN.to_string(),
+        deposit_token: None,
         default_poll_config: PollConfig {
             proposal_deposit: Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
             voting_period: DEFAULT_VOTING_PERIOD,
@@ -86,8 +89,15 @@ fn init_msg() -> InstantiateMsg {
         effective_delay: DEFAULT_EFFECTIVE_DELAY,
         voter_weight: DEFAULT_VOTER_WEIGHT,
         snapshot_period: DEFAULT_SNAPSHOT_PERIOD,
+        snapshot_ratio: None,
         admin_manager: TEST_ADMIN_MANAGER.to_string(),
         poll_gas_limit: DEFAULT_POLL_GAS_LIMIT,
+        min_vote_amount: Uint128::zero(),
+        reward_distributor: None,
+        abstain_in_quorum: None,
+        poll_creation_fee: Uint128::zero(),
+        execution_window: 0u64,
+        max_active_polls: None,
     }
 }
 
@@ -105,6 +115,7 @@ fn proper_initialization() {
         config,
         Config {
             mirror_token: deps.api.addr_canonicalize(VOTING_TOKEN).unwrap(),
+            deposit_token: deps.api.addr_canonicalize(VOTING_TOKEN).unwrap(),
             owner: deps.api.addr_canonicalize(TEST_CREATOR).unwrap(),
             default_poll_config: PollConfig {
                 proposal_deposit: Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
@@ -127,8 +138,15 @@ fn proper_initialization() {
             effective_delay: DEFAULT_EFFECTIVE_DELAY,
             voter_weight: DEFAULT_VOTER_WEIGHT,
             snapshot_period: DEFAULT_SNAPSHOT_PERIOD,
+            snapshot_ratio: None,
             admin_manager: deps.api.addr_canonicalize(TEST_ADMIN_MANAGER).unwrap(),
             poll_gas_limit: DEFAULT_POLL_GAS_LIMIT,
+            min_vote_amount: Uint128::zero(),
+            reward_distributor: None,
+            abstain_in_quorum: true,
+            poll_creation_fee: Uint128::zero(),
+            execution_window: 0u64,
+            max_active_polls: 50u64,
         }
     );
 
@@ -141,6 +159,7 @@ fn proper_initialization() {
             total_share: Uint128::zero(),
             total_deposit: Uint128::zero(),
             pending_voting_rewards: Uint128::zero(),
+            reward_dust: Uint128::zero(),
         }
     );
 }
@@ -175,7 +194,7 @@ fn fails_create_poll_invalid_quorum() {
 
     match res {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "quorum must be 0 to 1"),
+        Err(ContractError::InvalidQuorum {}) => (),
         Err(e) => panic!("Unexpected error: {:?}", e),
     }
 }
@@ -196,7 +215,7 @@ fn fails_create_poll_invalid_threshold() {
 
     match res {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "threshold must be 0 to 1"),
+        Err(ContractError::InvalidThreshold {}) => (),
         Err(e) => panic!("Unexpected error: {:?}", e),
     }
 }
@@ -217,7 +236,7 @@ fn fails_create_poll_invalid_title() {
     let info = mock_info(VOTING_TOKEN, &[]);
     match execute(deps.as_mut(), mock_env(), info.clone(), msg) {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Title too short"),
+        Err(ContractError::TitleTooShort {}) => (),
         Err(_) => panic!("Unknown error"),
     }
 
@@ -232,7 +251,7 @@ fn fails_create_poll_invalid_title() {
 
     match execute(deps.as_mut(), mock_env(), info, msg) {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Title too long"),
+        Err(ContractError::TitleTooLong {}) => (),
         Err(_) => panic!("Unknown error"),
     }
 }
@@ -253,7 +272,7 @@ fn fails_create_poll_invalid_description() {
     let info = mock_info(VOTING_TOKEN, &[]);
     match execute(deps.as_mut(), mock_env(), info.clone(), msg) {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Description too short"),
+        Err(ContractError::DescriptionTooShort {}) => (),
         Err(_) => panic!("Unknown error"),
     }
 
@@ -268,7 +287,7 @@ fn fails_create_poll_invalid_description() {
 
     match execute(deps.as_mut(), mock_env(), info, msg) {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Description too long"),
+        Err(ContractError::DescriptionTooLong {}) => (),
         Err(_) => panic!("Unknown error"),
     }
 }
@@ -289,7 +308,7 @@ fn fails_create_poll_invalid_link() {
     let info = mock_info(VOTING_TOKEN, &[]);
     match execute(deps.as_mut(), mock_env(), info.clone(), msg) {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Link too short"),
+        Err(ContractError::LinkTooShort {}) => (),
         Err(_) => panic!("Unknown error"),
     }
 
@@ -304,7 +323,7 @@ fn fails_create_poll_invalid_link() {
 
     match execute(deps.as_mut(), mock_env(), info, msg) {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Link too long"),
+        Err(ContractError::LinkTooLong {}) => (),
         Err(_) => panic!("Unknown error"),
     }
 }
@@ -323,16 +342,16 @@ fn fails_create_poll_invalid_deposit() {
             link: None,
             execute_msg: None,
             admin_action: None,
+            end_hook: None,
         })
         .unwrap(),
     });
     let info = mock_info(VOTING_TOKEN, &[]);
     match execute(deps.as_mut(), mock_env(), info, msg) {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
-            msg,
-            format!("Must deposit more than {} token", DEFAULT_PROPOSAL_DEPOSIT)
-        ),
+        Err(ContractError::InsufficientProposalDeposit(amount)) => {
+            assert_eq!(amount, Uint128::new(DEFAULT_PROPOSAL_DEPOSIT))
+        }
         Err(_) => panic!("Unknown error"),
     }
 }
@@ -354,6 +373,7 @@ fn create_poll_msg(
             link,
             execute_msg,
             admin_action,
+            end_hook: None,
         })
         .unwrap(),
     })
@@ -386,6 +406,339 @@ fn happy_days_create_poll() {
     );
 }
 
+#[test]
+fn query_poll_timeline() {
+    const EXECUTION_WINDOW: u64 = 1000;
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    let env = mock_env_height(0, 10000);
+    let info = mock_info(VOTING_TOKEN, &[]);
+
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        None,
+        None,
+        Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+    );
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PollTimeline { poll_id: 1 },
+    )
+    .unwrap();
+    let response: PollTimelineResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response,
+        PollTimelineResponse {
+            voting_ends_at: 10000 + DEFAULT_VOTING_PERIOD,
+            effective_at: 10000 + DEFAULT_VOTING_PERIOD + DEFAULT_EFFECTIVE_DELAY,
+            expires_at: None,
+        }
+    );
+
+    // passed polls expire at the end of the execution window
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        effective_delay: None,
+        default_poll_config: None,
+        migration_poll_config: None,
+        auth_admin_poll_config: None,
+        voter_weight: None,
+        snapshot_period: None,
+        snapshot_ratio: None,
+        admin_manager: None,
+        poll_gas_limit: None,
+        min_vote_amount: None,
+        reward_distributor: None,
+        abstain_in_quorum: None,
+        poll_creation_fee: None,
+        execution_window: Some(EXECUTION_WINDOW),
+        max_active_polls: None,
+    };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PollTimeline { poll_id: 1 },
+    )
+    .unwrap();
+    let response: PollTimelineResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response,
+        PollTimelineResponse {
+            voting_ends_at: 10000 + DEFAULT_VOTING_PERIOD,
+            effective_at: 10000 + DEFAULT_VOTING_PERIOD + DEFAULT_EFFECTIVE_DELAY,
+            expires_at: Some(
+                10000 + DEFAULT_VOTING_PERIOD + DEFAULT_EFFECTIVE_DELAY + EXECUTION_WINDOW
+            ),
+        }
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PollTimeline { poll_id: 2 },
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("Poll does not exist"));
+}
+
+#[test]
+fn create_poll_with_deposit_token() {
+    const DEPOSIT_TOKEN: &str = "deposit0000";
+    let stake_amount = 1000u128;
+
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        deposit_token: Some(DEPOSIT_TOKEN.to_string()),
+        ..init_msg()
+    };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::DepositTokenWithoutDistributor {});
+
+    let msg = InstantiateMsg {
+        deposit_token: Some(DEPOSIT_TOKEN.to_string()),
+        reward_distributor: Some(TEST_COLLECTOR.to_string()),
+        ..init_msg()
+    };
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        None,
+        None,
+        Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+    );
+
+    // deposit must be sent in the deposit token
+    let env = mock_env_height(0, 10000);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info(DEPOSIT_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // deposits in another token are not part of the staked balance
+    let state: State = state_read(&deps.storage).load().unwrap();
+    assert_eq!(state.total_deposit, Uint128::zero());
+
+    // deposit token can not be staked
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(stake_amount),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(DEPOSIT_TOKEN, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::new(stake_amount))],
+    )]);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(stake_amount),
+    };
+    let info = mock_info(TEST_VOTER, &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // deposit is refunded in the deposit token
+    let mut env = env;
+    env.block.time = env.block.time.plus_seconds(DEFAULT_VOTING_PERIOD);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: DEPOSIT_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: TEST_CREATOR.to_string(),
+                amount: Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // deposit of a poll failing quorum goes to the reward distributor
+    let msg = ExecuteMsg::EndPoll { poll_id: 2 };
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "end_poll"),
+            attr("poll_id", "2"),
+            attr("rejected_reason", "Quorum not reached"),
+            attr("passed", "false"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: DEPOSIT_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: TEST_COLLECTOR.to_string(),
+                amount: Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn create_poll_with_creation_fee() {
+    const CREATION_FEE: u128 = 1000u128;
+    let stake_amount = 1000u128;
+
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        poll_creation_fee: Uint128::new(CREATION_FEE),
+        ..init_msg()
+    };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::PollCreationFeeWithoutDistributor {});
+
+    let msg = InstantiateMsg {
+        poll_creation_fee: Uint128::new(CREATION_FEE),
+        reward_distributor: Some(TEST_COLLECTOR.to_string()),
+        ..init_msg()
+    };
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the deposit alone does not cover the fee
+    let env = mock_env_height(0, 10000);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        None,
+        None,
+        Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+    );
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InsufficientProposalDeposit(Uint128::new(
+            DEFAULT_PROPOSAL_DEPOSIT + CREATION_FEE
+        ))
+    );
+
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        None,
+        None,
+        Uint128::new(DEFAULT_PROPOSAL_DEPOSIT + CREATION_FEE),
+    );
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: VOTING_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: TEST_COLLECTOR.to_string(),
+                amount: Uint128::new(CREATION_FEE),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let state: State = state_read(&deps.storage).load().unwrap();
+    assert_eq!(state.total_deposit, Uint128::new(DEFAULT_PROPOSAL_DEPOSIT));
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::new(DEFAULT_PROPOSAL_DEPOSIT + stake_amount),
+        )],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(stake_amount),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(stake_amount),
+    };
+    let info = mock_info(TEST_VOTER, &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // only the deposit is refunded
+    let mut env = env;
+    env.block.time = env.block.time.plus_seconds(DEFAULT_VOTING_PERIOD);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: VOTING_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: TEST_CREATOR.to_string(),
+                amount: Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn fails_create_poll_max_active_polls() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        max_active_polls: Some(2u64),
+        ..init_msg()
+    };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        None,
+        None,
+        Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+    );
+    let info = mock_info(VOTING_TOKEN, &[]);
+    // rejected only once the polls in progress exceed the limit
+    for _ in 0..3 {
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    }
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::TooManyPollsInProgress {});
+}
+
 #[test]
 fn query_polls() {
     let mut deps = mock_dependencies(&[]);
@@ -444,6 +797,7 @@ fn query_polls() {
                 abstain_votes: Uint128::zero(),
                 staked_amount: None,
                 admin_action: None,
+                end_hook: None,
             },
             PollResponse {
                 id: 2u64,
@@ -462,6 +816,7 @@ fn query_polls() {
                 abstain_votes: Uint128::zero(),
                 staked_amount: None,
                 admin_action: None,
+                end_hook: None,
             },
         ]
     );
@@ -497,6 +852,7 @@ fn query_polls() {
             abstain_votes: Uint128::zero(),
             staked_amount: None,
             admin_action: None,
+            end_hook: None,
         },]
     );
 
@@ -531,6 +887,7 @@ fn query_polls() {
             abstain_votes: Uint128::zero(),
             staked_amount: None,
             admin_action: None,
+            end_hook: None,
         }]
     );
 
@@ -565,6 +922,7 @@ fn query_polls() {
             abstain_votes: Uint128::zero(),
             staked_amount: None,
             admin_action: None,
+            end_hook: None,
         },]
     );
 
@@ -610,6 +968,90 @@ fn create_poll_no_quorum() {
     );
 }
 
+#[test]
+fn end_poll_with_end_hook() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    let env = mock_env_height(0, 0);
+    let info = mock_info(VOTING_TOKEN, &[]);
+
+    // the hook can not target the token contracts
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_CREATOR.to_string(),
+        amount: Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+        msg: to_binary(&Cw20HookMsg::CreatePoll {
+            title: "test".to_string(),
+            description: "test".to_string(),
+            link: None,
+            execute_msg: None,
+            admin_action: None,
+            end_hook: Some(VOTING_TOKEN.to_string()),
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::InvalidEndHookTarget {});
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_CREATOR.to_string(),
+        amount: Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+        msg: to_binary(&Cw20HookMsg::CreatePoll {
+            title: "test".to_string(),
+            description: "test".to_string(),
+            link: None,
+            execute_msg: None,
+            admin_action: None,
+            end_hook: Some("hook0000".to_string()),
+        })
+        .unwrap(),
+    });
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+    let poll_res: PollResponse = from_binary(&res).unwrap();
+    assert_eq!(poll_res.end_hook, Some("hook0000".to_string()));
+
+    // hook fires even though the poll is rejected
+    let mut env = env;
+    env.block.time = env.block.time.plus_seconds(DEFAULT_VOTING_PERIOD);
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::EndPoll { poll_id: 1 }).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "end_poll"),
+            attr("poll_id", "1"),
+            attr("rejected_reason", "Quorum not reached"),
+            attr("passed", "false"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg {
+            msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "hook0000".to_string(),
+                msg: to_binary(&PollEndHookMsg::PollEnded {
+                    poll_id: 1,
+                    status: PollStatus::Rejected,
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            gas_limit: Some(DEFAULT_POLL_GAS_LIMIT),
+            id: 2u64,
+            reply_on: ReplyOn::Error,
+        }]
+    );
+
+    // a failed hook does not revert the poll end
+    let reply_msg = Reply {
+        id: 2,
+        result: ContractResult::Err("Error".to_string()),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+    assert_eq!(res.attributes, vec![attr("end_hook_failed", "true")]);
+}
+
 #[test]
 fn fails_end_poll_before_end_time() {
     let mut deps = mock_dependencies(&[]);
@@ -647,7 +1089,7 @@ fn fails_end_poll_before_end_time() {
 
     match execute_res {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Voting period has not expired"),
+        Err(ContractError::VotingPeriodNotExpired {}) => (),
         Err(e) => panic!("Unexpected error: {:?}", e),
     }
 }
@@ -733,7 +1175,7 @@ fn happy_days_end_poll() {
     };
     let env = mock_env_height(0, POLL_START_TIME);
     let info = mock_info(TEST_VOTER, &[]);
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let execute_res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
     assert_eq!(
         execute_res.attributes,
@@ -743,6 +1185,16 @@ fn happy_days_end_poll() {
             attr("amount", "1000"),
             attr("voter", TEST_VOTER),
             attr("vote_option", "yes"),
+            attr(
+                "vote_receipt",
+                vote_receipt(
+                    POLL_ID,
+                    TEST_VOTER,
+                    &VoteOption::Yes,
+                    Uint128::from(stake_amount),
+                    env.block.height
+                )
+            ),
         ]
     );
 
@@ -756,7 +1208,7 @@ fn happy_days_end_poll() {
     )
     .unwrap_err();
     match execute_res {
-        StdError::GenericErr { msg, .. } => assert_eq!(msg, "Poll is not in passed status"),
+        ContractError::PollNotPassed {} => (),
         _ => panic!("DO NOT ENTER HERE"),
     }
 
@@ -813,7 +1265,7 @@ fn happy_days_end_poll() {
     )
     .unwrap_err();
     match execute_res {
-        StdError::GenericErr { msg, .. } => assert_eq!(msg, "Effective delay has not expired"),
+        ContractError::EffectiveDelayNotExpired {} => (),
         _ => panic!("DO NOT ENTER HERE"),
     }
 
@@ -903,6 +1355,141 @@ fn happy_days_end_poll() {
     );
 }
 
+#[test]
+fn execute_poll_with_execution_window() {
+    const POLL_START_TIME: u64 = 1000;
+    const EXECUTION_WINDOW: u64 = 1000;
+    let stake_amount = 1000u128;
+
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        execution_window: EXECUTION_WINDOW,
+        ..init_msg()
+    };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let exec_msg_bz = to_binary(&Cw20ExecuteMsg::Burn {
+        amount: Uint128::new(123),
+    })
+    .unwrap();
+    let env = mock_env_height(0, POLL_START_TIME);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    for _ in 0..2 {
+        let msg = create_poll_msg(
+            "test".to_string(),
+            "test".to_string(),
+            None,
+            Some(PollExecuteMsg {
+                contract: VOTING_TOKEN.to_string(),
+                msg: exec_msg_bz.clone(),
+            }),
+            None,
+            Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+        );
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    }
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::new(stake_amount + 2 * DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(stake_amount),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let mut env = env;
+    for poll_id in 1..3u64 {
+        let msg = ExecuteMsg::CastVote {
+            poll_id,
+            vote: VoteOption::Yes,
+            amount: Uint128::from(stake_amount),
+        };
+        let info = mock_info(TEST_VOTER, &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    }
+
+    env.block.time = env.block.time.plus_seconds(DEFAULT_VOTING_PERIOD);
+    for poll_id in 1..3u64 {
+        // refunded deposits leave the contract balance
+        deps.querier.with_token_balances(&[(
+            &VOTING_TOKEN.to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::new(stake_amount + (3 - poll_id as u128) * DEFAULT_PROPOSAL_DEPOSIT),
+            )],
+        )]);
+        let msg = ExecuteMsg::EndPoll { poll_id };
+        let info = mock_info(TEST_CREATOR, &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: VOTING_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: TEST_CREATOR.to_string(),
+                    amount: Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+                })
+                .unwrap(),
+                funds: vec![],
+            }))]
+        );
+    }
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PollTimeline { poll_id: 1 },
+    )
+    .unwrap();
+    let response: PollTimelineResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response.expires_at,
+        Some(POLL_START_TIME + DEFAULT_VOTING_PERIOD + DEFAULT_EFFECTIVE_DELAY + EXECUTION_WINDOW)
+    );
+
+    // executed at the end of the window
+    env.block.time = env
+        .block
+        .time
+        .plus_seconds(DEFAULT_EFFECTIVE_DELAY + EXECUTION_WINDOW);
+    let msg = ExecuteMsg::ExecutePoll { poll_id: 1 };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "execute_poll"), attr("poll_id", "1")]
+    );
+    assert_eq!(res.messages.len(), 1);
+
+    // expires once the window has passed
+    env.block.time = env.block.time.plus_seconds(1);
+    let msg = ExecuteMsg::ExecutePoll { poll_id: 2 };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "expire_poll"), attr("poll_id", "2")]
+    );
+    assert!(res.messages.is_empty());
+
+    let poll: Poll = poll_read(&deps.storage).load(&2u64.to_be_bytes()).unwrap();
+    assert_eq!(poll.status, PollStatus::Expired);
+
+    // the deposit of the expired poll was refunded by end_poll, nothing is left to reclaim
+    let state: State = state_read(&deps.storage).load().unwrap();
+    assert_eq!(state.total_deposit, Uint128::zero());
+
+    let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    assert_eq!(res, ContractError::PollNotPassed {});
+}
+
 #[test]
 fn failed_execute_poll() {
     const POLL_START_TIME: u64 = 1000;
@@ -983,7 +1570,7 @@ fn failed_execute_poll() {
     };
     let env = mock_env_height(0, POLL_START_TIME);
     let info = mock_info(TEST_VOTER, &[]);
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let execute_res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
     assert_eq!(
         execute_res.attributes,
@@ -993,6 +1580,16 @@ fn failed_execute_poll() {
             attr("amount", "1000"),
             attr("voter", TEST_VOTER),
             attr("vote_option", "yes"),
+            attr(
+                "vote_receipt",
+                vote_receipt(
+                    POLL_ID,
+                    TEST_VOTER,
+                    &VoteOption::Yes,
+                    Uint128::from(stake_amount),
+                    env.block.height
+                )
+            ),
         ]
     );
 
@@ -1277,6 +1874,16 @@ fn end_poll_quorum_rejected() {
             attr("amount", "10"),
             attr("voter", TEST_VOTER),
             attr("vote_option", "yes"),
+            attr(
+                "vote_receipt",
+                vote_receipt(
+                    1,
+                    TEST_VOTER,
+                    &VoteOption::Yes,
+                    Uint128::from(10u128),
+                    mock_env().block.height
+                )
+            ),
         ]
     );
 
@@ -1297,6 +1904,78 @@ fn end_poll_quorum_rejected() {
     );
 }
 
+#[test]
+fn end_poll_abstain_in_quorum() {
+    for (abstain_in_quorum, passed) in [(true, true), (false, false)] {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            abstain_in_quorum: Some(abstain_in_quorum),
+            ..init_msg()
+        };
+        let info = mock_info(TEST_CREATOR, &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = create_poll_msg(
+            "test".to_string(),
+            "test".to_string(),
+            None,
+            None,
+            None,
+            Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+        );
+        let info = mock_info(VOTING_TOKEN, &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        deps.querier.with_token_balances(&[(
+            &VOTING_TOKEN.to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::new(100u128 + DEFAULT_PROPOSAL_DEPOSIT),
+            )],
+        )]);
+
+        // 10 yes and 90 abstain out of 100 staked
+        for (voter, amount, vote) in [
+            (TEST_VOTER, 10u128, VoteOption::Yes),
+            (TEST_VOTER_2, 90u128, VoteOption::Abstain),
+        ] {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: voter.to_string(),
+                amount: Uint128::from(amount),
+                msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+            });
+            let info = mock_info(VOTING_TOKEN, &[]);
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let msg = ExecuteMsg::CastVote {
+                poll_id: 1,
+                vote,
+                amount: Uint128::from(amount),
+            };
+            let info = mock_info(voter, &[]);
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(DEFAULT_VOTING_PERIOD);
+        let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+        let info = mock_info(TEST_CREATOR, &[]);
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "end_poll"),
+                attr("poll_id", "1"),
+                attr(
+                    "rejected_reason",
+                    if passed { "" } else { "Quorum not reached" }
+                ),
+                attr("passed", passed.to_string()),
+            ]
+        );
+    }
+}
+
 #[test]
 fn end_poll_quorum_rejected_noting_staked() {
     let mut deps = mock_dependencies(&coins(100, VOTING_TOKEN));
@@ -1450,7 +2129,14 @@ fn end_poll_nay_rejected() {
         amount: Uint128::from(voter2_stake),
     };
     let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    assert_cast_vote_success(TEST_VOTER_2, voter2_stake, 1, VoteOption::No, execute_res);
+    assert_cast_vote_success(
+        TEST_VOTER_2,
+        voter2_stake,
+        1,
+        VoteOption::No,
+        mock_env().block.height,
+        execute_res,
+    );
 
     let msg = ExecuteMsg::EndPoll { poll_id: 1 };
 
@@ -1531,9 +2217,7 @@ fn fails_cast_vote_not_enough_staked() {
 
     match res {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => {
-            assert_eq!(msg, "User does not have enough staked tokens.")
-        }
+        Err(ContractError::NotEnoughStaked {}) => (),
         Err(e) => panic!("Unexpected error: {:?}", e),
     }
 }
@@ -1598,8 +2282,15 @@ fn happy_days_cast_vote() {
         amount: Uint128::from(amount),
     };
 
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
-    assert_cast_vote_success(TEST_VOTER, amount, 1, VoteOption::Yes, execute_res);
+    let execute_res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_cast_vote_success(
+        TEST_VOTER,
+        amount,
+        1,
+        VoteOption::Yes,
+        env.block.height,
+        execute_res,
+    );
 
     // balance be double
     deps.querier.with_token_balances(&[(
@@ -1637,15 +2328,133 @@ fn happy_days_cast_vote() {
         }
     );
 
-    // Query voters
+    // Query voters
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Voters {
+            poll_id: 1u64,
+            start_after: None,
+            limit: None,
+            order_by: Some(OrderBy::Desc),
+            vote: None,
+        },
+    )
+    .unwrap();
+    let response: VotersResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response.voters,
+        vec![VotersResponseItem {
+            voter: TEST_VOTER.to_string(),
+            vote: VoteOption::Yes,
+            balance: Uint128::from(amount),
+        }]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Voters {
+            poll_id: 1u64,
+            start_after: Some(TEST_VOTER.to_string()),
+            limit: None,
+            order_by: None,
+            vote: None,
+        },
+    )
+    .unwrap();
+    let response: VotersResponse = from_binary(&res).unwrap();
+    assert_eq!(response.voters.len(), 0);
+
+    // Query a single voter
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::HasVoted {
+            poll_id: 1u64,
+            address: TEST_VOTER.to_string(),
+        },
+    )
+    .unwrap();
+    let response: HasVotedResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response.voter_info,
+        Some(VoterInfo {
+            vote: VoteOption::Yes,
+            balance: Uint128::from(amount),
+        })
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::HasVoted {
+            poll_id: 1u64,
+            address: TEST_VOTER_2.to_string(),
+        },
+    )
+    .unwrap();
+    let response: HasVotedResponse = from_binary(&res).unwrap();
+    assert_eq!(response.voter_info, None);
+}
+
+#[test]
+fn query_voters_with_vote_filter() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let env = mock_env_height(0, 10000);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        None,
+        None,
+        Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+    );
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let votes = [
+        (TEST_VOTER, VoteOption::Yes),
+        (TEST_VOTER_2, VoteOption::No),
+        (TEST_VOTER_3, VoteOption::No),
+    ];
+    for (i, (voter, vote)) in votes.iter().enumerate() {
+        deps.querier.with_token_balances(&[(
+            &VOTING_TOKEN.to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::new(10u128 * (i as u128 + 1) + DEFAULT_PROPOSAL_DEPOSIT),
+            )],
+        )]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: voter.to_string(),
+            amount: Uint128::from(10u128),
+            msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+        });
+        let info = mock_info(VOTING_TOKEN, &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: vote.clone(),
+            amount: Uint128::from(10u128),
+        };
+        let env = mock_env_height(0, 10000);
+        let info = mock_info(voter, &[]);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
+
     let res = query(
         deps.as_ref(),
         mock_env(),
         QueryMsg::Voters {
             poll_id: 1u64,
+            vote: Some(VoteOption::No),
             start_after: None,
-            limit: None,
-            order_by: Some(OrderBy::Desc),
+            limit: Some(1u32),
+            order_by: Some(OrderBy::Asc),
         },
     )
     .unwrap();
@@ -1653,9 +2462,9 @@ fn happy_days_cast_vote() {
     assert_eq!(
         response.voters,
         vec![VotersResponseItem {
-            voter: TEST_VOTER.to_string(),
-            vote: VoteOption::Yes,
-            balance: Uint128::from(amount),
+            voter: TEST_VOTER_2.to_string(),
+            vote: VoteOption::No,
+            balance: Uint128::from(10u128),
         }]
     );
 
@@ -1664,14 +2473,22 @@ fn happy_days_cast_vote() {
         mock_env(),
         QueryMsg::Voters {
             poll_id: 1u64,
-            start_after: Some(TEST_VOTER.to_string()),
+            vote: Some(VoteOption::No),
+            start_after: Some(TEST_VOTER_2.to_string()),
             limit: None,
-            order_by: None,
+            order_by: Some(OrderBy::Asc),
         },
     )
     .unwrap();
     let response: VotersResponse = from_binary(&res).unwrap();
-    assert_eq!(response.voters.len(), 0);
+    assert_eq!(
+        response.voters,
+        vec![VotersResponseItem {
+            voter: TEST_VOTER_3.to_string(),
+            vote: VoteOption::No,
+            balance: Uint128::from(10u128),
+        }]
+    );
 }
 
 #[test]
@@ -1703,6 +2520,7 @@ fn happy_days_withdraw_voting_tokens() {
             total_share: Uint128::from(11u128),
             total_deposit: Uint128::zero(),
             pending_voting_rewards: Uint128::zero(),
+            reward_dust: Uint128::zero(),
         }
     );
 
@@ -1742,6 +2560,7 @@ fn happy_days_withdraw_voting_tokens() {
             total_share: Uint128::from(6u128),
             total_deposit: Uint128::zero(),
             pending_voting_rewards: Uint128::zero(),
+            reward_dust: Uint128::zero(),
         }
     );
 }
@@ -1775,6 +2594,7 @@ fn happy_days_withdraw_voting_tokens_all() {
             total_share: Uint128::from(11u128),
             total_deposit: Uint128::zero(),
             pending_voting_rewards: Uint128::zero(),
+            reward_dust: Uint128::zero(),
         }
     );
 
@@ -1812,6 +2632,7 @@ fn happy_days_withdraw_voting_tokens_all() {
             total_share: Uint128::zero(),
             total_deposit: Uint128::zero(),
             pending_voting_rewards: Uint128::zero(),
+            reward_dust: Uint128::zero(),
         }
     );
 }
@@ -1847,6 +2668,7 @@ fn withdraw_voting_tokens() {
                 yes_votes: Uint128::zero(),
                 no_votes: Uint128::zero(),
                 abstain_votes: Uint128::zero(),
+                start_time: 0u64,
                 end_time: 0u64,
                 title: "title".to_string(),
                 description: "description".to_string(),
@@ -1856,6 +2678,8 @@ fn withdraw_voting_tokens() {
                 total_balance_at_end_poll: None,
                 voters_reward: Uint128::zero(),
                 staked_amount: None,
+                end_hook: None,
+                withdrawn_reward: Uint128::zero(),
             },
         )
         .unwrap();
@@ -1870,6 +2694,7 @@ fn withdraw_voting_tokens() {
                 yes_votes: Uint128::zero(),
                 no_votes: Uint128::zero(),
                 abstain_votes: Uint128::zero(),
+                start_time: 0u64,
                 end_time: 0u64,
                 title: "title".to_string(),
                 description: "description".to_string(),
@@ -1879,6 +2704,8 @@ fn withdraw_voting_tokens() {
                 total_balance_at_end_poll: None,
                 voters_reward: Uint128::zero(),
                 staked_amount: None,
+                end_hook: None,
+                withdrawn_reward: Uint128::zero(),
             },
         )
         .unwrap();
@@ -1974,7 +2801,7 @@ fn fails_withdraw_voting_tokens_no_stake() {
 
     match res {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Nothing staked"),
+        Err(ContractError::NothingStaked {}) => (),
         Err(e) => panic!("Unexpected error: {:?}", e),
     }
 }
@@ -2008,13 +2835,66 @@ fn fails_withdraw_too_many_tokens() {
 
     match res {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => {
-            assert_eq!(msg, "User is trying to withdraw too many tokens.")
-        }
+        Err(ContractError::WithdrawTooMany {}) => (),
         Err(e) => panic!("Unexpected error: {:?}", e),
     }
 }
 
+#[test]
+fn cast_vote_receipt() {
+    let mut receipts: Vec<String> = vec![];
+    for _ in 0..2 {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+
+        let msg = create_poll_msg(
+            "test".to_string(),
+            "test".to_string(),
+            None,
+            None,
+            None,
+            Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+        );
+        let info = mock_info(VOTING_TOKEN, &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        deps.querier.with_token_balances(&[(
+            &VOTING_TOKEN.to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::new(10u128 + DEFAULT_PROPOSAL_DEPOSIT),
+            )],
+        )]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: TEST_VOTER.to_string(),
+            amount: Uint128::from(10u128),
+            msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+        });
+        let info = mock_info(VOTING_TOKEN, &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: VoteOption::Yes,
+            amount: Uint128::from(10u128),
+        };
+        let info = mock_info(TEST_VOTER, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let receipt = res
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "vote_receipt")
+            .expect("vote_receipt attribute is emitted");
+        receipts.push(receipt.value.clone());
+    }
+
+    // sha256("1:voter1:yes:10:12345")
+    assert_eq!(
+        receipts,
+        vec!["f5ec38d5f2d0abab1404331eb29a8508fed62a373648468083626349c9944c92".to_string(); 2]
+    );
+}
+
 #[test]
 fn fails_cast_vote_twice() {
     let mut deps = mock_dependencies(&[]);
@@ -2076,7 +2956,14 @@ fn fails_cast_vote_twice() {
     let env = mock_env_height(0, 10000);
     let info = mock_info(TEST_VOTER, &[]);
     let execute_res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-    assert_cast_vote_success(TEST_VOTER, amount, 1, VoteOption::Yes, execute_res);
+    assert_cast_vote_success(
+        TEST_VOTER,
+        amount,
+        1,
+        VoteOption::Yes,
+        env.block.height,
+        execute_res,
+    );
 
     let msg = ExecuteMsg::CastVote {
         poll_id: 1,
@@ -2087,11 +2974,73 @@ fn fails_cast_vote_twice() {
 
     match res {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "User has already voted."),
+        Err(ContractError::AlreadyVoted {}) => (),
         Err(e) => panic!("Unexpected error: {:?}", e),
     }
 }
 
+#[test]
+fn fails_cast_vote_below_min_vote_amount() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        min_vote_amount: Uint128::new(10u128),
+        ..init_msg()
+    };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env = mock_env_height(0, 10000);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        None,
+        None,
+        Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+    );
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::new(11u128 + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(11u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info(TEST_VOTER, &[]);
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(9u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::InsufficientVoteAmount(Uint128::new(10)));
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(10u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_cast_vote_success(
+        TEST_VOTER,
+        10u128,
+        1,
+        VoteOption::Yes,
+        env.block.height,
+        res,
+    );
+}
+
 #[test]
 fn fails_cast_vote_without_poll() {
     let mut deps = mock_dependencies(&[]);
@@ -2108,7 +3057,7 @@ fn fails_cast_vote_without_poll() {
 
     match res {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Poll does not exist"),
+        Err(ContractError::PollNotFound {}) => (),
         Err(e) => panic!("Unexpected error: {:?}", e),
     }
 }
@@ -2156,7 +3105,7 @@ fn fails_insufficient_funds() {
 
     match res {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Insufficient funds sent"),
+        Err(ContractError::InsufficientFunds {}) => (),
         Err(e) => panic!("Unexpected error: {:?}", e),
     }
 }
@@ -2188,7 +3137,7 @@ fn fails_staking_wrong_token() {
 
     match res {
         Ok(_) => panic!("Must return error"),
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        Err(ContractError::Unauthorized {}) => (),
         Err(e) => panic!("Unexpected error: {:?}", e),
     }
 }
@@ -2446,6 +3395,7 @@ fn assert_create_poll_result(
             total_share: Uint128::zero(),
             total_deposit: proposal_deposit,
             pending_voting_rewards: Uint128::zero(),
+            reward_dust: Uint128::zero(),
         }
     );
 }
@@ -2472,6 +3422,7 @@ fn assert_stake_tokens_result(
             total_share: Uint128::new(total_share),
             total_deposit: Uint128::new(total_deposit),
             pending_voting_rewards: Uint128::zero(),
+            reward_dust: Uint128::zero(),
         }
     );
 }
@@ -2481,6 +3432,7 @@ fn assert_cast_vote_success(
     amount: u128,
     poll_id: u64,
     vote_option: VoteOption,
+    height: u64,
     execute_res: Response,
 ) {
     assert_eq!(
@@ -2491,6 +3443,10 @@ fn assert_cast_vote_success(
             attr("amount", amount.to_string()),
             attr("voter", voter),
             attr("vote_option", vote_option.to_string()),
+            attr(
+                "vote_receipt",
+                vote_receipt(poll_id, voter, &vote_option, Uint128::new(amount), height)
+            ),
         ]
     );
 }
@@ -2510,8 +3466,15 @@ fn update_config() {
         effective_delay: None,
         voter_weight: None,
         snapshot_period: None,
+        snapshot_ratio: None,
         admin_manager: None,
         poll_gas_limit: None,
+        min_vote_amount: None,
+        reward_distributor: None,
+        abstain_in_quorum: None,
+        poll_creation_fee: None,
+        execution_window: None,
+        max_active_polls: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -2551,8 +3514,15 @@ fn update_config() {
         effective_delay: Some(20000u64),
         voter_weight: Some(Decimal::percent(1)),
         snapshot_period: Some(60u64),
+        snapshot_ratio: None,
         admin_manager: Some("new_admin_mgr0000".to_string()),
         poll_gas_limit: Some(1_000_000u64),
+        min_vote_amount: None,
+        reward_distributor: None,
+        abstain_in_quorum: None,
+        poll_creation_fee: None,
+        execution_window: None,
+        max_active_polls: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -2581,13 +3551,20 @@ fn update_config() {
         effective_delay: None,
         voter_weight: None,
         snapshot_period: None,
+        snapshot_ratio: None,
         admin_manager: None,
         poll_gas_limit: None,
+        min_vote_amount: None,
+        reward_distributor: None,
+        abstain_in_quorum: None,
+        poll_creation_fee: None,
+        execution_window: None,
+        max_active_polls: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
     match res {
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        Err(ContractError::Unauthorized {}) => (),
         _ => panic!("Must return unauthorized error"),
     }
 }
@@ -2676,7 +3653,7 @@ fn distribute_voting_rewards() {
     let env = mock_env_height(0, 10000);
     let info = mock_info(TEST_VOTER, &[]);
     let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
-    assert_eq!(res, StdError::generic_err("Nothing to withdraw"));
+    assert_eq!(res, ContractError::NothingToWithdraw {});
 
     let env = mock_env_height(0, poll_end_time);
     let info = mock_info(TEST_VOTER, &[]);
@@ -2717,6 +3694,101 @@ fn distribute_voting_rewards() {
         .is_err())
 }
 
+#[test]
+fn query_poll_reward_stats() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        voter_weight: Decimal::percent(50), // distribute 50% rewards to voters
+        ..init_msg()
+    };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env = mock_env_height(0, 10000);
+    let poll_end_time = env.block.time.plus_seconds(DEFAULT_VOTING_PERIOD).seconds();
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        None,
+        None,
+        Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+    );
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 100 yes and 300 no
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::new(400u128 + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    for (voter, amount, vote) in [
+        (TEST_VOTER, 100u128, VoteOption::Yes),
+        (TEST_VOTER_2, 300u128, VoteOption::No),
+    ] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: voter.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+        });
+        let info = mock_info(VOTING_TOKEN, &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote,
+            amount: Uint128::from(amount),
+        };
+        let info = mock_info(voter, &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    }
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::new(500u128 + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_COLLECTOR.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let env = mock_env_height(0, poll_end_time);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // only the first voter withdraws, 50 * 100 / 400 = 12
+    let msg = ExecuteMsg::WithdrawVotingRewards { poll_id: Some(1) };
+    let info = mock_info(TEST_VOTER, &[]);
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PollRewardStats { poll_id: 1 },
+    )
+    .unwrap();
+    let response: PollRewardStatsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response,
+        PollRewardStatsResponse {
+            poll_id: 1,
+            voters_reward: Uint128::from(50u128),
+            withdrawn_reward: Uint128::from(12u128),
+            remaining_reward: Uint128::from(38u128),
+        }
+    );
+}
+
 #[test]
 fn stake_voting_rewards() {
     let mut deps = mock_dependencies(&[]);
@@ -2801,7 +3873,7 @@ fn stake_voting_rewards() {
     let env = mock_env_height(0, 10000);
     let info = mock_info(TEST_VOTER, &[]);
     let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
-    assert_eq!(res, StdError::generic_err("Nothing to withdraw"));
+    assert_eq!(res, ContractError::NothingToWithdraw {});
 
     let env = mock_env_height(0, poll_end_time);
     let info = mock_info(TEST_VOTER, &[]);
@@ -2839,7 +3911,7 @@ fn stake_voting_rewards() {
     let env = mock_env_height(0, 10000);
     let info = mock_info(TEST_VOTER, &[]);
     let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
-    assert_eq!(res, StdError::generic_err("Nothing to withdraw"));
+    assert_eq!(res, ContractError::NothingToWithdraw {});
 
     // voting info has been deleted
     assert!(poll_voter_read(&deps.storage, 1u64)
@@ -3086,6 +4158,7 @@ fn distribute_voting_rewards_only_to_polls_in_progress() {
                 yes_votes: Uint128::zero(),
                 no_votes: Uint128::zero(),
                 abstain_votes: Uint128::zero(),
+                start_time: 0u64,
                 end_time: 0u64,
                 title: "title".to_string(),
                 description: "description".to_string(),
@@ -3095,6 +4168,8 @@ fn distribute_voting_rewards_only_to_polls_in_progress() {
                 total_balance_at_end_poll: None,
                 voters_reward: Uint128::zero(),
                 staked_amount: None,
+                end_hook: None,
+                withdrawn_reward: Uint128::zero(),
             },
         )
         .unwrap();
@@ -3109,6 +4184,7 @@ fn distribute_voting_rewards_only_to_polls_in_progress() {
                 yes_votes: Uint128::zero(),
                 no_votes: Uint128::zero(),
                 abstain_votes: Uint128::zero(),
+                start_time: 0u64,
                 end_time: 0u64,
                 title: "title".to_string(),
                 description: "description".to_string(),
@@ -3118,6 +4194,8 @@ fn distribute_voting_rewards_only_to_polls_in_progress() {
                 total_balance_at_end_poll: None,
                 voters_reward: Uint128::zero(),
                 staked_amount: None,
+                end_hook: None,
+                withdrawn_reward: Uint128::zero(),
             },
         )
         .unwrap();
@@ -3171,6 +4249,7 @@ fn distribute_voting_rewards_only_to_polls_in_progress() {
                 voters_reward: Uint128::from(1000000000u128),
                 staked_amount: None,
                 admin_action: None,
+                end_hook: None,
             },
             PollResponse {
                 id: 2u64,
@@ -3189,6 +4268,7 @@ fn distribute_voting_rewards_only_to_polls_in_progress() {
                 voters_reward: Uint128::zero(),
                 staked_amount: None,
                 admin_action: None,
+                end_hook: None,
             },
         ]
     );
@@ -3933,10 +5013,7 @@ fn snapshot_poll() {
         ExecuteMsg::SnapshotPoll { poll_id: 1 },
     )
     .unwrap_err();
-    assert_eq!(
-        StdError::generic_err("Cannot snapshot at this time",),
-        snapshot_err
-    );
+    assert_eq!(ContractError::SnapshotNotAllowed {}, snapshot_err);
 
     // change time
     creator_env.block.time = creator_env
@@ -3952,16 +5029,132 @@ fn snapshot_poll() {
         )],
     )]);
 
-    let fix_res = execute(
+    let fix_res = execute(
+        deps.as_mut(),
+        creator_env.clone(),
+        creator_info.clone(),
+        ExecuteMsg::SnapshotPoll { poll_id: 1 },
+    )
+    .unwrap();
+
+    assert_eq!(
+        fix_res.attributes,
+        vec![
+            attr("action", "snapshot_poll"),
+            attr("poll_id", "1"),
+            attr("staked_amount", stake_amount.to_string()),
+        ]
+    );
+
+    //must not be executed
+    let snapshot_error = execute(
+        deps.as_mut(),
+        creator_env,
+        creator_info,
+        ExecuteMsg::SnapshotPoll { poll_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::SnapshotAlreadyOccurred {}, snapshot_error);
+}
+
+#[test]
+fn snapshot_poll_with_snapshot_ratio() {
+    let stake_amount = 1000;
+
+    let mut deps = mock_dependencies(&coins(100, VOTING_TOKEN));
+
+    // only one of snapshot_period and snapshot_ratio can be set
+    let msg = InstantiateMsg {
+        snapshot_ratio: Some(Decimal::percent(10)),
+        ..init_msg()
+    };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::SnapshotParamsConflict {});
+
+    // snapshot window is 10% of the voting period
+    let msg = InstantiateMsg {
+        snapshot_period: 0u64,
+        snapshot_ratio: Some(Decimal::percent(10)),
+        ..init_msg()
+    };
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        None,
+        None,
+        Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+    );
+    let mut creator_env = mock_env();
+    let creator_info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(
+        deps.as_mut(),
+        creator_env.clone(),
+        creator_info.clone(),
+        msg,
+    )
+    .unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::new(stake_amount + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+
+    // the window follows the poll's own voting period, not later config updates
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        default_poll_config: Some(PollConfig {
+            voting_period: DEFAULT_VOTING_PERIOD * 2,
+            ..init_msg().default_poll_config
+        }),
+        migration_poll_config: None,
+        auth_admin_poll_config: None,
+        effective_delay: None,
+        voter_weight: None,
+        snapshot_period: None,
+        snapshot_ratio: None,
+        admin_manager: None,
+        poll_gas_limit: None,
+        min_vote_amount: None,
+        reward_distributor: None,
+        abstain_in_quorum: None,
+        poll_creation_fee: None,
+        execution_window: None,
+        max_active_polls: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    // 1001 seconds before end_time, outside the window
+    creator_env.block.time = creator_env
+        .block
+        .time
+        .plus_seconds(DEFAULT_VOTING_PERIOD - 1001);
+    let snapshot_err = execute(
         deps.as_mut(),
         creator_env.clone(),
         creator_info.clone(),
         ExecuteMsg::SnapshotPoll { poll_id: 1 },
     )
-    .unwrap();
+    .unwrap_err();
+    assert_eq!(ContractError::SnapshotNotAllowed {}, snapshot_err);
 
+    // 1000 seconds before end_time, inside the window
+    creator_env.block.time = creator_env.block.time.plus_seconds(1);
+    let res = execute(
+        deps.as_mut(),
+        creator_env,
+        creator_info,
+        ExecuteMsg::SnapshotPoll { poll_id: 1 },
+    )
+    .unwrap();
     assert_eq!(
-        fix_res.attributes,
+        res.attributes,
         vec![
             attr("action", "snapshot_poll"),
             attr("poll_id", "1"),
@@ -3969,18 +5162,53 @@ fn snapshot_poll() {
         ]
     );
 
-    //must not be executed
-    let snapshot_error = execute(
-        deps.as_mut(),
-        creator_env,
-        creator_info,
-        ExecuteMsg::SnapshotPoll { poll_id: 1 },
-    )
-    .unwrap_err();
-    assert_eq!(
-        StdError::generic_err("Snapshot has already occurred"),
-        snapshot_error
-    );
+    // setting an absolute snapshot_period clears the ratio
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        default_poll_config: None,
+        migration_poll_config: None,
+        auth_admin_poll_config: None,
+        effective_delay: None,
+        voter_weight: None,
+        snapshot_period: Some(DEFAULT_SNAPSHOT_PERIOD),
+        snapshot_ratio: Some(Decimal::percent(10)),
+        admin_manager: None,
+        poll_gas_limit: None,
+        min_vote_amount: None,
+        reward_distributor: None,
+        abstain_in_quorum: None,
+        poll_creation_fee: None,
+        execution_window: None,
+        max_active_polls: None,
+    };
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::SnapshotParamsConflict {});
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        default_poll_config: None,
+        migration_poll_config: None,
+        auth_admin_poll_config: None,
+        effective_delay: None,
+        voter_weight: None,
+        snapshot_period: Some(DEFAULT_SNAPSHOT_PERIOD),
+        snapshot_ratio: None,
+        admin_manager: None,
+        poll_gas_limit: None,
+        min_vote_amount: None,
+        reward_distributor: None,
+        abstain_in_quorum: None,
+        poll_creation_fee: None,
+        execution_window: None,
+        max_active_polls: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.snapshot_period, DEFAULT_SNAPSHOT_PERIOD);
+    assert_eq!(config.snapshot_ratio, None);
 }
 
 #[test]
@@ -4045,8 +5273,15 @@ fn happy_days_cast_vote_with_snapshot() {
         amount: Uint128::from(amount),
     };
 
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
-    assert_cast_vote_success(TEST_VOTER, amount, 1, VoteOption::Yes, execute_res);
+    let execute_res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_cast_vote_success(
+        TEST_VOTER,
+        amount,
+        1,
+        VoteOption::Yes,
+        env.block.height,
+        execute_res,
+    );
 
     // balance be double
     deps.querier.with_token_balances(&[(
@@ -4081,7 +5316,14 @@ fn happy_days_cast_vote_with_snapshot() {
     let env = mock_env_height(0, end_time - 9);
     let info = mock_info(TEST_VOTER_2, &[]);
     let execute_res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-    assert_cast_vote_success(TEST_VOTER_2, amount, 1, VoteOption::Yes, execute_res);
+    assert_cast_vote_success(
+        TEST_VOTER_2,
+        amount,
+        1,
+        VoteOption::Yes,
+        env.block.height,
+        execute_res,
+    );
 
     let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
     let value: PollResponse = from_binary(&res).unwrap();
@@ -4095,10 +5337,7 @@ fn happy_days_cast_vote_with_snapshot() {
         ExecuteMsg::SnapshotPoll { poll_id: 1 },
     )
     .unwrap_err();
-    assert_eq!(
-        StdError::generic_err("Snapshot has already occurred"),
-        snap_error
-    );
+    assert_eq!(ContractError::SnapshotAlreadyOccurred {}, snap_error);
 
     // balance be double
     deps.querier.with_token_balances(&[(
@@ -4125,8 +5364,15 @@ fn happy_days_cast_vote_with_snapshot() {
     };
     let env = mock_env_height(0, end_time - 8);
     let info = mock_info(TEST_VOTER_3, &[]);
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
-    assert_cast_vote_success(TEST_VOTER_3, amount, 1, VoteOption::Yes, execute_res);
+    let execute_res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_cast_vote_success(
+        TEST_VOTER_3,
+        amount,
+        1,
+        VoteOption::Yes,
+        env.block.height,
+        execute_res,
+    );
 
     let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
     let value: PollResponse = from_binary(&res).unwrap();
@@ -4215,7 +5461,7 @@ fn fails_end_poll_quorum_inflation_without_snapshot_poll() {
     };
     let env = mock_env_height(0, 0);
     let info = mock_info(TEST_VOTER, &[]);
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let execute_res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
     assert_eq!(
         execute_res.attributes,
@@ -4225,6 +5471,16 @@ fn fails_end_poll_quorum_inflation_without_snapshot_poll() {
             attr("amount", "1000"),
             attr("voter", TEST_VOTER),
             attr("vote_option", "yes"),
+            attr(
+                "vote_receipt",
+                vote_receipt(
+                    POLL_ID,
+                    TEST_VOTER,
+                    &VoteOption::Yes,
+                    Uint128::from(stake_amount),
+                    env.block.height
+                )
+            ),
         ]
     );
 
@@ -4262,7 +5518,7 @@ fn fails_end_poll_quorum_inflation_without_snapshot_poll() {
     };
     let env = mock_env_height(creator_env.block.height, 10000);
     let info = mock_info(TEST_VOTER_2, &[]);
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let execute_res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
     assert_eq!(
         execute_res.attributes,
@@ -4272,6 +5528,16 @@ fn fails_end_poll_quorum_inflation_without_snapshot_poll() {
             attr("amount", "1000"),
             attr("voter", TEST_VOTER_2),
             attr("vote_option", "yes"),
+            attr(
+                "vote_receipt",
+                vote_receipt(
+                    POLL_ID,
+                    TEST_VOTER_2,
+                    &VoteOption::Yes,
+                    Uint128::from(stake_amount),
+                    env.block.height
+                )
+            ),
         ]
     );
 
@@ -4382,7 +5648,7 @@ fn happy_days_end_poll_with_controlled_quorum() {
     };
     let env = mock_env_height(0, POLL_START_TIME);
     let info = mock_info(TEST_VOTER, &[]);
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let execute_res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
     assert_eq!(
         execute_res.attributes,
@@ -4392,6 +5658,16 @@ fn happy_days_end_poll_with_controlled_quorum() {
             attr("amount", "1000"),
             attr("voter", TEST_VOTER),
             attr("vote_option", "yes"),
+            attr(
+                "vote_receipt",
+                vote_receipt(
+                    POLL_ID,
+                    TEST_VOTER,
+                    &VoteOption::Yes,
+                    Uint128::from(stake_amount),
+                    env.block.height
+                )
+            ),
         ]
     );
 
@@ -4444,7 +5720,7 @@ fn happy_days_end_poll_with_controlled_quorum() {
     };
     let env = mock_env_height(creator_env.block.height, 10000);
     let info = mock_info(TEST_VOTER_2, &[]);
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let execute_res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
     assert_eq!(
         execute_res.attributes,
@@ -4454,6 +5730,16 @@ fn happy_days_end_poll_with_controlled_quorum() {
             attr("amount", "8000"),
             attr("voter", TEST_VOTER_2),
             attr("vote_option", "yes"),
+            attr(
+                "vote_receipt",
+                vote_receipt(
+                    POLL_ID,
+                    TEST_VOTER_2,
+                    &VoteOption::Yes,
+                    Uint128::from(8 * stake_amount),
+                    env.block.height
+                )
+            ),
         ]
     );
 
@@ -4689,8 +5975,7 @@ fn create_auth_admin_poll_config() {
     .unwrap_err();
     assert_eq!(
         err,
-        StdError::generic_err(format!(
-            "Must deposit more than {} token",
+        ContractError::InsufficientProposalDeposit(Uint128::new(
             DEFAULT_AUTH_ADMIN_PROPOSAL_DEPOSIT
         ))
     );
@@ -4717,10 +6002,7 @@ fn create_auth_admin_poll_config() {
         msg,
     )
     .unwrap_err();
-    assert_eq!(
-        err,
-        StdError::generic_err("Can not make a poll with normal action and admin action",)
-    );
+    assert_eq!(err, ContractError::PollWithMixedActions {});
 
     let msg = create_poll_msg(
         "test".to_string(),
@@ -4791,6 +6073,7 @@ fn create_auth_admin_poll_config() {
             admin_action: Some(PollAdminAction::AuthorizeClaim {
                 authorized_addr: "someaddrr0000".to_string(),
             }),
+            end_hook: None,
         }]
     );
 
@@ -4931,8 +6214,7 @@ fn create_migration_poll_config() {
     .unwrap_err();
     assert_eq!(
         err,
-        StdError::generic_err(format!(
-            "Must deposit more than {} token",
+        ContractError::InsufficientProposalDeposit(Uint128::new(
             DEFAULT_MIGRATION_PROPOSAL_DEPOSIT
         ))
     );
@@ -5006,6 +6288,7 @@ fn create_migration_poll_config() {
             admin_action: Some(PollAdminAction::ExecuteMigrations {
                 migrations: vec![("contract0000".to_string(), 0, migration_msg.clone())],
             }),
+            end_hook: None,
         }]
     );
 
@@ -5112,3 +6395,245 @@ fn create_migration_poll_config() {
         vec![attr("action", "execute_poll"), attr("poll_id", "1"),]
     );
 }
+
+#[test]
+fn deposit_reward_carries_rounding_dust() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        voter_weight: Decimal::percent(50),
+        ..init_msg()
+    };
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg)
+        .expect("contract successfully handles InstantiateMsg");
+
+    let env = mock_env_height(0, 10000);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    for _ in 0..3 {
+        let msg = create_poll_msg(
+            "test".to_string(),
+            "test".to_string(),
+            None,
+            None,
+            None,
+            Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+        );
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    }
+
+    // 50 voter rewards split into 3 polls, 2 left as dust
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_COLLECTOR.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {}).unwrap(),
+    });
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+    let response: StateResponse = from_binary(&res).unwrap();
+    assert_eq!(response.pending_voting_rewards, Uint128::from(50u128));
+    assert_eq!(response.reward_dust, Uint128::from(2u128));
+    for poll_id in 1..4u64 {
+        let poll: Poll = poll_read(&deps.storage)
+            .load(&poll_id.to_be_bytes())
+            .unwrap();
+        assert_eq!(poll.voters_reward, Uint128::from(16u128));
+    }
+
+    // 50 new voter rewards plus 2 dust split into 3 polls, 1 left as dust
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+    let response: StateResponse = from_binary(&res).unwrap();
+    assert_eq!(response.pending_voting_rewards, Uint128::from(100u128));
+    assert_eq!(response.reward_dust, Uint128::from(1u128));
+    for poll_id in 1..4u64 {
+        let poll: Poll = poll_read(&deps.storage)
+            .load(&poll_id.to_be_bytes())
+            .unwrap();
+        assert_eq!(poll.voters_reward, Uint128::from(33u128));
+    }
+}
+
+#[test]
+fn fails_deposit_reward_from_non_distributor() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        reward_distributor: Some(TEST_COLLECTOR.to_string()),
+        ..init_msg()
+    };
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg)
+        .expect("contract successfully handles InstantiateMsg");
+
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {}).unwrap(),
+    });
+    match execute(deps.as_mut(), mock_env(), info.clone(), msg) {
+        Err(ContractError::NotRewardDistributor {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_COLLECTOR.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {}).unwrap(),
+    });
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let response: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response.reward_distributor,
+        Some(TEST_COLLECTOR.to_string())
+    );
+}
+
+#[test]
+fn query_staker_reward_debug() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        voter_weight: Decimal::percent(50),
+        ..init_msg()
+    };
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg)
+        .expect("contract successfully handles InstantiateMsg");
+
+    let env = mock_env_height(0, 10000);
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        None,
+        None,
+        Uint128::new(DEFAULT_PROPOSAL_DEPOSIT),
+    );
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let poll_end_time = env.block.time.plus_seconds(DEFAULT_VOTING_PERIOD).seconds();
+
+    let stake_amount = 100u128;
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(stake_amount + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(stake_amount),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(stake_amount),
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info(TEST_VOTER, &[]), msg).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(stake_amount + DEFAULT_PROPOSAL_DEPOSIT + 100u128),
+        )],
+    )]);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_COLLECTOR.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {}).unwrap(),
+    });
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    // rewards are not withdrawable while the poll is in progress
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::StakerRewardDebug {
+            address: TEST_VOTER.to_string(),
+        },
+    )
+    .unwrap();
+    let response: StakerRewardDebugResponse = from_binary(&res).unwrap();
+    assert_eq!(response.pending_voting_rewards, Uint128::zero());
+    assert!(!response.poll_rewards[0].withdrawable);
+
+    let env = mock_env_height(10000, poll_end_time);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    let _res = execute(deps.as_mut(), env, mock_info(TEST_VOTER, &[]), msg).unwrap();
+
+    // deposit is returned to the creator
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(stake_amount + 100u128),
+        )],
+    )]);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::StakerRewardDebug {
+            address: TEST_VOTER.to_string(),
+        },
+    )
+    .unwrap();
+    let response: StakerRewardDebugResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response,
+        StakerRewardDebugResponse {
+            share: Uint128::from(stake_amount),
+            total_share: Uint128::from(stake_amount),
+            total_balance: Uint128::from(stake_amount + 50u128),
+            balance: Uint128::from(stake_amount + 50u128),
+            pending_voting_rewards: Uint128::from(50u128),
+            poll_rewards: vec![PollRewardDebugItem {
+                poll_id: 1u64,
+                status: PollStatus::Passed,
+                vote_balance: Uint128::from(stake_amount),
+                total_votes: Uint128::from(stake_amount),
+                voters_reward: Uint128::from(50u128),
+                reward_amount: Uint128::from(50u128),
+                withdrawable: true,
+            }],
+        }
+    );
+}
+
+#[test]
+fn contract_error_messages() {
+    // typed errors keep the messages clients matched on before
+    assert_eq!(
+        ContractError::PollNotFound {}.to_string(),
+        StdError::generic_err("Poll does not exist").to_string()
+    );
+    assert_eq!(
+        ContractError::PollNotFound {}.to_string(),
+        "Generic error: Poll does not exist"
+    );
+    assert_eq!(
+        ContractError::AlreadyVoted {}.to_string(),
+        "Generic error: User has already voted."
+    );
+    assert_eq!(
+        ContractError::NotEnoughStaked {}.to_string(),
+        "Generic error: User does not have enough staked tokens."
+    );
+    assert_eq!(
+        ContractError::InsufficientProposalDeposit(Uint128::new(100)).to_string(),
+        "Generic error: Must deposit more than 100 token"
+    );
+}
@@ -6,17 +6,26 @@ use crate::state::{
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_binary, Binary, CanonicalAddr, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    attr, to_binary, Addr, Binary, CanonicalAddr, Deps, DepsMut, Env, MessageInfo, Response,
     StdError, StdResult, Uint128,
 };
+use cw2::set_contract_version;
 use mirror_protocol::lock::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, PositionLockInfoResponse, QueryMsg,
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PositionLockInfoResponse, QueryMsg,
 };
+use std::cmp::max;
 use terraswap::{
     asset::{Asset, AssetInfo},
     querier::query_balance,
 };
 
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:mirror-lock";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// one year
+const MAX_LOCKUP_PERIOD: u64 = 31_536_000u64;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -24,6 +33,8 @@ pub fn instantiate(
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
+    assert_lockup_period("lockup_period", msg.lockup_period)?;
+
     let config = Config {
         owner: deps.api.addr_canonicalize(&msg.owner)?,
         mint_contract: deps.api.addr_canonicalize(&msg.mint_contract)?,
@@ -31,6 +42,7 @@ pub fn instantiate(
         lockup_period: msg.lockup_period,
     };
 
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     store_config(deps.storage, &config)?;
     total_locked_funds_store(deps.storage).save(&Uint128::zero())?;
     Ok(Response::default())
@@ -48,10 +60,12 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
         ExecuteMsg::LockPositionFundsHook {
             position_idx,
             receiver,
-        } => lock_position_funds_hook(deps, env, info, position_idx, receiver),
-        ExecuteMsg::UnlockPositionFunds { positions_idx } => {
-            unlock_positions_funds(deps, env, info, positions_idx)
-        }
+            lock_period,
+        } => lock_position_funds_hook(deps, env, info, position_idx, receiver, lock_period),
+        ExecuteMsg::UnlockPositionFunds {
+            positions_idx,
+            recipient,
+        } => unlock_positions_funds(deps, env, info, positions_idx, recipient),
         ExecuteMsg::ReleasePositionFunds { position_idx } => {
             release_position_funds(deps, env, info, position_idx)
         }
@@ -85,6 +99,7 @@ pub fn update_config(
     }
 
     if let Some(lockup_period) = lockup_period {
+        assert_lockup_period("lockup_period", lockup_period)?;
         config.lockup_period = lockup_period;
     }
 
@@ -92,12 +107,24 @@ pub fn update_config(
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
+fn assert_lockup_period(name: &str, lockup_period: u64) -> StdResult<()> {
+    if lockup_period > MAX_LOCKUP_PERIOD {
+        return Err(StdError::generic_err(format!(
+            "{} can not exceed {} seconds",
+            name, MAX_LOCKUP_PERIOD
+        )));
+    }
+
+    Ok(())
+}
+
 pub fn lock_position_funds_hook(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     position_idx: Uint128,
     receiver: String,
+    lock_period: Option<u64>,
 ) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
     let sender_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
@@ -118,7 +145,13 @@ pub fn lock_position_funds_hook(
         return Err(StdError::generic_err("Nothing to lock"));
     }
 
-    let unlock_time: u64 = env.block.time.seconds() + config.lockup_period;
+    // mint can provide a custom lock period, otherwise use the default one
+    if let Some(lock_period) = lock_period {
+        assert_lockup_period("lock_period", lock_period)?;
+    }
+    let lockup_period: u64 = lock_period.unwrap_or(config.lockup_period);
+
+    let unlock_time: u64 = env.block.time.seconds() + lockup_period;
     let receiver_raw: CanonicalAddr = deps.api.addr_canonicalize(&receiver)?;
     let lock_info: PositionLockInfo =
         if let Ok(mut lock_info) = read_position_lock_info(deps.storage, position_idx) {
@@ -129,9 +162,9 @@ pub fn lock_position_funds_hook(
                     "Receiver address do not match with existing record",
                 ));
             }
-            // increase amount
+            // increase amount, a shorter lock period never releases earlier funds sooner
             lock_info.locked_amount += position_locked_amount;
-            lock_info.unlock_time = unlock_time;
+            lock_info.unlock_time = max(lock_info.unlock_time, unlock_time);
             lock_info
         } else {
             PositionLockInfo {
@@ -156,7 +189,7 @@ pub fn lock_position_funds_hook(
             "total_locked_amount",
             lock_info.locked_amount.to_string() + &config.base_denom,
         ),
-        attr("unlock_time", unlock_time.to_string()),
+        attr("unlock_time", lock_info.unlock_time.to_string()),
     ]))
 }
 
@@ -165,6 +198,7 @@ pub fn unlock_positions_funds(
     env: Env,
     info: MessageInfo,
     positions_idx: Vec<Uint128>,
+    recipient: Option<String>,
 ) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
     let sender_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
@@ -211,6 +245,13 @@ pub fn unlock_positions_funds(
             .map_err(StdError::overflow)
     })?;
 
+    // funds are sent to the position owner unless a recipient is provided
+    let recipient: Addr = if let Some(recipient) = recipient {
+        deps.api.addr_validate(&recipient)?
+    } else {
+        info.sender
+    };
+
     let tax_amount: Uint128 = unlock_asset.compute_tax(&deps.querier)?;
 
     Ok(Response::new()
@@ -218,8 +259,9 @@ pub fn unlock_positions_funds(
             attr("action", "unlock_shorting_funds"),
             attr("unlocked_amount", unlock_asset.to_string()),
             attr("tax_amount", tax_amount.to_string() + &config.base_denom),
+            attr("recipient", recipient.to_string()),
         ])
-        .add_message(unlock_asset.into_msg(&deps.querier, info.sender)?))
+        .add_message(unlock_asset.into_msg(&deps.querier, recipient)?))
 }
 
 pub fn release_position_funds(
@@ -314,6 +356,9 @@ pub fn query_position_lock_info(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    // config and position lock infos are kept as they are, only record the new version
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     Ok(Response::default())
 }
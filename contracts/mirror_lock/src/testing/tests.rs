@@ -1,12 +1,13 @@
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query};
 use crate::testing::mock_querier::mock_dependencies;
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
     attr, from_binary, BankMsg, BlockInfo, Coin, CosmosMsg, Decimal, Env, StdError, SubMsg,
     Timestamp, Uint128,
 };
+use cw2::{get_contract_version, ContractVersion};
 use mirror_protocol::lock::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, PositionLockInfoResponse, QueryMsg,
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PositionLockInfoResponse, QueryMsg,
 };
 
 fn mock_env_with_block_time(time: u64) -> Env {
@@ -78,6 +79,20 @@ fn update_config() {
     };
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
     assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    // lockup period above the max
+    let info = mock_info("owner0001", &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        mint_contract: None,
+        base_denom: None,
+        lockup_period: Some(31_536_001u64),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("lockup_period can not exceed 31536000 seconds")
+    );
 }
 
 #[test]
@@ -102,6 +117,7 @@ fn lock_position_funds() {
     let msg = ExecuteMsg::LockPositionFundsHook {
         position_idx: Uint128::from(1u128),
         receiver: "addr0000".to_string(),
+        lock_period: None,
     };
 
     // unauthorized attempt
@@ -148,6 +164,133 @@ fn lock_position_funds() {
     );
 }
 
+#[test]
+fn lock_position_funds_with_custom_period() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        mint_contract: "mint0000".to_string(),
+        base_denom: "uusd".to_string(),
+        lockup_period: 100u64,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_bank_balance(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128), // lock 100uusd
+        }],
+    );
+
+    // lock period above the max
+    let msg = ExecuteMsg::LockPositionFundsHook {
+        position_idx: Uint128::from(1u128),
+        receiver: "addr0000".to_string(),
+        lock_period: Some(31_536_001u64),
+    };
+    let env = mock_env_with_block_time(20u64);
+    let info = mock_info("mint0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("lock_period can not exceed 31536000 seconds")
+    );
+
+    // custom lock period overrides the config one
+    let msg = ExecuteMsg::LockPositionFundsHook {
+        position_idx: Uint128::from(1u128),
+        receiver: "addr0000".to_string(),
+        lock_period: Some(500u64),
+    };
+    let env = mock_env_with_block_time(20u64);
+    let info = mock_info("mint0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "lock_position_funds_hook"),
+            attr("position_idx", "1"),
+            attr("locked_amount", "100uusd"),
+            attr("total_locked_amount", "100uusd"),
+            attr("unlock_time", "520"),
+        ]
+    );
+
+    // query lock info
+    let res: PositionLockInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PositionLockInfo {
+                position_idx: Uint128::from(1u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.unlock_time, 520u64);
+
+    // a shorter lock period on the same position keeps the later unlock time
+    deps.querier.with_bank_balance(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(200u128), // lock 100uusd more
+        }],
+    );
+    let msg = ExecuteMsg::LockPositionFundsHook {
+        position_idx: Uint128::from(1u128),
+        receiver: "addr0000".to_string(),
+        lock_period: Some(10u64),
+    };
+    let env = mock_env_with_block_time(30u64);
+    let info = mock_info("mint0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "lock_position_funds_hook"),
+            attr("position_idx", "1"),
+            attr("locked_amount", "100uusd"),
+            attr("total_locked_amount", "200uusd"),
+            attr("unlock_time", "520"),
+        ]
+    );
+
+    // a longer one extends it
+    deps.querier.with_bank_balance(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(300u128), // lock 100uusd more
+        }],
+    );
+    let msg = ExecuteMsg::LockPositionFundsHook {
+        position_idx: Uint128::from(1u128),
+        receiver: "addr0000".to_string(),
+        lock_period: Some(1000u64),
+    };
+    let env = mock_env_with_block_time(40u64);
+    let info = mock_info("mint0000", &[]);
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let res: PositionLockInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PositionLockInfo {
+                position_idx: Uint128::from(1u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.locked_amount, Uint128::from(300u128));
+    assert_eq!(res.unlock_time, 1040u64);
+}
+
 #[test]
 fn unlock_position_funds() {
     let mut deps = mock_dependencies(&[]);
@@ -168,6 +311,7 @@ fn unlock_position_funds() {
     let msg = ExecuteMsg::LockPositionFundsHook {
         position_idx: Uint128::from(1u128),
         receiver: "addr0000".to_string(),
+        lock_period: None,
     };
     let env = mock_env_with_block_time(1u64);
     let info = mock_info("mint0000", &[]);
@@ -217,6 +361,7 @@ fn unlock_position_funds() {
 
     let msg = ExecuteMsg::UnlockPositionFunds {
         positions_idx: vec![Uint128::from(1u128)],
+        recipient: None,
     };
 
     // unauthorized attempt
@@ -246,6 +391,7 @@ fn unlock_position_funds() {
             attr("action", "unlock_shorting_funds"),
             attr("unlocked_amount", "200uusd"),
             attr("tax_amount", "2uusd"),
+            attr("recipient", "addr0000"),
         ]
     );
     assert_eq!(
@@ -272,6 +418,7 @@ fn unlock_position_funds() {
     let msg = ExecuteMsg::LockPositionFundsHook {
         position_idx: Uint128::from(2u128),
         receiver: "addr0000".to_string(),
+        lock_period: None,
     };
     let env = mock_env_with_block_time(1u64);
     deps.querier.with_bank_balance(
@@ -286,6 +433,7 @@ fn unlock_position_funds() {
     let msg = ExecuteMsg::LockPositionFundsHook {
         position_idx: Uint128::from(3u128),
         receiver: "addr0000".to_string(),
+        lock_period: None,
     };
     let env = mock_env_with_block_time(2u64);
     let info = mock_info("mint0000", &[]);
@@ -301,6 +449,7 @@ fn unlock_position_funds() {
     // unlock both positions
     let msg = ExecuteMsg::UnlockPositionFunds {
         positions_idx: vec![Uint128::from(2u128), Uint128::from(3u128)],
+        recipient: None,
     };
     let env = mock_env_with_block_time(102);
     let info = mock_info("addr0000", &[]);
@@ -311,10 +460,83 @@ fn unlock_position_funds() {
             attr("action", "unlock_shorting_funds"),
             attr("unlocked_amount", "300uusd"),
             attr("tax_amount", "3uusd"),
+            attr("recipient", "addr0000"),
         ]
     );
 }
 
+#[test]
+fn unlock_position_funds_to_recipient() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::percent(1u64),
+        &[(&"uusd".to_string(), &Uint128::from(100000000u128))],
+    );
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        mint_contract: "mint0000".to_string(),
+        base_denom: "uusd".to_string(),
+        lockup_period: 100u64,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // lock 100 UST
+    let msg = ExecuteMsg::LockPositionFundsHook {
+        position_idx: Uint128::from(1u128),
+        receiver: "addr0000".to_string(),
+        lock_period: None,
+    };
+    let env = mock_env_with_block_time(1u64);
+    let info = mock_info("mint0000", &[]);
+    deps.querier.with_bank_balance(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128), // lock 100uusd
+        }],
+    );
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let msg = ExecuteMsg::UnlockPositionFunds {
+        positions_idx: vec![Uint128::from(1u128)],
+        recipient: Some("vault0000".to_string()),
+    };
+
+    // only the position owner can unlock, even with a recipient
+    let env = mock_env_with_block_time(101u64);
+    let info = mock_info("vault0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg.clone()).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("There are no unlockable funds for the provided positions")
+    );
+
+    // unlock 100 UST to the recipient
+    let env = mock_env_with_block_time(101u64);
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "unlock_shorting_funds"),
+            attr("unlocked_amount", "100uusd"),
+            attr("tax_amount", "1uusd"),
+            attr("recipient", "vault0000"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "vault0000".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(99u128), // minus tax
+            }]
+        }))]
+    );
+}
+
 #[test]
 fn release_position_funds() {
     let mut deps = mock_dependencies(&[]);
@@ -335,6 +557,7 @@ fn release_position_funds() {
     let msg = ExecuteMsg::LockPositionFundsHook {
         position_idx: Uint128::from(1u128),
         receiver: "addr0000".to_string(),
+        lock_period: None,
     };
     let env = mock_env_with_block_time(1u64);
     let info = mock_info("mint0000", &[]);
@@ -375,3 +598,65 @@ fn release_position_funds() {
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
     assert_eq!(res.attributes.len(), 0);
 }
+
+#[test]
+fn migrate_keeps_position_lock_infos() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        mint_contract: "mint0000".to_string(),
+        base_denom: "uusd".to_string(),
+        lockup_period: 100u64,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // lock 100 UST
+    let msg = ExecuteMsg::LockPositionFundsHook {
+        position_idx: Uint128::from(1u128),
+        receiver: "addr0000".to_string(),
+        lock_period: None,
+    };
+    let env = mock_env_with_block_time(1u64);
+    let info = mock_info("mint0000", &[]);
+    deps.querier.with_bank_balance(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128), // lock 100uusd
+        }],
+    );
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    assert_eq!(
+        get_contract_version(deps.as_ref().storage).unwrap(),
+        ContractVersion {
+            contract: "crates.io:mirror-lock".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    );
+
+    // lock info survives the migration
+    let res: PositionLockInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PositionLockInfo {
+                position_idx: Uint128::from(1u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        PositionLockInfoResponse {
+            idx: Uint128::from(1u128),
+            receiver: "addr0000".to_string(),
+            locked_amount: Uint128::from(100u128),
+            unlock_time: 101u64,
+        }
+    );
+}
@@ -2,9 +2,11 @@ use std::str::FromStr;
 
 use crate::{
     contract::MIN_CR_ALLOWED,
+    math::decimal_mul_ceil,
+    querier::PRICE_EXPIRE_TIME,
     state::{AssetConfig, Position},
 };
-use cosmwasm_std::{Decimal, Deps, Env, StdError, StdResult};
+use cosmwasm_std::{Decimal, Deps, Env, StdError, StdResult, Uint128};
 use terraswap::asset::{Asset, AssetInfo};
 
 // Check zero balance & same collateral with position
@@ -39,8 +41,10 @@ pub fn assert_migrated_asset(asset_config: &AssetConfig) -> StdResult<()> {
     Ok(())
 }
 
+// suspended collaterals can still be withdrawn, burned against and auctioned,
+// but can not back new positions, deposits or mints
 pub fn assert_revoked_collateral(
-    load_collateral_res: (Decimal, Decimal, bool),
+    load_collateral_res: (Decimal, Decimal, bool, bool),
 ) -> StdResult<(Decimal, Decimal)> {
     if load_collateral_res.2 {
         return Err(StdError::generic_err(
@@ -48,6 +52,12 @@ pub fn assert_revoked_collateral(
         ));
     }
 
+    if load_collateral_res.3 {
+        return Err(StdError::generic_err(
+            "The collateral asset provided is suspended",
+        ));
+    }
+
     Ok((load_collateral_res.0, load_collateral_res.1))
 }
 
@@ -72,6 +82,46 @@ pub fn assert_min_collateral_ratio(min_collateral_ratio: Decimal) -> StdResult<(
     }
 }
 
+pub fn assert_max_initial_ltv(max_initial_ltv: Decimal) -> StdResult<()> {
+    if max_initial_ltv.is_zero() || max_initial_ltv > Decimal::one() {
+        Err(StdError::generic_err(
+            "max_initial_ltv must be greater than 0 and at most 1",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+pub fn assert_price_override(price_override: Decimal) -> StdResult<()> {
+    if price_override.is_zero() {
+        Err(StdError::generic_err(
+            "price_override must be greater than 0",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+// Check the minted asset value stays within the asset's max loan-to-value of the collateral
+pub fn assert_initial_ltv(
+    asset_config: &AssetConfig,
+    asset_amount: Uint128,
+    asset_price_in_collateral: Decimal,
+    collateral_amount: Uint128,
+) -> StdResult<()> {
+    if let Some(max_initial_ltv) = asset_config.max_initial_ltv {
+        if decimal_mul_ceil(asset_amount, asset_price_in_collateral)
+            > collateral_amount * max_initial_ltv
+        {
+            return Err(StdError::generic_err(
+                "Loan-to-value exceeds the max initial ltv of the asset",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 pub fn assert_protocol_fee(protocol_fee_rate: Decimal) -> StdResult<Decimal> {
     if protocol_fee_rate >= Decimal::one() {
         Err(StdError::generic_err(
@@ -82,6 +132,27 @@ pub fn assert_protocol_fee(protocol_fee_rate: Decimal) -> StdResult<Decimal> {
     }
 }
 
+pub fn assert_open_fee_rate(open_fee_rate: Decimal) -> StdResult<Decimal> {
+    if open_fee_rate >= Decimal::one() {
+        Err(StdError::generic_err(
+            "open_fee_rate must be smaller than 1",
+        ))
+    } else {
+        Ok(open_fee_rate)
+    }
+}
+
+pub fn assert_market_close_grace(market_close_grace: u64) -> StdResult<u64> {
+    if market_close_grace > PRICE_EXPIRE_TIME {
+        Err(StdError::generic_err(format!(
+            "market_close_grace can not be greater than {}",
+            PRICE_EXPIRE_TIME
+        )))
+    } else {
+        Ok(market_close_grace)
+    }
+}
+
 pub fn assert_mint_period(env: &Env, asset_config: &AssetConfig) -> StdResult<()> {
     if let Some(ipo_params) = asset_config.ipo_params.clone() {
         if ipo_params.mint_end < env.block.time.seconds() {
@@ -1,13 +1,19 @@
 use crate::{
-    asserts::{assert_auction_discount, assert_min_collateral_ratio, assert_protocol_fee},
-    migration::migrate_asset_configs,
+    asserts::{
+        assert_auction_discount, assert_market_close_grace, assert_max_initial_ltv,
+        assert_migrated_asset, assert_min_collateral_ratio, assert_open_fee_rate,
+        assert_price_override, assert_protocol_fee,
+    },
+    migration::{migrate_asset_configs, migrate_config, migrate_positions_collateral_index},
     positions::{
-        auction, burn, deposit, mint, open_position, query_next_position_idx, query_position,
-        query_positions, withdraw,
+        add_collateral, auction, burn, deposit, mint, open_position, query_liquidation_scan,
+        query_next_position_idx, query_position, query_position_count, query_positions,
+        query_short_positions, set_position_alert, withdraw,
     },
+    querier::{query_collateral_price_info, query_price_info, PRICE_EXPIRE_TIME},
     state::{
-        read_asset_config, read_config, store_asset_config, store_config, store_position_idx,
-        AssetConfig, Config,
+        read_asset_config, read_config, store_asset_config, store_collateral_index_cursor,
+        store_config, store_position_idx, AssetConfig, Config,
     },
 };
 #[cfg(not(feature = "library"))]
@@ -19,10 +25,11 @@ use cosmwasm_std::{
 use cw20::Cw20ReceiveMsg;
 use mirror_protocol::mint::{
     AssetConfigResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, IPOParams, InstantiateMsg,
-    QueryMsg,
+    PriceConsistencyResponse, QueryMsg,
 };
 use mirror_protocol::{
     collateral_oracle::{ExecuteMsg as CollateralOracleExecuteMsg, SourceType},
+    common::OwnerInfo,
     mint::MigrateMsg,
 };
 use terraswap::asset::{Asset, AssetInfo};
@@ -38,6 +45,7 @@ pub fn instantiate(
 ) -> StdResult<Response> {
     let config = Config {
         owner: deps.api.addr_canonicalize(&msg.owner)?,
+        pending_owner: None,
         oracle: deps.api.addr_canonicalize(&msg.oracle)?,
         collector: deps.api.addr_canonicalize(&msg.collector)?,
         collateral_oracle: deps.api.addr_canonicalize(&msg.collateral_oracle)?,
@@ -47,6 +55,11 @@ pub fn instantiate(
         base_denom: msg.base_denom,
         token_code_id: msg.token_code_id,
         protocol_fee_rate: assert_protocol_fee(msg.protocol_fee_rate)?,
+        max_positions_per_owner: msg.max_positions_per_owner,
+        liquidation_incentive: msg.liquidation_incentive.filter(|i| !i.is_zero()),
+        open_fee_rate: assert_open_fee_rate(msg.open_fee_rate)?,
+        market_close_grace: 0,
+        multi_collateral: false,
     };
 
     store_config(deps.storage, &config)?;
@@ -68,6 +81,11 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             token_code_id,
             protocol_fee_rate,
             staking,
+            max_positions_per_owner,
+            liquidation_incentive,
+            open_fee_rate,
+            market_close_grace,
+            multi_collateral,
         } => update_config(
             deps,
             info,
@@ -80,7 +98,14 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             token_code_id,
             protocol_fee_rate,
             staking,
+            max_positions_per_owner,
+            liquidation_incentive,
+            open_fee_rate,
+            market_close_grace,
+            multi_collateral,
         ),
+        ExecuteMsg::ProposeOwner { owner } => propose_owner(deps, info, owner),
+        ExecuteMsg::AcceptOwnership {} => accept_ownership(deps, info),
         ExecuteMsg::UpdateAsset {
             asset_token,
             auction_discount,
@@ -120,10 +145,34 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             let asset_addr = deps.api.addr_validate(asset_token.as_str())?;
             register_migration(deps, info, asset_addr, end_price)
         }
+        ExecuteMsg::SetPriceOverride {
+            asset_token,
+            price_override,
+        } => {
+            let asset_addr = deps.api.addr_validate(asset_token.as_str())?;
+            set_price_override(deps, info, asset_addr, price_override)
+        }
+        ExecuteMsg::SetMaxInitialLtv {
+            asset_token,
+            max_initial_ltv,
+        } => {
+            let asset_addr = deps.api.addr_validate(asset_token.as_str())?;
+            set_max_initial_ltv(deps, info, asset_addr, max_initial_ltv)
+        }
+        ExecuteMsg::SetLockPeriod {
+            asset_token,
+            lock_period,
+        } => {
+            let asset_addr = deps.api.addr_validate(asset_token.as_str())?;
+            set_lock_period(deps, info, asset_addr, lock_period)
+        }
         ExecuteMsg::TriggerIPO { asset_token } => {
             let asset_addr = deps.api.addr_validate(asset_token.as_str())?;
             trigger_ipo(deps, info, asset_addr)
         }
+        ExecuteMsg::MigratePositionsCollateralIndex { limit } => {
+            index_positions_by_collateral(deps, info, limit)
+        }
         ExecuteMsg::OpenPosition {
             collateral,
             asset_info,
@@ -162,6 +211,9 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
 
             deposit(deps, info.sender, position_idx, collateral)
         }
+        ExecuteMsg::AddCollateral { position_idx } => {
+            add_collateral(deps, info.sender, position_idx, info.funds)
+        }
         ExecuteMsg::Withdraw {
             position_idx,
             collateral,
@@ -171,6 +223,10 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             asset,
             short_params,
         } => mint(deps, env, info.sender, position_idx, asset, short_params),
+        ExecuteMsg::SetPositionAlert {
+            position_idx,
+            ratio,
+        } => set_position_alert(deps, info.sender, position_idx, ratio),
     }
 }
 
@@ -208,6 +264,10 @@ pub fn receive_cw20(
             let cw20_sender = deps.api.addr_validate(cw20_msg.sender.as_str())?;
             deposit(deps, cw20_sender, position_idx, passed_asset)
         }
+        Ok(Cw20HookMsg::AddCollateral { position_idx }) => {
+            let cw20_sender = deps.api.addr_validate(cw20_msg.sender.as_str())?;
+            deposit(deps, cw20_sender, position_idx, passed_asset)
+        }
         Ok(Cw20HookMsg::Burn { position_idx }) => {
             let cw20_sender = deps.api.addr_validate(cw20_msg.sender.as_str())?;
             burn(deps, env, cw20_sender, position_idx, passed_asset)
@@ -233,6 +293,11 @@ pub fn update_config(
     token_code_id: Option<u64>,
     protocol_fee_rate: Option<Decimal>,
     staking: Option<String>,
+    max_positions_per_owner: Option<u32>,
+    liquidation_incentive: Option<Uint128>,
+    open_fee_rate: Option<Decimal>,
+    market_close_grace: Option<u64>,
+    multi_collateral: Option<bool>,
 ) -> StdResult<Response> {
     let mut config: Config = read_config(deps.storage)?;
 
@@ -240,8 +305,9 @@ pub fn update_config(
         return Err(StdError::generic_err("unauthorized"));
     }
 
+    // the new owner still has to accept the ownership
     if let Some(owner) = owner {
-        config.owner = deps.api.addr_canonicalize(&owner)?;
+        config.pending_owner = Some(deps.api.addr_canonicalize(&owner)?);
     }
 
     if let Some(oracle) = oracle {
@@ -277,10 +343,62 @@ pub fn update_config(
         config.staking = deps.api.addr_canonicalize(&staking)?;
     }
 
+    if let Some(max_positions_per_owner) = max_positions_per_owner {
+        config.max_positions_per_owner = Some(max_positions_per_owner);
+    }
+
+    if let Some(liquidation_incentive) = liquidation_incentive {
+        config.liquidation_incentive = Some(liquidation_incentive).filter(|i| !i.is_zero());
+    }
+
+    if let Some(open_fee_rate) = open_fee_rate {
+        config.open_fee_rate = assert_open_fee_rate(open_fee_rate)?;
+    }
+
+    if let Some(market_close_grace) = market_close_grace {
+        config.market_close_grace = assert_market_close_grace(market_close_grace)?;
+    }
+
+    if let Some(multi_collateral) = multi_collateral {
+        config.multi_collateral = multi_collateral;
+    }
+
     store_config(deps.storage, &config)?;
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
+pub fn propose_owner(deps: DepsMut, info: MessageInfo, owner: String) -> StdResult<Response> {
+    let mut config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    config.pending_owner = Some(deps.api.addr_canonicalize(&owner)?);
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_owner"),
+        attr("pending_owner", owner),
+    ]))
+}
+
+pub fn accept_ownership(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let mut config: Config = read_config(deps.storage)?;
+    let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if config.pending_owner != Some(sender_raw.clone()) {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    config.owner = sender_raw;
+    config.pending_owner = None;
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "accept_ownership"),
+        attr("owner", info.sender.as_str()),
+    ]))
+}
+
 pub fn update_asset(
     deps: DepsMut,
     info: MessageInfo,
@@ -374,6 +492,9 @@ pub fn register_asset(
             min_collateral_ratio,
             end_price: None,
             ipo_params,
+            price_override: None,
+            max_initial_ltv: None,
+            lock_period: None,
         },
     )?;
 
@@ -432,6 +553,114 @@ pub fn register_migration(
         ]))
 }
 
+pub fn set_price_override(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_token: Addr,
+    price_override: Option<Decimal>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let asset_token_raw = deps.api.addr_canonicalize(asset_token.as_str())?;
+    let mut asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+
+    // the end_price of a migrated asset takes precedence over any override
+    if let Some(price_override) = price_override {
+        assert_price_override(price_override)?;
+        assert_migrated_asset(&asset_config)?;
+    }
+    asset_config.price_override = price_override;
+    store_asset_config(deps.storage, &asset_token_raw, &asset_config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_price_override"),
+        attr("asset_token", asset_token.as_str()),
+        attr(
+            "price_override",
+            price_override.map_or_else(|| "none".to_string(), |p| p.to_string()),
+        ),
+    ]))
+}
+
+pub fn set_max_initial_ltv(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_token: Addr,
+    max_initial_ltv: Option<Decimal>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    if let Some(max_initial_ltv) = max_initial_ltv {
+        assert_max_initial_ltv(max_initial_ltv)?;
+    }
+
+    let asset_token_raw = deps.api.addr_canonicalize(asset_token.as_str())?;
+    let mut asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    asset_config.max_initial_ltv = max_initial_ltv;
+    store_asset_config(deps.storage, &asset_token_raw, &asset_config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_max_initial_ltv"),
+        attr("asset_token", asset_token.as_str()),
+        attr(
+            "max_initial_ltv",
+            max_initial_ltv.map_or_else(|| "none".to_string(), |l| l.to_string()),
+        ),
+    ]))
+}
+
+pub fn set_lock_period(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_token: Addr,
+    lock_period: Option<u64>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    // the lock contract validates the period against its maximum
+    let asset_token_raw = deps.api.addr_canonicalize(asset_token.as_str())?;
+    let mut asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    asset_config.lock_period = lock_period;
+    store_asset_config(deps.storage, &asset_token_raw, &asset_config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_lock_period"),
+        attr("asset_token", asset_token.as_str()),
+        attr(
+            "lock_period",
+            lock_period.map_or_else(|| "none".to_string(), |p| p.to_string()),
+        ),
+    ]))
+}
+
+pub fn index_positions_by_collateral(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let (indexed_count, has_more) = migrate_positions_collateral_index(deps.storage, limit)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate_positions_collateral_index"),
+        attr("indexed_count", indexed_count.to_string()),
+        attr("has_more", has_more.to_string()),
+    ]))
+}
+
 pub fn trigger_ipo(deps: DepsMut, info: MessageInfo, asset_token: Addr) -> StdResult<Response> {
     let config = read_config(deps.storage)?;
     let asset_token_raw: CanonicalAddr = deps.api.addr_canonicalize(asset_token.as_str())?;
@@ -478,14 +707,16 @@ pub fn trigger_ipo(deps: DepsMut, info: MessageInfo, asset_token: Addr) -> StdRe
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Owner {} => to_binary(&query_owner(deps)?),
         QueryMsg::AssetConfig { asset_token } => to_binary(&query_asset_config(deps, asset_token)?),
         QueryMsg::Position { position_idx } => to_binary(&query_position(deps, position_idx)?),
         QueryMsg::Positions {
             owner_addr,
             asset_token,
+            collateral_info,
             start_after,
             limit,
             order_by,
@@ -493,18 +724,64 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             deps,
             owner_addr,
             asset_token,
+            collateral_info,
             start_after,
             limit,
             order_by,
         )?),
         QueryMsg::NextPositionIdx {} => to_binary(&query_next_position_idx(deps)?),
+        QueryMsg::PositionCount { owner_addr } => {
+            to_binary(&query_position_count(deps, owner_addr)?)
+        }
+        QueryMsg::ShortPositions {
+            owner_addr,
+            start_after,
+            limit,
+            order_by,
+        } => to_binary(&query_short_positions(
+            deps,
+            owner_addr,
+            start_after,
+            limit,
+            order_by,
+        )?),
+        QueryMsg::LiquidationScan {
+            asset_token,
+            start_after,
+            limit,
+        } => to_binary(&query_liquidation_scan(
+            deps,
+            asset_token,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::PriceConsistency { asset_token } => {
+            to_binary(&query_price_consistency(deps, env, asset_token)?)
+        }
     }
 }
 
+pub fn query_owner(deps: Deps) -> StdResult<OwnerInfo> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(OwnerInfo {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        pending_owner: config
+            .pending_owner
+            .map(|pending_owner| deps.api.addr_humanize(&pending_owner))
+            .transpose()?
+            .map(|pending_owner| pending_owner.to_string()),
+    })
+}
+
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let state = read_config(deps.storage)?;
     let resp = ConfigResponse {
         owner: deps.api.addr_humanize(&state.owner)?.to_string(),
+        pending_owner: state
+            .pending_owner
+            .map(|pending_owner| deps.api.addr_humanize(&pending_owner))
+            .transpose()?
+            .map(|pending_owner| pending_owner.to_string()),
         oracle: deps.api.addr_humanize(&state.oracle)?.to_string(),
         staking: deps.api.addr_humanize(&state.staking)?.to_string(),
         collector: deps.api.addr_humanize(&state.collector)?.to_string(),
@@ -520,6 +797,11 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         base_denom: state.base_denom,
         token_code_id: state.token_code_id,
         protocol_fee_rate: state.protocol_fee_rate,
+        max_positions_per_owner: state.max_positions_per_owner,
+        liquidation_incentive: state.liquidation_incentive,
+        open_fee_rate: state.open_fee_rate,
+        market_close_grace: state.market_close_grace,
+        multi_collateral: state.multi_collateral,
     };
 
     Ok(resp)
@@ -529,7 +811,8 @@ pub fn query_asset_config(deps: Deps, asset_token: String) -> StdResult<AssetCon
     let asset_config: AssetConfig = read_asset_config(
         deps.storage,
         &deps.api.addr_canonicalize(asset_token.as_str())?,
-    )?;
+    )
+    .map_err(|_| StdError::generic_err(format!("Asset not registered: {}", asset_token)))?;
 
     let resp = AssetConfigResponse {
         token: deps
@@ -541,20 +824,62 @@ pub fn query_asset_config(deps: Deps, asset_token: String) -> StdResult<AssetCon
         min_collateral_ratio: asset_config.min_collateral_ratio,
         end_price: asset_config.end_price,
         ipo_params: asset_config.ipo_params,
+        price_override: asset_config.price_override,
+        max_initial_ltv: asset_config.max_initial_ltv,
+        lock_period: asset_config.lock_period,
     };
 
     Ok(resp)
 }
 
+pub fn query_price_consistency(
+    deps: Deps,
+    env: Env,
+    asset_token: String,
+) -> StdResult<PriceConsistencyResponse> {
+    let config: Config = read_config(deps.storage)?;
+
+    let (oracle_price, oracle_last_updated) = query_price_info(
+        &deps.querier,
+        deps.api.addr_humanize(&config.oracle)?,
+        asset_token.clone(),
+    )?;
+    let (collateral_oracle_price, collateral_oracle_last_updated) = query_collateral_price_info(
+        &deps.querier,
+        deps.api.addr_humanize(&config.collateral_oracle)?,
+        asset_token.clone(),
+    )?;
+
+    let oracle_price_age = env.block.time.seconds().saturating_sub(oracle_last_updated);
+    let collateral_oracle_price_age = env
+        .block
+        .time
+        .seconds()
+        .saturating_sub(collateral_oracle_last_updated);
+    let price_timeframe = PRICE_EXPIRE_TIME + config.market_close_grace;
+
+    Ok(PriceConsistencyResponse {
+        asset_token,
+        oracle_price,
+        oracle_price_age,
+        collateral_oracle_price,
+        collateral_oracle_price_age,
+        oracle_price_stale: oracle_price_age > price_timeframe,
+        collateral_oracle_price_stale: collateral_oracle_price_age > price_timeframe,
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
     // change oracle address to point to new tefi hub
-    let mut config: Config = read_config(deps.storage)?;
-    config.oracle = deps.api.addr_canonicalize(&msg.tefi_oracle_contract)?;
-    store_config(deps.storage, &config)?;
+    let tefi_oracle_raw = deps.api.addr_canonicalize(&msg.tefi_oracle_contract)?;
+    migrate_config(deps.storage, tefi_oracle_raw)?;
 
     // just to check that there are no ipo assets so that the ipo params type can be changed
     migrate_asset_configs(deps.storage)?;
 
+    // existing positions are indexed by collateral in batches, with MigratePositionsCollateralIndex
+    store_collateral_index_cursor(deps.storage, Uint128::zero())?;
+
     Ok(Response::default())
 }
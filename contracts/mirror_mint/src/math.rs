@@ -1,4 +1,4 @@
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Decimal, Fraction, Uint128};
 
 const DECIMAL_FRACTIONAL: Uint128 = Uint128::new(1_000_000_000u128);
 
@@ -31,3 +31,28 @@ pub fn decimal_min(a: Decimal, b: Decimal) -> Decimal {
         b
     }
 }
+
+/// return amount * decimal, rounded up
+pub fn decimal_mul_ceil(amount: Uint128, decimal: Decimal) -> Uint128 {
+    let floor = amount * decimal;
+    if floor.full_mul(decimal.denominator()) < amount.full_mul(decimal.numerator()) {
+        floor + Uint128::new(1u128)
+    } else {
+        floor
+    }
+}
+
+/// return the collateral required to back asset_amount at the min collateral ratio,
+/// rounded up on every step so the check never lets a position sit below the ratio
+pub fn required_collateral(
+    asset_amount: Uint128,
+    asset_price_in_collateral: Decimal,
+    min_collateral_ratio: Decimal,
+    collateral_multiplier: Decimal,
+) -> Uint128 {
+    let asset_value_in_collateral_asset = decimal_mul_ceil(asset_amount, asset_price_in_collateral);
+    decimal_mul_ceil(
+        decimal_mul_ceil(asset_value_in_collateral_asset, min_collateral_ratio),
+        collateral_multiplier,
+    )
+}
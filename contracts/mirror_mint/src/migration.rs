@@ -1,10 +1,34 @@
-use cosmwasm_storage::Bucket;
+use cosmwasm_storage::{
+    singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton, Singleton,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{CanonicalAddr, Decimal, Order, StdError, StdResult, Storage};
 
-use crate::state::{AssetConfig, PREFIX_ASSET_CONFIG};
+use crate::state::{
+    read_collateral_index_cursor, remove_collateral_index_cursor, store_collateral_index_cursor,
+    store_position_collateral_index, AssetConfig, Config, Position, KEY_CONFIG,
+    PREFIX_ASSET_CONFIG, PREFIX_POSITION,
+};
+
+const MAX_MIGRATION_LIMIT: u32 = 100;
+const DEFAULT_MIGRATION_LIMIT: u32 = 30;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub owner: CanonicalAddr,
+    pub oracle: CanonicalAddr,
+    pub collector: CanonicalAddr,
+    pub collateral_oracle: CanonicalAddr,
+    pub staking: CanonicalAddr,
+    pub terraswap_factory: CanonicalAddr,
+    pub lock: CanonicalAddr,
+    pub base_denom: String,
+    pub token_code_id: u64,
+    pub protocol_fee_rate: Decimal,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct LegacyAssetConfig {
     pub token: CanonicalAddr,
@@ -21,6 +45,32 @@ pub struct LegacyIPOParams {
     pub min_collateral_ratio_after_ipo: Decimal,
 }
 
+pub fn migrate_config(storage: &mut dyn Storage, oracle: CanonicalAddr) -> StdResult<()> {
+    let legacy_store: ReadonlySingleton<LegacyConfig> = singleton_read(storage, KEY_CONFIG);
+    let legacy_config: LegacyConfig = legacy_store.load()?;
+    let config = Config {
+        owner: legacy_config.owner,
+        pending_owner: None,
+        oracle,
+        collector: legacy_config.collector,
+        collateral_oracle: legacy_config.collateral_oracle,
+        staking: legacy_config.staking,
+        terraswap_factory: legacy_config.terraswap_factory,
+        lock: legacy_config.lock,
+        base_denom: legacy_config.base_denom,
+        token_code_id: legacy_config.token_code_id,
+        protocol_fee_rate: legacy_config.protocol_fee_rate,
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+        market_close_grace: 0,
+        multi_collateral: false,
+    };
+    let mut store: Singleton<Config> = singleton(storage, KEY_CONFIG);
+    store.save(&config)?;
+    Ok(())
+}
+
 pub fn migrate_asset_configs(storage: &mut dyn Storage) -> StdResult<()> {
     let mut legacy_asset_configs_bucket: Bucket<LegacyAssetConfig> =
         Bucket::new(storage, PREFIX_ASSET_CONFIG);
@@ -50,6 +100,9 @@ pub fn migrate_asset_configs(storage: &mut dyn Storage) -> StdResult<()> {
             min_collateral_ratio: asset_config.min_collateral_ratio,
             end_price: asset_config.end_price,
             ipo_params: None,
+            price_override: None,
+            max_initial_ltv: None,
+            lock_period: None,
         };
         new_asset_configs_bucket.save(asset.as_slice(), new_asset_config)?;
     }
@@ -57,17 +110,113 @@ pub fn migrate_asset_configs(storage: &mut dyn Storage) -> StdResult<()> {
     Ok(())
 }
 
+/// Indexes up to `limit` existing positions by collateral, following the cursor stored by
+/// the contract migration. Returns the number of indexed positions and whether positions
+/// are left for the next call.
+pub fn migrate_positions_collateral_index(
+    storage: &mut dyn Storage,
+    limit: Option<u32>,
+) -> StdResult<(u32, bool)> {
+    let limit = limit
+        .unwrap_or(DEFAULT_MIGRATION_LIMIT)
+        .min(MAX_MIGRATION_LIMIT) as usize;
+    let last_idx = read_collateral_index_cursor(storage)?
+        .ok_or_else(|| StdError::generic_err("Positions are already indexed by collateral"))?;
+
+    let start = if last_idx.is_zero() {
+        None
+    } else {
+        Some((last_idx.u128() + 1).to_be_bytes().to_vec())
+    };
+    let position_bucket: ReadonlyBucket<Position> = ReadonlyBucket::new(storage, PREFIX_POSITION);
+
+    let mut positions: Vec<Position> = vec![];
+    for item in position_bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+    {
+        let (_, position) = item?;
+        positions.push(position);
+    }
+
+    for position in positions.iter() {
+        store_position_collateral_index(storage, position.idx, position)?;
+    }
+
+    let has_more = positions.len() == limit;
+    match positions.last() {
+        Some(position) if has_more => store_collateral_index_cursor(storage, position.idx)?,
+        _ => remove_collateral_index_cursor(storage),
+    }
+
+    Ok((positions.len() as u32, has_more))
+}
+
 #[cfg(test)]
 mod migrate_tests {
-    use crate::state::read_asset_config;
+    use crate::state::{read_asset_config, read_config, read_positions_with_collateral_indexer};
+    use terraswap::asset::{AssetInfoRaw, AssetRaw};
 
     use super::*;
-    use cosmwasm_std::{testing::mock_dependencies, Api};
+    use cosmwasm_std::{testing::mock_dependencies, Api, Uint128};
 
     pub fn asset_configs_old_store(storage: &mut dyn Storage) -> Bucket<LegacyAssetConfig> {
         Bucket::new(storage, PREFIX_ASSET_CONFIG)
     }
 
+    pub fn config_old_store(storage: &mut dyn Storage) -> Singleton<'_, LegacyConfig> {
+        Singleton::new(storage, KEY_CONFIG)
+    }
+
+    #[test]
+    fn test_config_migration() {
+        let mut deps = mock_dependencies(&[]);
+        let mut legacy_config_store = config_old_store(&mut deps.storage);
+        legacy_config_store
+            .save(&LegacyConfig {
+                owner: deps.api.addr_canonicalize("owner0000").unwrap(),
+                oracle: deps.api.addr_canonicalize("oracle0000").unwrap(),
+                collector: deps.api.addr_canonicalize("collector0000").unwrap(),
+                collateral_oracle: deps.api.addr_canonicalize("collateraloracle0000").unwrap(),
+                staking: deps.api.addr_canonicalize("staking0000").unwrap(),
+                terraswap_factory: deps.api.addr_canonicalize("terraswapfactory").unwrap(),
+                lock: deps.api.addr_canonicalize("lock0000").unwrap(),
+                base_denom: "uusd".to_string(),
+                token_code_id: 10u64,
+                protocol_fee_rate: Decimal::percent(1),
+            })
+            .unwrap();
+
+        migrate_config(
+            &mut deps.storage,
+            deps.api.addr_canonicalize("tefioracle0000").unwrap(),
+        )
+        .unwrap();
+
+        let config: Config = read_config(&deps.storage).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                owner: deps.api.addr_canonicalize("owner0000").unwrap(),
+                pending_owner: None,
+                oracle: deps.api.addr_canonicalize("tefioracle0000").unwrap(),
+                collector: deps.api.addr_canonicalize("collector0000").unwrap(),
+                collateral_oracle: deps.api.addr_canonicalize("collateraloracle0000").unwrap(),
+                staking: deps.api.addr_canonicalize("staking0000").unwrap(),
+                terraswap_factory: deps.api.addr_canonicalize("terraswapfactory").unwrap(),
+                lock: deps.api.addr_canonicalize("lock0000").unwrap(),
+                base_denom: "uusd".to_string(),
+                token_code_id: 10u64,
+                protocol_fee_rate: Decimal::percent(1),
+                max_positions_per_owner: None,
+                liquidation_incentive: None,
+                open_fee_rate: Decimal::zero(),
+                market_close_grace: 0,
+                multi_collateral: false,
+            }
+        )
+    }
+
     #[test]
     fn test_asset_configs_migration() {
         let mut deps = mock_dependencies(&[]);
@@ -98,7 +247,76 @@ mod migrate_tests {
                 min_collateral_ratio: asset_config.min_collateral_ratio,
                 end_price: asset_config.end_price,
                 ipo_params: None,
+                price_override: None,
+                max_initial_ltv: None,
+                lock_period: None,
             }
         );
     }
+
+    #[test]
+    fn test_positions_collateral_index_migration() {
+        let mut deps = mock_dependencies(&[]);
+        let collateral_token = deps.api.addr_canonicalize("collateral0000").unwrap();
+        let position = Position {
+            idx: Uint128::from(1u128),
+            owner: deps.api.addr_canonicalize("addr0000").unwrap(),
+            collateral: AssetRaw {
+                info: AssetInfoRaw::Token {
+                    contract_addr: collateral_token.clone(),
+                },
+                amount: Uint128::from(100u128),
+            },
+            asset: AssetRaw {
+                info: AssetInfoRaw::Token {
+                    contract_addr: deps.api.addr_canonicalize("asset0000").unwrap(),
+                },
+                amount: Uint128::from(100u128),
+            },
+        };
+
+        let position_2 = Position {
+            idx: Uint128::from(2u128),
+            ..position.clone()
+        };
+
+        // positions stored before the collateral index existed
+        let mut position_bucket: Bucket<Position> = Bucket::new(&mut deps.storage, PREFIX_POSITION);
+        for position in [&position, &position_2] {
+            position_bucket
+                .save(&position.idx.u128().to_be_bytes(), position)
+                .unwrap();
+        }
+        store_collateral_index_cursor(deps.as_mut().storage, Uint128::zero()).unwrap();
+
+        let res = migrate_positions_collateral_index(deps.as_mut().storage, Some(1)).unwrap();
+        assert_eq!(res, (1, true));
+        let positions = read_positions_with_collateral_indexer(
+            &deps.storage,
+            collateral_token.as_slice(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(positions, vec![position.clone()]);
+
+        let res = migrate_positions_collateral_index(deps.as_mut().storage, None).unwrap();
+        assert_eq!(res, (1, false));
+        let positions = read_positions_with_collateral_indexer(
+            &deps.storage,
+            collateral_token.as_slice(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(positions, vec![position_2, position]);
+
+        let res = migrate_positions_collateral_index(deps.as_mut().storage, None).unwrap_err();
+        assert_eq!(
+            res,
+            StdError::generic_err("Positions are already indexed by collateral")
+        );
+    }
 }
@@ -1,22 +1,30 @@
+use std::collections::HashMap;
+
 use cosmwasm_std::{
-    attr, to_binary, Addr, Attribute, CosmosMsg, Decimal, Deps, DepsMut, Env, Response, StdError,
-    StdResult, Uint128, WasmMsg,
+    attr, to_binary, Addr, Attribute, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, Response,
+    StdError, StdResult, Storage, Uint128, WasmMsg,
 };
 
 use crate::{
     asserts::{
-        assert_asset, assert_burn_period, assert_collateral, assert_migrated_asset,
-        assert_mint_period, assert_pre_ipo_collateral, assert_revoked_collateral,
+        assert_asset, assert_burn_period, assert_collateral, assert_initial_ltv,
+        assert_migrated_asset, assert_mint_period, assert_pre_ipo_collateral,
+        assert_revoked_collateral,
     },
     math::{
-        decimal_division, decimal_min, decimal_multiplication, decimal_subtraction, reverse_decimal,
+        decimal_division, decimal_min, decimal_multiplication, decimal_subtraction,
+        required_collateral, reverse_decimal,
     },
     querier::{load_asset_price, load_collateral_info},
     state::{
-        create_position, is_short_position, read_asset_config, read_config, read_position,
-        read_position_idx, read_positions, read_positions_with_asset_indexer,
-        read_positions_with_user_indexer, remove_position, store_position, store_position_idx,
-        store_short_position, AssetConfig, Config, Position,
+        count_positions_with_user_indexer, create_position, is_short_position, read_asset_config,
+        read_collateral_index_cursor, read_config, read_position, read_position_alert,
+        read_position_extra_collaterals, read_position_idx, read_positions,
+        read_positions_with_asset_indexer, read_positions_with_collateral_indexer,
+        read_positions_with_user_indexer, read_short_positions_with_user_indexer, remove_position,
+        remove_position_alert, remove_position_collateral_index, store_position,
+        store_position_alert, store_position_collateral_index, store_position_extra_collaterals,
+        store_position_idx, store_short_position, AssetConfig, Config, Position,
     },
 };
 
@@ -24,7 +32,10 @@ use cw20::Cw20ExecuteMsg;
 use mirror_protocol::{
     common::OrderBy,
     lock::ExecuteMsg as LockExecuteMsg,
-    mint::{NextPositionIdxResponse, PositionResponse, PositionsResponse, ShortParams},
+    mint::{
+        LiquidationScanItem, LiquidationScanResponse, NextPositionIdxResponse,
+        PositionCountResponse, PositionResponse, PositionsResponse, ShortParams,
+    },
     staking::ExecuteMsg as StakingExecuteMsg,
 };
 use terraswap::{
@@ -33,6 +44,9 @@ use terraswap::{
     querier::query_pair_info,
 };
 
+// maximum number of collaterals a multi-collateral position can hold
+const MAX_POSITION_COLLATERALS: usize = 5;
+
 pub fn open_position(
     deps: DepsMut,
     env: Env,
@@ -47,6 +61,25 @@ pub fn open_position(
         return Err(StdError::generic_err("Wrong collateral"));
     }
 
+    // open fee is taken from the deposited collateral
+    let open_fee = Asset {
+        info: collateral.info.clone(),
+        amount: collateral.amount * config.open_fee_rate,
+    };
+    let collateral = Asset {
+        info: collateral.info,
+        amount: collateral.amount.checked_sub(open_fee.amount)?,
+    };
+
+    let owner_raw = deps.api.addr_canonicalize(sender.as_str())?;
+    if let Some(max_positions_per_owner) = config.max_positions_per_owner {
+        if count_positions_with_user_indexer(deps.storage, &owner_raw) >= max_positions_per_owner {
+            return Err(StdError::generic_err(
+                "Owner has reached the maximum number of positions",
+            ));
+        }
+    }
+
     // assert the collateral is listed and has not been migrated/revoked
     let collateral_info_raw: AssetInfoRaw = collateral.info.to_raw(deps.api)?;
     let collateral_oracle: Addr = deps.api.addr_humanize(&config.collateral_oracle)?;
@@ -88,6 +121,13 @@ pub fn open_position(
         return Err(StdError::generic_err("collateral is too small"));
     }
 
+    assert_initial_ltv(
+        &asset_config,
+        mint_amount,
+        decimal_division(asset_price, collateral_price),
+        collateral.amount,
+    )?;
+
     let position_idx = read_position_idx(deps.storage)?;
     let asset_info_raw = asset_info.to_raw(deps.api)?;
 
@@ -96,7 +136,7 @@ pub fn open_position(
         position_idx,
         &Position {
             idx: position_idx,
-            owner: deps.api.addr_canonicalize(sender.as_str())?,
+            owner: owner_raw,
             collateral: AssetRaw {
                 amount: collateral.amount,
                 info: collateral_info_raw,
@@ -108,12 +148,17 @@ pub fn open_position(
         },
     )?;
 
+    // positions opened while multi_collateral is enabled can take other collaterals later on
+    if config.multi_collateral {
+        store_position_extra_collaterals(deps.storage, position_idx, &[])?;
+    }
+
     // If the short_params exists, the position is
     // flagged as short position. so if want to make short position,
     // the one must pass at least empty {} as short_params
     let is_short: bool;
     let asset_token = deps.api.addr_humanize(&asset_config.token)?.to_string();
-    let messages: Vec<CosmosMsg> = if let Some(short_params) = short_params {
+    let mut messages: Vec<CosmosMsg> = if let Some(short_params) = short_params {
         is_short = true;
         store_short_position(deps.storage, position_idx)?;
 
@@ -163,6 +208,7 @@ pub fn open_position(
                 msg: to_binary(&LockExecuteMsg::LockPositionFundsHook {
                     position_idx,
                     receiver: sender.to_string(),
+                    lock_period: asset_config.lock_period,
                 })?,
             }),
             CosmosMsg::Wasm(WasmMsg::Execute {
@@ -187,18 +233,26 @@ pub fn open_position(
         })]
     };
 
+    let mut attributes = vec![
+        attr("action", "open_position"),
+        attr("position_idx", position_idx.to_string()),
+        attr(
+            "mint_amount",
+            mint_amount.to_string() + &asset_info.to_string(),
+        ),
+        attr("collateral_amount", collateral.to_string()),
+        attr("is_short", is_short.to_string()),
+    ];
+
+    if !open_fee.amount.is_zero() {
+        attributes.push(attr("open_fee", open_fee.to_string()));
+        messages
+            .push(open_fee.into_msg(&deps.querier, deps.api.addr_humanize(&config.collector)?)?);
+    }
+
     store_position_idx(deps.storage, position_idx + Uint128::from(1u128))?;
     Ok(Response::new()
-        .add_attributes(vec![
-            attr("action", "open_position"),
-            attr("position_idx", position_idx.to_string()),
-            attr(
-                "mint_amount",
-                mint_amount.to_string() + &asset_info.to_string(),
-            ),
-            attr("collateral_amount", collateral.to_string()),
-            attr("is_short", is_short.to_string()),
-        ])
+        .add_attributes(attributes)
         .add_messages(messages))
 }
 
@@ -215,31 +269,71 @@ pub fn deposit(
         return Err(StdError::generic_err("unauthorized"));
     }
 
-    // Check the given collateral has same asset info
-    // with position's collateral token
-    // also Check the collateral amount is non-zero
-    assert_collateral(deps.as_ref(), &position, &collateral)?;
+    // multi-collateral positions also take other collaterals, held besides the position's one
+    let extra_collaterals = read_position_extra_collaterals(deps.storage, position_idx)?;
+    let is_extra_collateral = extra_collaterals.is_some()
+        && !collateral
+            .info
+            .equal(&position.collateral.info.to_normal(deps.api)?);
+    if is_extra_collateral {
+        if collateral.amount.is_zero() {
+            return Err(StdError::generic_err("Wrong collateral"));
+        }
+    } else {
+        // Check the given collateral has same asset info
+        // with position's collateral token
+        // also Check the collateral amount is non-zero
+        assert_collateral(deps.as_ref(), &position, &collateral)?;
+    }
 
     // assert the collateral is listed and has not been migrated/revoked
+    let collateral_info_raw: AssetInfoRaw = collateral.info.to_raw(deps.api)?;
     let collateral_oracle: Addr = deps.api.addr_humanize(&config.collateral_oracle)?;
     assert_revoked_collateral(load_collateral_info(
         deps.as_ref(),
         collateral_oracle,
-        &position.collateral.info,
+        &collateral_info_raw,
         false,
     )?)?;
 
     // assert asset migrated
-    match position.asset.info.clone() {
-        AssetInfoRaw::Token { contract_addr } => {
-            assert_migrated_asset(&read_asset_config(deps.storage, &contract_addr)?)?
-        }
+    let asset_config: AssetConfig = match position.asset.info.clone() {
+        AssetInfoRaw::Token { contract_addr } => read_asset_config(deps.storage, &contract_addr)?,
         _ => panic!("DO NOT ENTER HERE"),
     };
+    assert_migrated_asset(&asset_config)?;
 
-    // Increase collateral amount
-    position.collateral.amount += collateral.amount;
-    store_position(deps.storage, position_idx, &position)?;
+    match extra_collaterals {
+        Some(mut extra_collaterals) if is_extra_collateral => {
+            assert_pre_ipo_collateral(config.base_denom, &asset_config, &collateral.info)?;
+
+            match extra_collaterals
+                .iter_mut()
+                .find(|extra_collateral| extra_collateral.info == collateral_info_raw)
+            {
+                Some(extra_collateral) => extra_collateral.amount += collateral.amount,
+                None => {
+                    if extra_collaterals.len() + 1 >= MAX_POSITION_COLLATERALS {
+                        return Err(StdError::generic_err(format!(
+                            "A position can not hold more than {} collaterals",
+                            MAX_POSITION_COLLATERALS
+                        )));
+                    }
+
+                    extra_collaterals.push(AssetRaw {
+                        info: collateral_info_raw,
+                        amount: collateral.amount,
+                    });
+                }
+            }
+            store_position_extra_collaterals(deps.storage, position_idx, &extra_collaterals)?;
+        }
+        _ => {
+            // Increase collateral amount
+            position.collateral.amount += collateral.amount;
+            store_position(deps.storage, position_idx, &position)?;
+        }
+    }
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "deposit"),
@@ -248,6 +342,48 @@ pub fn deposit(
     ]))
 }
 
+pub fn add_collateral(
+    deps: DepsMut,
+    sender: Addr,
+    position_idx: Uint128,
+    funds: Vec<Coin>,
+) -> StdResult<Response> {
+    let position: Position = read_position(deps.storage, position_idx)?;
+    let collateral_info: AssetInfo = position.collateral.info.to_normal(deps.api)?;
+
+    // multi-collateral positions take any native collateral
+    let collateral_info: AssetInfo = match funds.first() {
+        Some(coin) if read_position_extra_collaterals(deps.storage, position_idx)?.is_some() => {
+            AssetInfo::NativeToken {
+                denom: coin.denom.clone(),
+            }
+        }
+        _ => collateral_info,
+    };
+
+    // token collaterals are added through the cw20 receive hook
+    let denom = match &collateral_info {
+        AssetInfo::NativeToken { denom } => denom,
+        AssetInfo::Token { .. } => {
+            return Err(StdError::generic_err(
+                "Token collateral must be sent through the cw20 hook",
+            ))
+        }
+    };
+
+    // only the position's collateral denom can be attached
+    if funds.len() != 1 || &funds[0].denom != denom {
+        return Err(StdError::generic_err("Wrong collateral"));
+    }
+
+    let collateral = Asset {
+        info: collateral_info,
+        amount: funds[0].amount,
+    };
+
+    deposit(deps, sender, position_idx, collateral)
+}
+
 pub fn withdraw(
     deps: DepsMut,
     sender: Addr,
@@ -261,22 +397,71 @@ pub fn withdraw(
         return Err(StdError::generic_err("unauthorized"));
     }
 
-    // if collateral is not provided, withraw all collateral
-    let collateral: Asset = if let Some(collateral) = collateral {
-        // Check the given collateral has same asset info
-        // with position's collateral token
-        // also Check the collateral amount is non-zero
-        assert_collateral(deps.as_ref(), &position, &collateral)?;
+    let is_multi_collateral: bool;
+    let mut extra_collaterals: Vec<AssetRaw> =
+        match read_position_extra_collaterals(deps.storage, position_idx)? {
+            Some(extra_collaterals) => {
+                is_multi_collateral = true;
+                extra_collaterals
+            }
+            None => {
+                is_multi_collateral = false;
+                vec![]
+            }
+        };
 
-        if position.collateral.amount < collateral.amount {
-            return Err(StdError::generic_err(
-                "Cannot withdraw more than you provide",
-            ));
+    // if collateral is not provided, withraw all collaterals
+    let withdraw_collaterals: Vec<Asset> = if let Some(collateral) = collateral {
+        let collateral_info_raw: AssetInfoRaw = collateral.info.to_raw(deps.api)?;
+        match extra_collaterals
+            .iter()
+            .position(|extra_collateral| extra_collateral.info == collateral_info_raw)
+        {
+            Some(extra_idx) => {
+                if collateral.amount.is_zero() {
+                    return Err(StdError::generic_err("Wrong collateral"));
+                }
+
+                if extra_collaterals[extra_idx].amount < collateral.amount {
+                    return Err(StdError::generic_err(
+                        "Cannot withdraw more than you provide",
+                    ));
+                }
+
+                extra_collaterals[extra_idx].amount = extra_collaterals[extra_idx]
+                    .amount
+                    .checked_sub(collateral.amount)?;
+                if extra_collaterals[extra_idx].amount.is_zero() {
+                    extra_collaterals.remove(extra_idx);
+                }
+            }
+            None => {
+                // Check the given collateral has same asset info
+                // with position's collateral token
+                // also Check the collateral amount is non-zero
+                assert_collateral(deps.as_ref(), &position, &collateral)?;
+
+                if position.collateral.amount < collateral.amount {
+                    return Err(StdError::generic_err(
+                        "Cannot withdraw more than you provide",
+                    ));
+                }
+
+                // Compute new collateral amount
+                position.collateral.amount =
+                    position.collateral.amount.checked_sub(collateral.amount)?;
+            }
         }
 
-        collateral
+        vec![collateral]
     } else {
-        position.collateral.to_normal(deps.api)?
+        let mut withdraw_collaterals: Vec<Asset> = vec![position.collateral.to_normal(deps.api)?];
+        for extra_collateral in extra_collaterals.drain(..) {
+            withdraw_collaterals.push(extra_collateral.to_normal(deps.api)?);
+        }
+
+        position.collateral.amount = Uint128::zero();
+        withdraw_collaterals
     };
 
     let asset_token_raw = match position.asset.info.clone() {
@@ -290,29 +475,44 @@ pub fn withdraw(
 
     // Fetch collateral info from collateral oracle
     let collateral_oracle: Addr = deps.api.addr_humanize(&config.collateral_oracle)?;
-    let (collateral_price, mut collateral_multiplier, _collateral_is_revoked) =
-        load_collateral_info(
-            deps.as_ref(),
-            collateral_oracle,
-            &position.collateral.info,
-            true,
-        )?;
+    let (
+        collateral_price,
+        mut collateral_multiplier,
+        _collateral_is_revoked,
+        _collateral_is_suspended,
+    ) = load_collateral_info(
+        deps.as_ref(),
+        collateral_oracle,
+        &position.collateral.info,
+        true,
+    )?;
 
     // ignore multiplier for delisted assets
     if asset_config.end_price.is_some() {
         collateral_multiplier = Decimal::one();
     }
 
-    // Compute new collateral amount
-    let collateral_amount: Uint128 = position.collateral.amount.checked_sub(collateral.amount)?;
-
-    // Convert asset to collateral unit
-    let asset_value_in_collateral_asset: Uint128 =
-        position.asset.amount * decimal_division(asset_price, collateral_price);
+    let collateral_amount: Uint128 = total_collateral_amount(
+        deps.as_ref(),
+        &config,
+        position.collateral.amount,
+        &extra_collaterals,
+        collateral_price,
+        if asset_config.end_price.is_some() {
+            None
+        } else {
+            Some(collateral_multiplier)
+        },
+        false,
+    )?;
 
     // Check minimum collateral ratio is satisfied
-    if asset_value_in_collateral_asset * asset_config.min_collateral_ratio * collateral_multiplier
-        > collateral_amount
+    if required_collateral(
+        position.asset.amount,
+        decimal_division(asset_price, collateral_price),
+        asset_config.min_collateral_ratio,
+        collateral_multiplier,
+    ) > collateral_amount
     {
         return Err(StdError::generic_err(
             "Cannot withdraw collateral over than minimum collateral ratio",
@@ -321,8 +521,10 @@ pub fn withdraw(
 
     let mut messages: Vec<CosmosMsg> = vec![];
 
-    position.collateral.amount = collateral_amount;
-    if position.collateral.amount == Uint128::zero() && position.asset.amount == Uint128::zero() {
+    if position.collateral.amount == Uint128::zero()
+        && position.asset.amount == Uint128::zero()
+        && extra_collaterals.is_empty()
+    {
         // if it is a short position, release locked funds
         if is_short_position(deps.storage, position_idx)? {
             messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -333,28 +535,33 @@ pub fn withdraw(
         }
         remove_position(deps.storage, position_idx)?;
     } else {
+        promote_extra_collateral(deps.storage, &mut position, &mut extra_collaterals)?;
         store_position(deps.storage, position_idx, &position)?;
+        if is_multi_collateral {
+            store_position_extra_collaterals(deps.storage, position_idx, &extra_collaterals)?;
+        }
     }
 
     // Compute tax amount
-    let tax_amount = collateral.compute_tax(&deps.querier)?;
+    let mut withdraw_messages: Vec<CosmosMsg> = vec![];
+    let mut tax_amounts: Vec<String> = vec![];
+    for collateral in withdraw_collaterals.iter() {
+        let tax_amount = collateral.compute_tax(&deps.querier)?;
+        tax_amounts.push(tax_amount.to_string() + &collateral.info.to_string());
+        withdraw_messages.push(
+            collateral
+                .clone()
+                .into_msg(&deps.querier, position_owner.clone())?,
+        );
+    }
 
     Ok(Response::new()
-        .add_messages(
-            vec![
-                vec![collateral.clone().into_msg(&deps.querier, position_owner)?],
-                messages,
-            ]
-            .concat(),
-        )
+        .add_messages([withdraw_messages, messages].concat())
         .add_attributes(vec![
             attr("action", "withdraw"),
             attr("position_idx", position_idx.to_string()),
-            attr("withdraw_amount", collateral.to_string()),
-            attr(
-                "tax_amount",
-                tax_amount.to_string() + &collateral.info.to_string(),
-            ),
+            attr("withdraw_amount", join_assets(&withdraw_collaterals)),
+            attr("tax_amount", tax_amounts.join(",")),
         ]))
 }
 
@@ -405,19 +612,39 @@ pub fn mint(
     // Compute new asset amount
     let asset_amount: Uint128 = mint_amount + position.asset.amount;
 
-    // Convert asset to collateral unit
-    let asset_value_in_collateral_asset: Uint128 =
-        asset_amount * decimal_division(asset_price, collateral_price);
+    // extra collaterals back the new exposure too, so they must still be valid
+    let extra_collaterals: Vec<AssetRaw> =
+        read_position_extra_collaterals(deps.storage, position_idx)?.unwrap_or_default();
+    let collateral_amount: Uint128 = total_collateral_amount(
+        deps.as_ref(),
+        &config,
+        position.collateral.amount,
+        &extra_collaterals,
+        collateral_price,
+        Some(collateral_multiplier),
+        true,
+    )?;
 
     // Check minimum collateral ratio is satisfied
-    if asset_value_in_collateral_asset * asset_config.min_collateral_ratio * collateral_multiplier
-        > position.collateral.amount
+    if required_collateral(
+        asset_amount,
+        decimal_division(asset_price, collateral_price),
+        asset_config.min_collateral_ratio,
+        collateral_multiplier,
+    ) > collateral_amount
     {
         return Err(StdError::generic_err(
             "Cannot mint asset over than min collateral ratio",
         ));
     }
 
+    assert_initial_ltv(
+        &asset_config,
+        asset_amount,
+        decimal_division(asset_price, collateral_price),
+        collateral_amount,
+    )?;
+
     position.asset.amount += mint_amount;
     store_position(deps.storage, position_idx, &position)?;
 
@@ -483,6 +710,7 @@ pub fn mint(
                 msg: to_binary(&LockExecuteMsg::LockPositionFundsHook {
                     position_idx,
                     receiver: position_owner.to_string(),
+                    lock_period: asset_config.lock_period,
                 })?,
             }),
             CosmosMsg::Wasm(WasmMsg::Execute {
@@ -557,7 +785,12 @@ pub fn burn(
 
     // fetch collateral info from collateral oracle
     let collateral_oracle: Addr = deps.api.addr_humanize(&config.collateral_oracle)?;
-    let (collateral_price, _collateral_multiplier, _collateral_is_revoked) = load_collateral_info(
+    let (
+        collateral_price,
+        _collateral_multiplier,
+        _collateral_is_revoked,
+        _collateral_is_suspended,
+    ) = load_collateral_info(
         deps.as_ref(),
         collateral_oracle,
         &position.collateral.info,
@@ -568,7 +801,98 @@ pub fn burn(
     // anyone can execute burn the asset to any position without permission
     let mut close_position: bool = false;
 
-    if let Some(end_price) = asset_config.end_price {
+    let mut extra_collaterals: Vec<AssetRaw> =
+        read_position_extra_collaterals(deps.storage, position_idx)?.unwrap_or_default();
+    if let (Some(end_price), false) = (asset_config.end_price, extra_collaterals.is_empty()) {
+        let collateral_price_in_asset = decimal_division(end_price, collateral_price);
+
+        // Burn deprecated asset to receive every collateral of the position back pro rata,
+        // valued at their prices without multipliers
+        let collateral_amount: Uint128 = total_collateral_amount(
+            deps.as_ref(),
+            &config,
+            position.collateral.amount,
+            &extra_collaterals,
+            collateral_price,
+            None,
+            false,
+        )?;
+        let refund_ratio: Decimal = if collateral_amount.is_zero() {
+            Decimal::zero()
+        } else {
+            decimal_min(
+                Decimal::from_ratio(burn_amount * collateral_price_in_asset, collateral_amount),
+                Decimal::from_ratio(burn_amount, position.asset.amount),
+            )
+        };
+
+        let mut refund_collaterals: Vec<Asset> = vec![];
+        let mut protocol_fees: Vec<Asset> = vec![];
+        for collateral in
+            std::iter::once(&mut position.collateral).chain(extra_collaterals.iter_mut())
+        {
+            let refund_amount = collateral.amount * refund_ratio;
+            collateral.amount = collateral.amount.checked_sub(refund_amount)?;
+
+            // Subtract protocol fee from refunded collateral
+            let collateral_info: AssetInfo = collateral.info.to_normal(deps.api)?;
+            let protocol_fee = Asset {
+                info: collateral_info.clone(),
+                amount: refund_amount * config.protocol_fee_rate,
+            };
+            refund_collaterals.push(Asset {
+                info: collateral_info,
+                amount: refund_amount.checked_sub(protocol_fee.amount)?,
+            });
+            protocol_fees.push(protocol_fee);
+        }
+
+        position.asset.amount = position.asset.amount.checked_sub(burn_amount).unwrap();
+
+        // due to rounding, include 1
+        if position.asset.amount == Uint128::zero()
+            && position.collateral.amount <= Uint128::from(1u128)
+            && extra_collaterals
+                .iter()
+                .all(|extra_collateral| extra_collateral.amount <= Uint128::from(1u128))
+        {
+            close_position = true;
+            remove_position(deps.storage, position_idx)?;
+        } else {
+            extra_collaterals.retain(|extra_collateral| !extra_collateral.amount.is_zero());
+            promote_extra_collateral(deps.storage, &mut position, &mut extra_collaterals)?;
+            store_position(deps.storage, position_idx, &position)?;
+            store_position_extra_collaterals(deps.storage, position_idx, &extra_collaterals)?;
+        }
+
+        let collector: Addr = deps.api.addr_humanize(&config.collector)?;
+        for protocol_fee in protocol_fees.iter() {
+            if !protocol_fee.amount.is_zero() {
+                messages.push(
+                    protocol_fee
+                        .clone()
+                        .into_msg(&deps.querier, collector.clone())?,
+                );
+            }
+        }
+        attributes.push(attr("protocol_fee", join_assets(&protocol_fees)));
+
+        // Refund collateral msgs
+        for refund_collateral in refund_collaterals.iter() {
+            if !refund_collateral.amount.is_zero() {
+                messages.push(
+                    refund_collateral
+                        .clone()
+                        .into_msg(&deps.querier, sender.clone())?,
+                );
+            }
+        }
+
+        attributes.push(attr(
+            "refund_collateral_amount",
+            join_assets(&refund_collaterals),
+        ));
+    } else if let Some(end_price) = asset_config.end_price {
         let asset_price: Decimal = end_price;
 
         let collateral_price_in_asset = decimal_division(asset_price, collateral_price);
@@ -742,22 +1066,48 @@ pub fn auction(
 
     // fetch collateral info from collateral oracle
     let collateral_oracle: Addr = deps.api.addr_humanize(&config.collateral_oracle)?;
-    let (collateral_price, collateral_multiplier, _collateral_is_revoked) = load_collateral_info(
-        deps.as_ref(),
-        collateral_oracle,
-        &position.collateral.info,
-        true,
-    )?;
+    let (collateral_price, collateral_multiplier, _collateral_is_revoked, _collateral_is_suspended) =
+        load_collateral_info(
+            deps.as_ref(),
+            collateral_oracle,
+            &position.collateral.info,
+            true,
+        )?;
 
     // Compute collateral price in asset unit
     let collateral_price_in_asset: Decimal = decimal_division(asset_price, collateral_price);
 
+    // only the position's collateral is sold, extra collaterals take its place once used up
+    let is_multi_collateral: bool;
+    let mut extra_collaterals: Vec<AssetRaw> =
+        match read_position_extra_collaterals(deps.storage, position_idx)? {
+            Some(extra_collaterals) => {
+                is_multi_collateral = true;
+                extra_collaterals
+            }
+            None => {
+                is_multi_collateral = false;
+                vec![]
+            }
+        };
+    let collateral_amount: Uint128 = total_collateral_amount(
+        deps.as_ref(),
+        &config,
+        position.collateral.amount,
+        &extra_collaterals,
+        collateral_price,
+        Some(collateral_multiplier),
+        false,
+    )?;
+
     // Check the position is in auction state
     // asset_amount * price_to_collateral * auction_threshold > collateral_amount
-    let asset_value_in_collateral_asset: Uint128 =
-        position.asset.amount * collateral_price_in_asset;
-    if asset_value_in_collateral_asset * asset_config.min_collateral_ratio * collateral_multiplier
-        < position.collateral.amount
+    if required_collateral(
+        position.asset.amount,
+        collateral_price_in_asset,
+        asset_config.min_collateral_ratio,
+        collateral_multiplier,
+    ) < collateral_amount
     {
         return Err(StdError::generic_err(
             "Cannot liquidate a safely collateralized position",
@@ -810,17 +1160,33 @@ pub fn auction(
         .amount
         .checked_sub(liquidated_asset_amount)
         .unwrap();
-    let left_collateral_amount = position
+    let mut left_collateral_amount = position
         .collateral
         .amount
         .checked_sub(return_collateral_amount)
         .unwrap();
 
+    // flat incentive for the liquidator, paid from the position's remaining collateral.
+    // While debt is left, it is only paid if some collateral still backs the position,
+    // otherwise the position would be closed with its debt forgiven
+    let liquidation_incentive = config.liquidation_incentive.unwrap_or_default();
+    let liquidation_incentive = if left_asset_amount.is_zero() || !extra_collaterals.is_empty() {
+        std::cmp::min(liquidation_incentive, left_collateral_amount)
+    } else if liquidation_incentive < left_collateral_amount {
+        liquidation_incentive
+    } else {
+        Uint128::zero()
+    };
+    left_collateral_amount = left_collateral_amount
+        .checked_sub(liquidation_incentive)
+        .unwrap();
+    let return_collateral_amount = return_collateral_amount + liquidation_incentive;
+
     // Check if it is a short position
     let is_short_position: bool = is_short_position(deps.storage, position_idx)?;
 
     let mut close_position: bool = false;
-    if left_collateral_amount.is_zero() {
+    if left_collateral_amount.is_zero() && extra_collaterals.is_empty() {
         // all collaterals are sold out
         close_position = true;
         remove_position(deps.storage, position_idx)?;
@@ -835,12 +1201,25 @@ pub fn auction(
             amount: left_collateral_amount,
         };
 
-        messages.push(refund_collateral.into_msg(&deps.querier, position_owner.clone())?);
+        if !refund_collateral.amount.is_zero() {
+            messages.push(refund_collateral.into_msg(&deps.querier, position_owner.clone())?);
+        }
+        for extra_collateral in extra_collaterals.iter() {
+            messages.push(
+                extra_collateral
+                    .to_normal(deps.api)?
+                    .into_msg(&deps.querier, position_owner.clone())?,
+            );
+        }
     } else {
         position.collateral.amount = left_collateral_amount;
         position.asset.amount = left_asset_amount;
 
+        promote_extra_collateral(deps.storage, &mut position, &mut extra_collaterals)?;
         store_position(deps.storage, position_idx, &position)?;
+        if is_multi_collateral {
+            store_position_extra_collaterals(deps.storage, position_idx, &extra_collaterals)?;
+        }
     }
 
     // token burn message
@@ -918,17 +1297,54 @@ pub fn auction(
             "protocol_fee",
             protocol_fee.to_string() + &collateral_info_str,
         ),
+        attr(
+            "liquidation_incentive",
+            liquidation_incentive.to_string() + &collateral_info_str,
+        ),
+    ]))
+}
+
+pub fn set_position_alert(
+    deps: DepsMut,
+    sender: Addr,
+    position_idx: Uint128,
+    ratio: Option<Decimal>,
+) -> StdResult<Response> {
+    let position: Position = read_position(deps.storage, position_idx)?;
+    if sender != deps.api.addr_humanize(&position.owner)? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    match ratio {
+        Some(ratio) if ratio.is_zero() => {
+            return Err(StdError::generic_err("Alert ratio must be greater than 0"));
+        }
+        Some(ratio) => store_position_alert(deps.storage, position_idx, ratio)?,
+        None => remove_position_alert(deps.storage, position_idx),
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_position_alert"),
+        attr("position_idx", position_idx.to_string()),
+        attr(
+            "ratio",
+            ratio.map_or_else(|| "none".to_string(), |r| r.to_string()),
+        ),
     ]))
 }
 
 pub fn query_position(deps: Deps, position_idx: Uint128) -> StdResult<PositionResponse> {
+    let config: Config = read_config(deps.storage)?;
     let position: Position = read_position(deps.storage, position_idx)?;
     let resp = PositionResponse {
         idx: position.idx,
         owner: deps.api.addr_humanize(&position.owner)?.to_string(),
         collateral: position.collateral.to_normal(deps.api)?,
+        extra_collaterals: query_extra_collaterals(deps, position.idx)?,
         asset: position.asset.to_normal(deps.api)?,
         is_short: is_short_position(deps.storage, position.idx)?,
+        liquidatable: is_liquidatable(deps, &config, &position)?,
+        alert_ratio: read_position_alert(deps.storage, position.idx)?,
     };
 
     Ok(resp)
@@ -938,6 +1354,7 @@ pub fn query_positions(
     deps: Deps,
     owner_addr: Option<String>,
     asset_token: Option<String>,
+    collateral_info: Option<AssetInfo>,
     start_after: Option<Uint128>,
     limit: Option<u32>,
     order_by: Option<OrderBy>,
@@ -958,25 +1375,346 @@ pub fn query_positions(
             limit,
             order_by,
         )?
+    } else if let Some(collateral_info) = collateral_info {
+        if read_collateral_index_cursor(deps.storage)?.is_some() {
+            return Err(StdError::generic_err(
+                "Positions are still being indexed by collateral",
+            ));
+        }
+        read_positions_with_collateral_indexer(
+            deps.storage,
+            collateral_info.to_raw(deps.api)?.as_bytes(),
+            start_after,
+            limit,
+            order_by,
+        )?
     } else {
         read_positions(deps.storage, start_after, limit, order_by)?
     };
 
-    let position_responses: StdResult<Vec<PositionResponse>> = positions
+    Ok(PositionsResponse {
+        positions: to_position_responses(deps, positions)?,
+    })
+}
+
+pub fn query_short_positions(
+    deps: Deps,
+    owner_addr: String,
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+    order_by: Option<OrderBy>,
+) -> StdResult<PositionsResponse> {
+    let positions: Vec<Position> = read_short_positions_with_user_indexer(
+        deps.storage,
+        &deps.api.addr_canonicalize(&owner_addr)?,
+        start_after,
+        limit,
+        order_by,
+    )?;
+
+    Ok(PositionsResponse {
+        positions: to_position_responses(deps, positions)?,
+    })
+}
+
+fn query_extra_collaterals(deps: Deps, position_idx: Uint128) -> StdResult<Vec<Asset>> {
+    read_position_extra_collaterals(deps.storage, position_idx)?
+        .unwrap_or_default()
+        .iter()
+        .map(|extra_collateral| extra_collateral.to_normal(deps.api))
+        .collect()
+}
+
+fn to_position_responses(deps: Deps, positions: Vec<Position>) -> StdResult<Vec<PositionResponse>> {
+    let config: Config = read_config(deps.storage)?;
+    positions
         .iter()
         .map(|position| {
             Ok(PositionResponse {
                 idx: position.idx,
                 owner: deps.api.addr_humanize(&position.owner)?.to_string(),
                 collateral: position.collateral.to_normal(deps.api)?,
+                extra_collaterals: query_extra_collaterals(deps, position.idx)?,
                 asset: position.asset.to_normal(deps.api)?,
                 is_short: is_short_position(deps.storage, position.idx)?,
+                liquidatable: is_liquidatable(deps, &config, position)?,
+                alert_ratio: read_position_alert(deps.storage, position.idx)?,
             })
         })
-        .collect();
+        .collect()
+}
 
-    Ok(PositionsResponse {
-        positions: position_responses?,
+/// Whether the position can currently be auctioned, following the checks done in `auction`.
+/// Migrated assets and stale prices (market closed) are never liquidatable.
+fn is_liquidatable(deps: Deps, config: &Config, position: &Position) -> StdResult<bool> {
+    let asset_token_raw = match position.asset.info.clone() {
+        AssetInfoRaw::Token { contract_addr } => contract_addr,
+        _ => return Ok(false),
+    };
+    let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    if asset_config.end_price.is_some() {
+        return Ok(false);
+    }
+
+    let oracle: Addr = deps.api.addr_humanize(&config.oracle)?;
+    let asset_price = match load_asset_price(deps, oracle, &position.asset.info, true) {
+        Ok(price) => price,
+        Err(_) => return Ok(false),
+    };
+
+    let collateral_oracle: Addr = deps.api.addr_humanize(&config.collateral_oracle)?;
+    let (collateral_price, collateral_multiplier) =
+        match load_collateral_info(deps, collateral_oracle, &position.collateral.info, true) {
+            Ok((price, multiplier, _, _)) => (price, multiplier),
+            Err(_) => return Ok(false),
+        };
+
+    let extra_collaterals: Vec<AssetRaw> =
+        read_position_extra_collaterals(deps.storage, position.idx)?.unwrap_or_default();
+    let collateral_amount = match total_collateral_amount(
+        deps,
+        config,
+        position.collateral.amount,
+        &extra_collaterals,
+        collateral_price,
+        Some(collateral_multiplier),
+        false,
+    ) {
+        Ok(collateral_amount) => collateral_amount,
+        Err(_) => return Ok(false),
+    };
+
+    Ok(is_undercollateralized(
+        position,
+        collateral_amount,
+        &asset_config,
+        asset_price,
+        collateral_price,
+        collateral_multiplier,
+    ))
+}
+
+fn is_undercollateralized(
+    position: &Position,
+    collateral_amount: Uint128,
+    asset_config: &AssetConfig,
+    asset_price: Decimal,
+    collateral_price: Decimal,
+    collateral_multiplier: Decimal,
+) -> bool {
+    let collateral_price_in_asset: Decimal = decimal_division(asset_price, collateral_price);
+    required_collateral(
+        position.asset.amount,
+        collateral_price_in_asset,
+        asset_config.min_collateral_ratio,
+        collateral_multiplier,
+    ) >= collateral_amount
+}
+
+/// Collateral backing the position in units of the position's collateral. Extra collaterals
+/// of a multi-collateral position are converted at their price and, unless no multiplier is
+/// given, discounted by their multiplier relative to the one of the position's collateral
+fn total_collateral_amount(
+    deps: Deps,
+    config: &Config,
+    collateral_amount: Uint128,
+    extra_collaterals: &[AssetRaw],
+    collateral_price: Decimal,
+    collateral_multiplier: Option<Decimal>,
+    new_exposure: bool,
+) -> StdResult<Uint128> {
+    let mut total_collateral_amount = collateral_amount;
+    if extra_collaterals.is_empty() {
+        return Ok(total_collateral_amount);
+    }
+
+    let collateral_oracle: Addr = deps.api.addr_humanize(&config.collateral_oracle)?;
+    for extra_collateral in extra_collaterals.iter() {
+        let load_collateral_res = load_collateral_info(
+            deps,
+            collateral_oracle.clone(),
+            &extra_collateral.info,
+            true,
+        )?;
+        let (extra_collateral_price, extra_collateral_multiplier) = if new_exposure {
+            assert_revoked_collateral(load_collateral_res)?
+        } else {
+            (load_collateral_res.0, load_collateral_res.1)
+        };
+
+        let conversion_rate: Decimal = match collateral_multiplier {
+            Some(collateral_multiplier) => decimal_division(
+                decimal_multiplication(extra_collateral_price, collateral_multiplier),
+                decimal_multiplication(collateral_price, extra_collateral_multiplier),
+            ),
+            None => decimal_division(extra_collateral_price, collateral_price),
+        };
+        total_collateral_amount += extra_collateral.amount * conversion_rate;
+    }
+
+    Ok(total_collateral_amount)
+}
+
+/// Once the position's collateral is used up, the first extra collateral takes its place
+fn promote_extra_collateral(
+    storage: &mut dyn Storage,
+    position: &mut Position,
+    extra_collaterals: &mut Vec<AssetRaw>,
+) -> StdResult<()> {
+    if position.collateral.amount.is_zero() && !extra_collaterals.is_empty() {
+        remove_position_collateral_index(storage, position.idx, position);
+        position.collateral = extra_collaterals.remove(0);
+        store_position_collateral_index(storage, position.idx, position)?;
+    }
+
+    Ok(())
+}
+
+fn join_assets(assets: &[Asset]) -> String {
+    assets
+        .iter()
+        .map(|asset| asset.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+pub fn query_liquidation_scan(
+    deps: Deps,
+    asset_token: String,
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+) -> StdResult<LiquidationScanResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    let positions: Vec<Position> = read_positions_with_asset_indexer(
+        deps.storage,
+        &asset_token_raw,
+        start_after,
+        limit,
+        Some(OrderBy::Asc),
+    )?;
+
+    // fetch the asset price once, migrated assets are never liquidatable
+    let oracle: Addr = deps.api.addr_humanize(&config.oracle)?;
+    let asset_price: Option<Decimal> = load_asset_price(
+        deps,
+        oracle,
+        &AssetInfoRaw::Token {
+            contract_addr: asset_token_raw,
+        },
+        true,
+    )
+    .ok();
+
+    // collateral prices are cached, positions of an asset share few collateral types
+    let collateral_oracle: Addr = deps.api.addr_humanize(&config.collateral_oracle)?;
+    let mut collateral_infos: HashMap<Vec<u8>, Option<(Decimal, Decimal)>> = HashMap::new();
+
+    let mut items: Vec<LiquidationScanItem> = vec![];
+    for position in positions.iter() {
+        let collateral_key = position.collateral.info.as_bytes().to_vec();
+        let collateral_info = match collateral_infos.get(&collateral_key) {
+            Some(collateral_info) => *collateral_info,
+            None => {
+                let collateral_info = load_collateral_info(
+                    deps,
+                    collateral_oracle.clone(),
+                    &position.collateral.info,
+                    true,
+                )
+                .ok()
+                .map(|(price, multiplier, _, _)| (price, multiplier));
+                collateral_infos.insert(collateral_key, collateral_info);
+                collateral_info
+            }
+        };
+
+        // multi-collateral positions also count their extra collaterals, at their value for
+        // the ratio and weighted by their multiplier for the liquidation check
+        let extra_collaterals: Vec<AssetRaw> =
+            read_position_extra_collaterals(deps.storage, position.idx)?.unwrap_or_default();
+        let collateral_info =
+            collateral_info.and_then(|(collateral_price, collateral_multiplier)| {
+                let collateral_value = total_collateral_amount(
+                    deps,
+                    &config,
+                    position.collateral.amount,
+                    &extra_collaterals,
+                    collateral_price,
+                    None,
+                    false,
+                )
+                .ok()?;
+                let collateral_amount = total_collateral_amount(
+                    deps,
+                    &config,
+                    position.collateral.amount,
+                    &extra_collaterals,
+                    collateral_price,
+                    Some(collateral_multiplier),
+                    false,
+                )
+                .ok()?;
+                Some((
+                    collateral_price,
+                    collateral_multiplier,
+                    collateral_value,
+                    collateral_amount,
+                ))
+            });
+
+        let (collateral_ratio, liquidatable) = match (asset_price, collateral_info) {
+            (
+                Some(asset_price),
+                Some((
+                    collateral_price,
+                    collateral_multiplier,
+                    collateral_value,
+                    collateral_amount,
+                )),
+            ) => {
+                let asset_value_in_collateral_asset =
+                    position.asset.amount * decimal_division(asset_price, collateral_price);
+                let collateral_ratio = if asset_value_in_collateral_asset.is_zero() {
+                    None
+                } else {
+                    Some(Decimal::from_ratio(
+                        collateral_value,
+                        asset_value_in_collateral_asset,
+                    ))
+                };
+
+                (
+                    collateral_ratio,
+                    asset_config.end_price.is_none()
+                        && is_undercollateralized(
+                            position,
+                            collateral_amount,
+                            &asset_config,
+                            asset_price,
+                            collateral_price,
+                            collateral_multiplier,
+                        ),
+                )
+            }
+            _ => (None, false),
+        };
+
+        items.push(LiquidationScanItem {
+            idx: position.idx,
+            owner: deps.api.addr_humanize(&position.owner)?.to_string(),
+            collateral: position.collateral.to_normal(deps.api)?,
+            asset: position.asset.to_normal(deps.api)?,
+            collateral_ratio,
+            liquidatable,
+        });
+    }
+
+    Ok(LiquidationScanResponse {
+        asset_token,
+        asset_price,
+        positions: items,
     })
 }
 
@@ -988,3 +1726,11 @@ pub fn query_next_position_idx(deps: Deps) -> StdResult<NextPositionIdxResponse>
 
     Ok(resp)
 }
+
+pub fn query_position_count(deps: Deps, owner_addr: String) -> StdResult<PositionCountResponse> {
+    let count =
+        count_positions_with_user_indexer(deps.storage, &deps.api.addr_canonicalize(&owner_addr)?);
+    let resp = PositionCountResponse { owner_addr, count };
+
+    Ok(resp)
+}
@@ -4,7 +4,7 @@ use cosmwasm_std::{
 
 use crate::{
     math::decimal_division,
-    state::{read_config, read_fixed_price, Config},
+    state::{read_config, read_fixed_price, read_price_override, Config},
 };
 use mirror_protocol::collateral_oracle::{
     CollateralInfoResponse, CollateralPriceResponse, QueryMsg as CollateralOracleQueryMsg,
@@ -12,7 +12,16 @@ use mirror_protocol::collateral_oracle::{
 use tefi_oracle::hub::{HubQueryMsg as OracleQueryMsg, PriceResponse};
 use terraswap::asset::AssetInfoRaw;
 
-const PRICE_EXPIRE_TIME: u64 = 60;
+pub const PRICE_EXPIRE_TIME: u64 = 60;
+
+// oracle timeframe for expiry checks, tolerating stale prices up to the market close grace
+fn price_timeframe(config: &Config, check_expire: bool) -> Option<u64> {
+    if check_expire {
+        Some(PRICE_EXPIRE_TIME + config.market_close_grace)
+    } else {
+        None
+    }
+}
 
 pub fn load_asset_price(
     deps: Deps,
@@ -27,13 +36,21 @@ pub fn load_asset_price(
 
     let price: Decimal = if let Some(stored_price) = stored_price {
         stored_price
+    } else if let Some(price_override) = read_price_override(deps.storage, asset) {
+        price_override
     } else {
         let asset_denom: String = (asset.to_normal(deps.api)?).to_string();
         if asset_denom == config.base_denom {
             Decimal::one()
         } else {
             // fetch price from oracle
-            query_price(&deps.querier, oracle, asset_denom, None, check_expire)?
+            query_price(
+                &deps.querier,
+                oracle,
+                asset_denom,
+                None,
+                price_timeframe(&config, check_expire),
+            )?
         }
     };
 
@@ -45,13 +62,13 @@ pub fn load_collateral_info(
     collateral_oracle: Addr,
     collateral: &AssetInfoRaw,
     check_expire: bool,
-) -> StdResult<(Decimal, Decimal, bool)> {
+) -> StdResult<(Decimal, Decimal, bool, bool)> {
     let config: Config = read_config(deps.storage)?;
     let collateral_denom: String = (collateral.to_normal(deps.api)?).to_string();
 
     // base collateral
     if collateral_denom == config.base_denom {
-        return Ok((Decimal::one(), Decimal::one(), false));
+        return Ok((Decimal::one(), Decimal::one(), false, false));
     }
 
     // check if the collateral is a revoked mAsset, will ignore pre_ipo_price since all preIPO
@@ -61,20 +78,18 @@ pub fn load_collateral_info(
     if let Some(end_price) = end_price {
         // load collateral_multiplier from collateral oracle
         // if asset is revoked, no need to check for old price
-        let (collateral_multiplier, _) =
+        let (collateral_multiplier, _, is_suspended) =
             query_collateral_info(&deps.querier, collateral_oracle, collateral_denom)?;
 
-        Ok((end_price, collateral_multiplier, true))
+        Ok((end_price, collateral_multiplier, true, is_suspended))
     } else {
         // load collateral info from collateral oracle
-        let (collateral_oracle_price, collateral_multiplier, is_revoked) = query_collateral(
+        query_collateral(
             &deps.querier,
             collateral_oracle,
             collateral_denom,
-            check_expire,
-        )?;
-
-        Ok((collateral_oracle_price, collateral_multiplier, is_revoked))
+            price_timeframe(&config, check_expire),
+        )
     }
 }
 
@@ -83,13 +98,8 @@ pub fn query_price(
     oracle: Addr,
     base_asset: String,
     quote_asset: Option<String>,
-    check_expire: bool,
+    timeframe: Option<u64>,
 ) -> StdResult<Decimal> {
-    let timeframe: Option<u64> = if check_expire {
-        Some(PRICE_EXPIRE_TIME)
-    } else {
-        None
-    };
     let base_res: PriceResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: oracle.to_string(),
         msg: to_binary(&OracleQueryMsg::Price {
@@ -115,36 +125,65 @@ pub fn query_price(
     Ok(rate)
 }
 
-// queries the collateral oracle to get the asset rate and multiplier
+// queries the oracle price without expiry check, along with its last update time
+pub fn query_price_info(
+    querier: &QuerierWrapper,
+    oracle: Addr,
+    asset: String,
+) -> StdResult<(Decimal, u64)> {
+    let res: PriceResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: oracle.to_string(),
+        msg: to_binary(&OracleQueryMsg::Price {
+            asset_token: asset,
+            timeframe: None,
+        })?,
+    }))?;
+
+    Ok((res.rate, res.last_updated))
+}
+
+// queries the collateral oracle price without expiry check, along with its last update time
+pub fn query_collateral_price_info(
+    querier: &QuerierWrapper,
+    collateral_oracle: Addr,
+    asset: String,
+) -> StdResult<(Decimal, u64)> {
+    let res: CollateralPriceResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: collateral_oracle.to_string(),
+        msg: to_binary(&CollateralOracleQueryMsg::CollateralPrice {
+            asset,
+            timeframe: None,
+        })?,
+    }))?;
+
+    Ok((res.rate, res.last_updated))
+}
+
+// queries the collateral oracle to get the asset rate, multiplier and status flags
 pub fn query_collateral(
     querier: &QuerierWrapper,
     collateral_oracle: Addr,
     asset: String,
-    check_expire: bool,
-) -> StdResult<(Decimal, Decimal, bool)> {
-    let timeframe: Option<u64> = if check_expire {
-        Some(PRICE_EXPIRE_TIME)
-    } else {
-        None
-    };
+    timeframe: Option<u64>,
+) -> StdResult<(Decimal, Decimal, bool, bool)> {
     let res: CollateralPriceResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: collateral_oracle.to_string(),
         msg: to_binary(&CollateralOracleQueryMsg::CollateralPrice { asset, timeframe })?,
     }))?;
 
-    Ok((res.rate, res.multiplier, res.is_revoked))
+    Ok((res.rate, res.multiplier, res.is_revoked, res.is_suspended))
 }
 
-// queries only collateral information (multiplier and is_revoked), without price
+// queries only collateral information (multiplier, is_revoked and is_suspended), without price
 pub fn query_collateral_info(
     querier: &QuerierWrapper,
     collateral_oracle: Addr,
     asset: String,
-) -> StdResult<(Decimal, bool)> {
+) -> StdResult<(Decimal, bool, bool)> {
     let res: CollateralInfoResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: collateral_oracle.to_string(),
         msg: to_binary(&CollateralOracleQueryMsg::CollateralAssetInfo { asset })?,
     }))?;
 
-    Ok((res.multiplier, res.is_revoked))
+    Ok((res.multiplier, res.is_revoked, res.is_suspended))
 }
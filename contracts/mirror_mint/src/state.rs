@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{CanonicalAddr, Decimal, StdError, StdResult, Storage, Uint128};
+use cosmwasm_std::{CanonicalAddr, Decimal, Order, StdError, StdResult, Storage, Uint128};
 
 use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
 use mirror_protocol::common::OrderBy;
@@ -10,13 +10,17 @@ use std::convert::TryInto;
 use terraswap::asset::{AssetInfoRaw, AssetRaw};
 
 pub static PREFIX_ASSET_CONFIG: &[u8] = b"asset_config";
-static PREFIX_POSITION: &[u8] = b"position";
+pub static PREFIX_POSITION: &[u8] = b"position";
 static PREFIX_INDEX_BY_USER: &[u8] = b"by_user";
 static PREFIX_INDEX_BY_ASSET: &[u8] = b"by_asset";
+static PREFIX_INDEX_BY_COLLATERAL: &[u8] = b"by_collateral";
 static PREFIX_SHORT_POSITION: &[u8] = b"short_position";
+static PREFIX_POSITION_ALERT: &[u8] = b"position_alert";
+static PREFIX_POSITION_EXTRA_COLLATERALS: &[u8] = b"position_extra_collaterals";
 
 pub static KEY_CONFIG: &[u8] = b"config";
 static KEY_POSITION_IDX: &[u8] = b"position_idx";
+static KEY_COLLATERAL_INDEX_CURSOR: &[u8] = b"collateral_index_cursor";
 
 pub fn store_position_idx(storage: &mut dyn Storage, position_idx: Uint128) -> StdResult<()> {
     singleton(storage, KEY_POSITION_IDX).save(&position_idx)
@@ -26,9 +30,27 @@ pub fn read_position_idx(storage: &dyn Storage) -> StdResult<Uint128> {
     singleton_read(storage, KEY_POSITION_IDX).load()
 }
 
+/// last position indexed by collateral while the existing positions are being indexed,
+/// zero means none was indexed yet
+pub fn store_collateral_index_cursor(
+    storage: &mut dyn Storage,
+    last_idx: Uint128,
+) -> StdResult<()> {
+    singleton(storage, KEY_COLLATERAL_INDEX_CURSOR).save(&last_idx)
+}
+
+pub fn remove_collateral_index_cursor(storage: &mut dyn Storage) {
+    singleton::<Uint128>(storage, KEY_COLLATERAL_INDEX_CURSOR).remove()
+}
+
+pub fn read_collateral_index_cursor(storage: &dyn Storage) -> StdResult<Option<Uint128>> {
+    singleton_read(storage, KEY_COLLATERAL_INDEX_CURSOR).may_load()
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: CanonicalAddr,
+    pub pending_owner: Option<CanonicalAddr>,
     pub oracle: CanonicalAddr,
     pub collector: CanonicalAddr,
     pub collateral_oracle: CanonicalAddr,
@@ -38,6 +60,12 @@ pub struct Config {
     pub base_denom: String,
     pub token_code_id: u64,
     pub protocol_fee_rate: Decimal,
+    pub max_positions_per_owner: Option<u32>,
+    // flat amount of collateral paid to liquidators, capped by the position collateral
+    pub liquidation_incentive: Option<Uint128>,
+    pub open_fee_rate: Decimal, // collateral rate charged when opening a position
+    pub market_close_grace: u64, // extra seconds a price may be stale before it expires
+    pub multi_collateral: bool, // whether new positions can hold several collaterals
 }
 
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -55,6 +83,9 @@ pub struct AssetConfig {
     pub min_collateral_ratio: Decimal,
     pub end_price: Option<Decimal>,
     pub ipo_params: Option<IPOParams>,
+    pub price_override: Option<Decimal>,
+    pub max_initial_ltv: Option<Decimal>,
+    pub lock_period: Option<u64>,
 }
 
 pub fn store_asset_config(
@@ -101,6 +132,21 @@ pub fn read_fixed_price(storage: &dyn Storage, asset_info: &AssetInfoRaw) -> Opt
     }
 }
 
+// check if the owner has frozen the asset price
+pub fn read_price_override(storage: &dyn Storage, asset_info: &AssetInfoRaw) -> Option<Decimal> {
+    match asset_info {
+        AssetInfoRaw::Token { contract_addr } => {
+            let asset_bucket: ReadonlyBucket<AssetConfig> =
+                ReadonlyBucket::new(storage, PREFIX_ASSET_CONFIG);
+            match asset_bucket.load(contract_addr.as_slice()) {
+                Ok(data) => data.price_override,
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 pub fn store_short_position(storage: &mut dyn Storage, idx: Uint128) -> StdResult<()> {
     let mut short_position_bucket: Bucket<bool> = Bucket::new(storage, PREFIX_SHORT_POSITION);
     short_position_bucket.save(&idx.u128().to_be_bytes(), &true)
@@ -118,6 +164,26 @@ pub fn is_short_position(storage: &dyn Storage, idx: Uint128) -> StdResult<bool>
     Ok(res.is_some())
 }
 
+pub fn store_position_alert(
+    storage: &mut dyn Storage,
+    idx: Uint128,
+    ratio: Decimal,
+) -> StdResult<()> {
+    let mut position_alert_bucket: Bucket<Decimal> = Bucket::new(storage, PREFIX_POSITION_ALERT);
+    position_alert_bucket.save(&idx.u128().to_be_bytes(), &ratio)
+}
+
+pub fn remove_position_alert(storage: &mut dyn Storage, idx: Uint128) {
+    let mut position_alert_bucket: Bucket<Decimal> = Bucket::new(storage, PREFIX_POSITION_ALERT);
+    position_alert_bucket.remove(&idx.u128().to_be_bytes())
+}
+
+pub fn read_position_alert(storage: &dyn Storage, idx: Uint128) -> StdResult<Option<Decimal>> {
+    let position_alert_bucket: ReadonlyBucket<Decimal> =
+        ReadonlyBucket::new(storage, PREFIX_POSITION_ALERT);
+    position_alert_bucket.may_load(&idx.u128().to_be_bytes())
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Position {
     pub idx: Uint128,
@@ -145,9 +211,62 @@ pub fn create_position(
     );
     position_indexer_by_asset.save(&idx.u128().to_be_bytes(), &true)?;
 
+    store_position_collateral_index(storage, idx, position)?;
+
     Ok(())
 }
 
+pub fn store_position_collateral_index(
+    storage: &mut dyn Storage,
+    idx: Uint128,
+    position: &Position,
+) -> StdResult<()> {
+    let mut position_indexer_by_collateral: Bucket<bool> = Bucket::multilevel(
+        storage,
+        &[
+            PREFIX_INDEX_BY_COLLATERAL,
+            position.collateral.info.as_bytes(),
+        ],
+    );
+    position_indexer_by_collateral.save(&idx.u128().to_be_bytes(), &true)
+}
+
+pub fn remove_position_collateral_index(
+    storage: &mut dyn Storage,
+    idx: Uint128,
+    position: &Position,
+) {
+    let mut position_indexer_by_collateral: Bucket<bool> = Bucket::multilevel(
+        storage,
+        &[
+            PREFIX_INDEX_BY_COLLATERAL,
+            position.collateral.info.as_bytes(),
+        ],
+    );
+    position_indexer_by_collateral.remove(&idx.u128().to_be_bytes());
+}
+
+/// store the collaterals a multi-collateral position holds besides its (indexed) collateral
+pub fn store_position_extra_collaterals(
+    storage: &mut dyn Storage,
+    idx: Uint128,
+    extra_collaterals: &[AssetRaw],
+) -> StdResult<()> {
+    let mut extra_collaterals_bucket: Bucket<Vec<AssetRaw>> =
+        Bucket::new(storage, PREFIX_POSITION_EXTRA_COLLATERALS);
+    extra_collaterals_bucket.save(&idx.u128().to_be_bytes(), &extra_collaterals.to_vec())
+}
+
+/// None if the position only holds a single collateral
+pub fn read_position_extra_collaterals(
+    storage: &dyn Storage,
+    idx: Uint128,
+) -> StdResult<Option<Vec<AssetRaw>>> {
+    let extra_collaterals_bucket: ReadonlyBucket<Vec<AssetRaw>> =
+        ReadonlyBucket::new(storage, PREFIX_POSITION_EXTRA_COLLATERALS);
+    extra_collaterals_bucket.may_load(&idx.u128().to_be_bytes())
+}
+
 /// store position with idx
 pub fn store_position(
     storage: &mut dyn Storage,
@@ -177,9 +296,18 @@ pub fn remove_position(storage: &mut dyn Storage, idx: Uint128) -> StdResult<()>
     );
     position_indexer_by_asset.remove(&idx.u128().to_be_bytes());
 
+    // remove indexer
+    remove_position_collateral_index(storage, idx, &position);
+
     // remove short position flag
     remove_short_position(storage, idx);
 
+    let mut extra_collaterals_bucket: Bucket<Vec<AssetRaw>> =
+        Bucket::new(storage, PREFIX_POSITION_EXTRA_COLLATERALS);
+    extra_collaterals_bucket.remove(&idx.u128().to_be_bytes());
+
+    remove_position_alert(storage, idx);
+
     Ok(())
 }
 
@@ -201,9 +329,9 @@ pub fn read_positions(
     let position_bucket: ReadonlyBucket<Position> = ReadonlyBucket::new(storage, PREFIX_POSITION);
 
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let (start, end, order_by) = match order_by {
-        Some(OrderBy::Asc) => (calc_range_start(start_after), None, OrderBy::Asc),
-        _ => (None, calc_range_end(start_after), OrderBy::Desc),
+    let (start, end, order_by) = match OrderBy::from_opt(order_by) {
+        OrderBy::Asc => (calc_range_start(start_after), None, OrderBy::Asc),
+        OrderBy::Desc => (None, calc_range_end(start_after), OrderBy::Desc),
     };
 
     position_bucket
@@ -227,9 +355,9 @@ pub fn read_positions_with_user_indexer(
         ReadonlyBucket::multilevel(storage, &[PREFIX_INDEX_BY_USER, position_owner.as_slice()]);
 
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let (start, end, order_by) = match order_by {
-        Some(OrderBy::Asc) => (calc_range_start(start_after), None, OrderBy::Asc),
-        _ => (None, calc_range_end(start_after), OrderBy::Desc),
+    let (start, end, order_by) = match OrderBy::from_opt(order_by) {
+        OrderBy::Asc => (calc_range_start(start_after), None, OrderBy::Asc),
+        OrderBy::Desc => (None, calc_range_end(start_after), OrderBy::Desc),
     };
 
     position_indexer
@@ -242,6 +370,47 @@ pub fn read_positions_with_user_indexer(
         .collect()
 }
 
+pub fn read_short_positions_with_user_indexer(
+    storage: &dyn Storage,
+    position_owner: &CanonicalAddr,
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+    order_by: Option<OrderBy>,
+) -> StdResult<Vec<Position>> {
+    let position_indexer: ReadonlyBucket<bool> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_INDEX_BY_USER, position_owner.as_slice()]);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let (start, end, order_by) = match OrderBy::from_opt(order_by) {
+        OrderBy::Asc => (calc_range_start(start_after), None, OrderBy::Asc),
+        OrderBy::Desc => (None, calc_range_end(start_after), OrderBy::Desc),
+    };
+
+    position_indexer
+        .range(start.as_deref(), end.as_deref(), order_by.into())
+        .map(|item| {
+            let (k, _) = item?;
+            Ok(Uint128::from(bytes_to_u128(&k)?))
+        })
+        .filter(|idx: &StdResult<Uint128>| match idx {
+            Ok(idx) => is_short_position(storage, *idx).unwrap_or(false),
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|idx| read_position(storage, idx?))
+        .collect()
+}
+
+pub fn count_positions_with_user_indexer(
+    storage: &dyn Storage,
+    position_owner: &CanonicalAddr,
+) -> u32 {
+    let position_indexer: ReadonlyBucket<bool> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_INDEX_BY_USER, position_owner.as_slice()]);
+
+    position_indexer.range(None, None, Order::Ascending).count() as u32
+}
+
 pub fn read_positions_with_asset_indexer(
     storage: &dyn Storage,
     asset_token: &CanonicalAddr,
@@ -253,9 +422,35 @@ pub fn read_positions_with_asset_indexer(
         ReadonlyBucket::multilevel(storage, &[PREFIX_INDEX_BY_ASSET, asset_token.as_slice()]);
 
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let (start, end, order_by) = match order_by {
-        Some(OrderBy::Asc) => (calc_range_start(start_after), None, OrderBy::Asc),
-        _ => (None, calc_range_end(start_after), OrderBy::Desc),
+    let (start, end, order_by) = match OrderBy::from_opt(order_by) {
+        OrderBy::Asc => (calc_range_start(start_after), None, OrderBy::Asc),
+        OrderBy::Desc => (None, calc_range_end(start_after), OrderBy::Desc),
+    };
+
+    position_indexer
+        .range(start.as_deref(), end.as_deref(), order_by.into())
+        .take(limit)
+        .map(|item| {
+            let (k, _) = item?;
+            read_position(storage, Uint128::from(bytes_to_u128(&k)?))
+        })
+        .collect()
+}
+
+pub fn read_positions_with_collateral_indexer(
+    storage: &dyn Storage,
+    collateral_key: &[u8],
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+    order_by: Option<OrderBy>,
+) -> StdResult<Vec<Position>> {
+    let position_indexer: ReadonlyBucket<bool> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_INDEX_BY_COLLATERAL, collateral_key]);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let (start, end, order_by) = match OrderBy::from_opt(order_by) {
+        OrderBy::Asc => (calc_range_start(start_after), None, OrderBy::Asc),
+        OrderBy::Desc => (None, calc_range_end(start_after), OrderBy::Desc),
     };
 
     position_indexer
@@ -1,10 +1,14 @@
 use crate::contract::{execute, instantiate, query};
+use crate::state::store_collateral_index_cursor;
 use crate::testing::mock_querier::mock_dependencies;
 use cosmwasm_std::testing::{mock_env, mock_info};
-use cosmwasm_std::{from_binary, to_binary, CosmosMsg, Decimal, StdError, SubMsg, WasmMsg};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Binary, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg,
+};
 use mirror_protocol::collateral_oracle::{ExecuteMsg::RegisterCollateralAsset, SourceType};
 use mirror_protocol::mint::{
-    AssetConfigResponse, ConfigResponse, ExecuteMsg, IPOParams, InstantiateMsg, QueryMsg,
+    AssetConfigResponse, ConfigResponse, ExecuteMsg, IPOParams, InstantiateMsg, PositionsResponse,
+    PriceConsistencyResponse, QueryMsg,
 };
 use terraswap::asset::AssetInfo;
 
@@ -24,6 +28,9 @@ fn proper_initialization() {
         base_denom: "uusd".to_string(),
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
     let info = mock_info("addr0000", &[]);
     // we can just call .unwrap() to assert this was a success
@@ -55,6 +62,9 @@ fn update_config() {
         base_denom: "uusd".to_string(),
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
     let info = mock_info("addr0000", &[]);
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -70,9 +80,27 @@ fn update_config() {
         protocol_fee_rate: None,
         collateral_oracle: None,
         staking: None,
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: None,
+        market_close_grace: None,
+        multi_collateral: None,
     };
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(0, res.messages.len());
+    // the owner is only proposed until it accepts
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!("owner0000", config.owner.as_str());
+    assert_eq!(Some("owner0001".to_string()), config.pending_owner);
+    let info = mock_info("owner0001", &[]);
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap();
     // it worked, let's query the state
     let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
     let config: ConfigResponse = from_binary(&res).unwrap();
@@ -90,6 +118,11 @@ fn update_config() {
         protocol_fee_rate: None,
         collateral_oracle: None,
         staking: None,
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: None,
+        market_close_grace: None,
+        multi_collateral: None,
     };
     let res = execute(deps.as_mut(), mock_env(), info, msg);
     match res {
@@ -97,6 +130,86 @@ fn update_config() {
         _ => panic!("Must return unauthorized error"),
     }
 }
+
+#[test]
+fn transfer_ownership() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // only the owner can propose
+    let msg = ExecuteMsg::ProposeOwner {
+        owner: "owner0001".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "propose_owner"),
+            attr("pending_owner", "owner0001"),
+        ]
+    );
+
+    // the owner does not change until the proposal is accepted
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!("owner0000", config.owner.as_str());
+    assert_eq!(Some("owner0001".to_string()), config.pending_owner);
+
+    // only the pending owner can accept
+    let msg = ExecuteMsg::AcceptOwnership {};
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner0001", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "accept_ownership"),
+            attr("owner", "owner0001"),
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!("owner0001", config.owner.as_str());
+    assert_eq!(None, config.pending_owner);
+}
+
 #[test]
 fn register_asset() {
     let mut deps = mock_dependencies(&[]);
@@ -112,6 +225,9 @@ fn register_asset() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
     let info = mock_info("addr0000", &[]);
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -158,8 +274,25 @@ fn register_asset() {
             min_collateral_ratio: Decimal::percent(150),
             end_price: None,
             ipo_params: None,
+            price_override: None,
+            max_initial_ltv: None,
+            lock_period: None,
         }
     );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AssetConfig {
+            asset_token: "asset0001".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Asset not registered: asset0001")
+    );
+
     // must be failed with the already registered token error
     let msg = ExecuteMsg::RegisterAsset {
         asset_token: "asset0000".to_string(),
@@ -232,6 +365,9 @@ fn update_asset() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
     let info = mock_info("addr0000", &[]);
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -278,6 +414,9 @@ fn update_asset() {
                 pre_ipo_price: Decimal::percent(1),
                 trigger_addr: "ipotrigger0000".to_string(),
             }),
+            price_override: None,
+            max_initial_ltv: None,
+            lock_period: None,
         }
     );
     let msg = ExecuteMsg::UpdateAsset {
@@ -321,3 +460,187 @@ fn update_asset() {
         _ => panic!("Must return unauthorized error"),
     }
 }
+
+#[test]
+fn query_price_consistency() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::percent(100)),
+    ]);
+    deps.querier.with_collateral_infos(&[(
+        &"asset0000".to_string(),
+        &Decimal::percent(101),
+        &Decimal::one(),
+        &false,
+    )]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the oracle price was updated 10 seconds ago, the collateral oracle price is stale
+    let env = mock_env();
+    deps.querier
+        .with_oracle_price_last_updated("asset0000", env.block.time.seconds() - 10);
+
+    let res: PriceConsistencyResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PriceConsistency {
+                asset_token: "asset0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        PriceConsistencyResponse {
+            asset_token: "asset0000".to_string(),
+            oracle_price: Decimal::percent(100),
+            oracle_price_age: 10,
+            collateral_oracle_price: Decimal::percent(101),
+            collateral_oracle_price_age: env.block.time.seconds() - 1000,
+            oracle_price_stale: false,
+            collateral_oracle_price_stale: true,
+        }
+    );
+}
+
+#[test]
+fn migrate_positions_collateral_index() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // left by the contract migration
+    store_collateral_index_cursor(deps.as_mut().storage, Uint128::zero()).unwrap();
+
+    let query_msg = QueryMsg::Positions {
+        owner_addr: None,
+        asset_token: None,
+        collateral_info: Some(AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        }),
+        start_after: None,
+        limit: None,
+        order_by: None,
+    };
+    let res = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Positions are still being indexed by collateral")
+    );
+
+    let msg = ExecuteMsg::MigratePositionsCollateralIndex { limit: None };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate_positions_collateral_index"),
+            attr("indexed_count", "0"),
+            attr("has_more", "false"),
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+    let positions: PositionsResponse = from_binary(&res).unwrap();
+    assert!(positions.positions.is_empty());
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Positions are already indexed by collateral")
+    );
+}
+
+#[test]
+fn liquidation_incentive_is_optional() {
+    let msg: InstantiateMsg = from_binary(&Binary::from(
+        br#"{
+            "owner": "owner0000",
+            "oracle": "oracle0000",
+            "collector": "collector0000",
+            "collateral_oracle": "collateraloracle0000",
+            "staking": "staking0000",
+            "terraswap_factory": "terraswap_factory",
+            "lock": "lock0000",
+            "base_denom": "uusd",
+            "token_code_id": 10,
+            "protocol_fee_rate": "0.015",
+            "max_positions_per_owner": null,
+            "open_fee_rate": "0"
+        }"#
+        .to_vec(),
+    ))
+    .unwrap();
+    assert_eq!(msg.liquidation_incentive, None);
+
+    let mut deps = mock_dependencies(&[]);
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // zero removes the incentive
+    let info = mock_info("owner0000", &[]);
+    for (incentive, expected) in [
+        (Uint128::from(1000u128), Some(Uint128::from(1000u128))),
+        (Uint128::zero(), None),
+    ] {
+        let msg = ExecuteMsg::UpdateConfig {
+            owner: None,
+            oracle: None,
+            collector: None,
+            collateral_oracle: None,
+            terraswap_factory: None,
+            lock: None,
+            token_code_id: None,
+            protocol_fee_rate: None,
+            staking: None,
+            max_positions_per_owner: None,
+            liquidation_incentive: Some(incentive),
+            open_fee_rate: None,
+            market_close_grace: None,
+            multi_collateral: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let config: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(config.liquidation_incentive, expected);
+    }
+}
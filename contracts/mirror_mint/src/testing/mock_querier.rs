@@ -1,11 +1,11 @@
-use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::testing::{mock_env, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
     from_binary, from_slice, to_binary, Coin, ContractResult, Decimal, OwnedDeps, Querier,
     QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use mirror_protocol::collateral_oracle::CollateralPriceResponse;
 use tefi_oracle::hub::PriceResponse;
@@ -63,12 +63,14 @@ pub(crate) fn caps_to_map(caps: &[(&String, &Uint128)]) -> HashMap<String, Uint1
 pub struct OraclePriceQuerier {
     // this lets us iterate over all pairs that match the first string
     oracle_price: HashMap<String, Decimal>,
+    last_updated: HashMap<String, u64>,
 }
 
 impl OraclePriceQuerier {
     pub fn new(oracle_price: &[(&String, &Decimal)]) -> Self {
         OraclePriceQuerier {
             oracle_price: oracle_price_to_map(oracle_price),
+            last_updated: HashMap::new(),
         }
     }
 }
@@ -88,12 +90,14 @@ pub(crate) fn oracle_price_to_map(
 pub struct CollateralOracleQuerier {
     // this lets us iterate over all pairs that match the first string
     collateral_infos: HashMap<String, (Decimal, Decimal, bool)>,
+    suspended: HashSet<String>,
 }
 
 impl CollateralOracleQuerier {
     pub fn new(collateral_infos: &[(&String, &Decimal, &Decimal, &bool)]) -> Self {
         CollateralOracleQuerier {
             collateral_infos: collateral_infos_to_map(collateral_infos),
+            suspended: HashSet::new(),
         }
     }
 }
@@ -201,12 +205,22 @@ impl WasmMockQuerier {
             }) => match from_binary(msg).unwrap() {
                 MockQueryMsg::Price {
                     asset_token,
-                    timeframe: _,
+                    timeframe,
                 } => match self.oracle_price_querier.oracle_price.get(&asset_token) {
                     Some(base_price) => {
+                        let last_updated = self.oracle_price_querier.last_updated.get(&asset_token);
+                        // the expiry is only enforced on explicitly configured update times
+                        if let (Some(timeframe), Some(last_updated)) = (timeframe, last_updated) {
+                            if mock_env().block.time.seconds() - last_updated > timeframe {
+                                return SystemResult::Ok(ContractResult::Err(
+                                    "Price is too old".to_string(),
+                                ));
+                            }
+                        }
+
                         SystemResult::Ok(ContractResult::from(to_binary(&PriceResponse {
                             rate: *base_price,
-                            last_updated: 1000u64,
+                            last_updated: last_updated.copied().unwrap_or(1000u64),
                         })))
                     }
                     None => SystemResult::Err(SystemError::InvalidRequest {
@@ -218,11 +232,16 @@ impl WasmMockQuerier {
                     match self.collateral_oracle_querier.collateral_infos.get(&asset) {
                         Some(collateral_info) => SystemResult::Ok(ContractResult::from(to_binary(
                             &CollateralPriceResponse {
-                                asset,
+                                asset: asset.clone(),
+                                base_denom: "uusd".to_string(),
                                 rate: collateral_info.0,
                                 last_updated: 1000u64,
                                 multiplier: collateral_info.1,
                                 is_revoked: collateral_info.2,
+                                is_suspended: self
+                                    .collateral_oracle_querier
+                                    .suspended
+                                    .contains(&asset),
                             },
                         ))),
                         None => SystemResult::Err(SystemError::InvalidRequest {
@@ -277,6 +296,13 @@ impl WasmMockQuerier {
         self.oracle_price_querier = OraclePriceQuerier::new(oracle_price);
     }
 
+    // configure the oracle price update time, defaults to 1000
+    pub fn with_oracle_price_last_updated(&mut self, asset_token: &str, last_updated: u64) {
+        self.oracle_price_querier
+            .last_updated
+            .insert(asset_token.to_string(), last_updated);
+    }
+
     // configure the collateral oracle mock querier
     pub fn with_collateral_infos(
         &mut self,
@@ -285,6 +311,13 @@ impl WasmMockQuerier {
         self.collateral_oracle_querier = CollateralOracleQuerier::new(collateral_infos);
     }
 
+    // flag a configured collateral as suspended in the collateral oracle
+    pub fn with_suspended_collateral(&mut self, collateral: &str) {
+        self.collateral_oracle_querier
+            .suspended
+            .insert(collateral.to_string());
+    }
+
     // configure the terraswap factory pair mock querier
     pub fn with_terraswap_pair(&mut self, pairs: &[(&String, &String, &String)]) {
         self.terraswap_pair_querier = TerraswapPairQuerier::new(pairs);
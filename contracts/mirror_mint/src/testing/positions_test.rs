@@ -1,4 +1,5 @@
 use crate::contract::{execute, instantiate, query};
+use crate::querier::PRICE_EXPIRE_TIME;
 use crate::testing::mock_querier::mock_dependencies;
 use cosmwasm_std::testing::{mock_env, mock_info};
 use cosmwasm_std::{
@@ -8,7 +9,9 @@ use cosmwasm_std::{
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use mirror_protocol::common::OrderBy;
 use mirror_protocol::mint::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, PositionResponse, PositionsResponse, QueryMsg,
+    AssetConfigResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg,
+    LiquidationScanItem, LiquidationScanResponse, PositionCountResponse, PositionResponse,
+    PositionsResponse, QueryMsg,
 };
 use terraswap::asset::{Asset, AssetInfo};
 
@@ -54,6 +57,9 @@ fn open_position() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -181,6 +187,7 @@ fn open_position() {
         PositionResponse {
             idx: Uint128::from(1u128),
             owner: "addr0000".to_string(),
+            extra_collaterals: vec![],
             asset: Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -194,6 +201,8 @@ fn open_position() {
                 amount: Uint128::from(1000000u128),
             },
             is_short: false,
+            liquidatable: false,
+            alert_ratio: None,
         }
     );
 
@@ -207,6 +216,7 @@ fn open_position() {
             limit: None,
             start_after: None,
             order_by: Some(OrderBy::Asc),
+            collateral_info: None,
         },
     )
     .unwrap();
@@ -217,6 +227,7 @@ fn open_position() {
             positions: vec![PositionResponse {
                 idx: Uint128::from(1u128),
                 owner: "addr0000".to_string(),
+                extra_collaterals: vec![],
                 asset: Asset {
                     info: AssetInfo::Token {
                         contract_addr: "asset0000".to_string(),
@@ -230,6 +241,8 @@ fn open_position() {
                     amount: Uint128::from(1000000u128),
                 },
                 is_short: false,
+                liquidatable: false,
+                alert_ratio: None,
             }],
         }
     );
@@ -308,6 +321,7 @@ fn open_position() {
         PositionResponse {
             idx: Uint128::from(2u128),
             owner: "addr0000".to_string(),
+            extra_collaterals: vec![],
             asset: Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -321,6 +335,8 @@ fn open_position() {
                 amount: Uint128::from(1000000u128),
             },
             is_short: false,
+            liquidatable: false,
+            alert_ratio: None,
         }
     );
 
@@ -334,6 +350,7 @@ fn open_position() {
             limit: None,
             start_after: None,
             order_by: Some(OrderBy::Desc),
+            collateral_info: None,
         },
     )
     .unwrap();
@@ -345,6 +362,7 @@ fn open_position() {
                 PositionResponse {
                     idx: Uint128::from(2u128),
                     owner: "addr0000".to_string(),
+                    extra_collaterals: vec![],
                     asset: Asset {
                         info: AssetInfo::Token {
                             contract_addr: "asset0000".to_string(),
@@ -358,10 +376,13 @@ fn open_position() {
                         amount: Uint128::from(1000000u128),
                     },
                     is_short: false,
+                    liquidatable: false,
+                    alert_ratio: None,
                 },
                 PositionResponse {
                     idx: Uint128::from(1u128),
                     owner: "addr0000".to_string(),
+                    extra_collaterals: vec![],
                     asset: Asset {
                         info: AssetInfo::Token {
                             contract_addr: "asset0000".to_string(),
@@ -375,6 +396,8 @@ fn open_position() {
                         amount: Uint128::from(1000000u128),
                     },
                     is_short: false,
+                    liquidatable: false,
+                    alert_ratio: None,
                 }
             ],
         }
@@ -389,6 +412,7 @@ fn open_position() {
             limit: None,
             start_after: Some(Uint128::from(2u128)),
             order_by: Some(OrderBy::Desc),
+            collateral_info: None,
         },
     )
     .unwrap();
@@ -399,6 +423,7 @@ fn open_position() {
             positions: vec![PositionResponse {
                 idx: Uint128::from(1u128),
                 owner: "addr0000".to_string(),
+                extra_collaterals: vec![],
                 asset: Asset {
                     info: AssetInfo::Token {
                         contract_addr: "asset0000".to_string(),
@@ -412,9 +437,51 @@ fn open_position() {
                     amount: Uint128::from(1000000u128),
                 },
                 is_short: false,
+                liquidatable: false,
+                alert_ratio: None,
             }],
         }
     );
+
+    // filter by collateral token
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Positions {
+            owner_addr: None,
+            asset_token: None,
+            collateral_info: Some(AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            }),
+            limit: None,
+            start_after: None,
+            order_by: Some(OrderBy::Asc),
+        },
+    )
+    .unwrap();
+    let positions: PositionsResponse = from_binary(&res).unwrap();
+    assert_eq!(positions.positions.len(), 1);
+    assert_eq!(positions.positions[0].idx, Uint128::from(2u128));
+
+    // filter by native collateral denom
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Positions {
+            owner_addr: None,
+            asset_token: None,
+            collateral_info: Some(AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            }),
+            limit: None,
+            start_after: None,
+            order_by: Some(OrderBy::Asc),
+        },
+    )
+    .unwrap();
+    let positions: PositionsResponse = from_binary(&res).unwrap();
+    assert_eq!(positions.positions.len(), 1);
+    assert_eq!(positions.positions[0].idx, Uint128::from(1u128));
 }
 
 #[test]
@@ -445,6 +512,9 @@ fn deposit() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -543,6 +613,7 @@ fn deposit() {
         PositionResponse {
             idx: Uint128::from(1u128),
             owner: "addr0000".to_string(),
+            extra_collaterals: vec![],
             asset: Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -556,6 +627,8 @@ fn deposit() {
                 amount: Uint128::from(2000000u128),
             },
             is_short: false,
+            liquidatable: false,
+            alert_ratio: None,
         }
     );
 
@@ -603,6 +676,7 @@ fn deposit() {
         PositionResponse {
             idx: Uint128::from(2u128),
             owner: "addr0000".to_string(),
+            extra_collaterals: vec![],
             asset: Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -616,6 +690,8 @@ fn deposit() {
                 amount: Uint128::from(2000000u128),
             },
             is_short: false,
+            liquidatable: false,
+            alert_ratio: None,
         }
     );
 }
@@ -654,6 +730,9 @@ fn mint() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -901,6 +980,9 @@ fn burn() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -1186,6 +1268,9 @@ fn withdraw() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -1387,6 +1472,9 @@ fn auction() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -1630,6 +1718,7 @@ fn auction() {
             attr("liquidated_amount", "6666asset0000"),
             attr("tax_amount", "45659uusd"),
             attr("protocol_fee", "7732uusd"),
+            attr("liquidation_incentive", "0uusd"),
         ]
     );
 
@@ -1720,6 +1809,7 @@ fn auction() {
             attr("liquidated_amount", "200000asset0000"),
             attr("tax_amount", "0asset0001"),
             attr("protocol_fee", "8000asset0001"),
+            attr("liquidation_incentive", "0asset0001"),
         ]
     );
 }
@@ -1752,6 +1842,9 @@ fn liquidate_with_low_discount_test() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -1858,6 +1951,2302 @@ fn liquidate_with_low_discount_test() {
             attr("liquidated_amount", "892asset0000"),
             attr("tax_amount", "0uusd"),
             attr("protocol_fee", "9uusd"),
+            attr("liquidation_incentive", "0uusd"),
+        ]
+    );
+}
+
+#[test]
+fn max_positions_per_owner() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::percent(100)),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: Some(2),
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(150),
+        short_params: None,
+    };
+    let env = mock_env_with_block_time(1000);
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+
+    // open positions up to the cap
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PositionCount {
+            owner_addr: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let count_res: PositionCountResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        count_res,
+        PositionCountResponse {
+            owner_addr: "addr0000".to_string(),
+            count: 2,
+        }
+    );
+
+    // one more is rejected
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Owner has reached the maximum number of positions")
+    );
+
+    // other owners are not affected
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+}
+
+#[test]
+fn add_collateral() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::percent(100)),
+        (&"asset0001".to_string(), &Decimal::percent(50)),
+    ]);
+    deps.querier.with_collateral_infos(&[(
+        &"asset0001".to_string(),
+        &Decimal::percent(50),
+        &Decimal::one(),
+        &false,
+    )]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // open uusd-asset0000 position
+    let msg = ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(150),
+        short_params: None,
+    };
+    let env = mock_env_with_block_time(1000);
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    // open asset0001-asset0000 position
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        msg: to_binary(&Cw20HookMsg::OpenPosition {
+            asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            collateral_ratio: Decimal::percent(150),
+            short_params: None,
+        })
+        .unwrap(),
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000000u128),
+    });
+    let env = mock_env_with_block_time(1000);
+    let info = mock_info("asset0001", &[]);
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    // wrong denom attached
+    let msg = ExecuteMsg::AddCollateral {
+        position_idx: Uint128::from(1u128),
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "ukrw".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, StdError::generic_err("Wrong collateral"));
+
+    // native top up
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(500000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit"),
+            attr("position_idx", "1"),
+            attr("deposit_amount", "500000uusd"),
         ]
     );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Position {
+            position_idx: Uint128::from(1u128),
+        },
+    )
+    .unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert_eq!(position.collateral.amount, Uint128::from(1500000u128));
+
+    // token collateral can not be added with native coins
+    let msg = ExecuteMsg::AddCollateral {
+        position_idx: Uint128::from(2u128),
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(500000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Token collateral must be sent through the cw20 hook")
+    );
+
+    // token top up
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(500000u128),
+        msg: to_binary(&Cw20HookMsg::AddCollateral {
+            position_idx: Uint128::from(2u128),
+        })
+        .unwrap(),
+    });
+
+    // other token than the position collateral
+    let info = mock_info("asset0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, StdError::generic_err("Wrong collateral"));
+
+    let info = mock_info("asset0001", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Position {
+            position_idx: Uint128::from(2u128),
+        },
+    )
+    .unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert_eq!(position.collateral.amount, Uint128::from(1500000u128));
+}
+
+#[test]
+fn price_override() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::percent(100)),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // open position at 300% collateral ratio, minting 333333 asset
+    let msg = ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(300),
+        short_params: None,
+    };
+    let env = mock_env_with_block_time(1000);
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    // unauthorized
+    let msg = ExecuteMsg::SetPriceOverride {
+        asset_token: "asset0000".to_string(),
+        price_override: Some(Decimal::percent(200)),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    // a zero price would let anyone mint against dust collateral
+    let info = mock_info("owner0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::SetPriceOverride {
+            asset_token: "asset0000".to_string(),
+            price_override: Some(Decimal::zero()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("price_override must be greater than 0")
+    );
+
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // with the price doubled, 333333 * 2 * 1.5 > 600000 collateral left
+    let msg = ExecuteMsg::Withdraw {
+        position_idx: Uint128::from(1u128),
+        collateral: Some(Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(400000u128),
+        }),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Cannot withdraw collateral over than minimum collateral ratio")
+    );
+
+    // remove the override, the oracle price is used again
+    let msg_clear = ExecuteMsg::SetPriceOverride {
+        asset_token: "asset0000".to_string(),
+        price_override: None,
+    };
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner0000", &[]),
+        msg_clear,
+    )
+    .unwrap();
+
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the end_price of a migrated asset can not be overridden
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner0000", &[]),
+        ExecuteMsg::RegisterMigration {
+            asset_token: "asset0000".to_string(),
+            end_price: Decimal::one(),
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner0000", &[]),
+        ExecuteMsg::SetPriceOverride {
+            asset_token: "asset0000".to_string(),
+            price_override: Some(Decimal::percent(200)),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Operation is not allowed for the deprecated asset")
+    );
+}
+
+#[test]
+fn auction_with_liquidation_incentive() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::zero(),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::one()),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: Some(Uint128::from(5000u128)),
+        open_fee_rate: Decimal::zero(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // open uusd-asset0000 position, minting 666666 asset
+    let msg = ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(150),
+        short_params: None,
+    };
+    let env = mock_env_with_block_time(1000);
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::percent(110)),
+    ]);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(100000u128),
+        msg: to_binary(&Cw20HookMsg::Auction {
+            position_idx: Uint128::from(1u128),
+        })
+        .unwrap(),
+    });
+
+    let env = mock_env_with_block_time(1000u64);
+    let info = mock_info("asset0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Burn {
+                    amount: Uint128::from(100000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            // 100000 * 1.1 / 0.8 (discounted) + 5000 (incentive)
+            // - 100000 * 1.1 * 0.01 (protocol fee)
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0001".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(141400u128)
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "collector0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(1100u128)
+                }]
+            }))
+        ],
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "auction"),
+            attr("position_idx", "1"),
+            attr("owner", "addr0000"),
+            attr("return_collateral_amount", "141400uusd"),
+            attr("liquidated_amount", "100000asset0000"),
+            attr("tax_amount", "0uusd"),
+            attr("protocol_fee", "1100uusd"),
+            attr("liquidation_incentive", "5000uusd"),
+        ]
+    );
+
+    // incentive is taken from the position collateral
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Position {
+            position_idx: Uint128::from(1u128),
+        },
+    )
+    .unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert_eq!(position.collateral.amount, Uint128::from(857500u128));
+    assert_eq!(position.asset.amount, Uint128::from(566666u128));
+}
+
+#[test]
+fn auction_incentive_does_not_consume_remaining_collateral() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::zero(),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::one()),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: Some(Uint128::from(5000u128)),
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // open uusd-asset0000 position, minting 666666 asset
+    let msg = ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(150),
+        short_params: None,
+    };
+    let env = mock_env_with_block_time(1000);
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::percent(190)),
+    ]);
+
+    // 420000 * 1.9 / 0.8 = 997500 collateral is returned, leaving 2500 collateral,
+    // which is less than the 5000 incentive
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(420000u128),
+        msg: to_binary(&Cw20HookMsg::Auction {
+            position_idx: Uint128::from(1u128),
+        })
+        .unwrap(),
+    });
+    let env = mock_env_with_block_time(1000u64);
+    let info = mock_info("asset0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "auction"),
+            attr("position_idx", "1"),
+            attr("owner", "addr0000"),
+            attr("return_collateral_amount", "989520uusd"),
+            attr("liquidated_amount", "420000asset0000"),
+            attr("tax_amount", "0uusd"),
+            attr("protocol_fee", "7980uusd"),
+            attr("liquidation_incentive", "0uusd"),
+        ]
+    );
+
+    // the position keeps its debt and the remaining collateral
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Position {
+            position_idx: Uint128::from(1u128),
+        },
+    )
+    .unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert_eq!(position.collateral.amount, Uint128::from(2500u128));
+    assert_eq!(position.asset.amount, Uint128::from(246666u128));
+}
+
+#[test]
+fn open_position_with_open_fee() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::one()),
+    ]);
+    deps.querier.with_collateral_infos(&[(
+        &"asset0001".to_string(),
+        &Decimal::percent(50),
+        &Decimal::one(),
+        &false,
+    )]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let update_msg = |open_fee_rate| ExecuteMsg::UpdateConfig {
+        owner: None,
+        oracle: None,
+        collector: None,
+        collateral_oracle: None,
+        terraswap_factory: None,
+        lock: None,
+        token_code_id: None,
+        protocol_fee_rate: None,
+        staking: None,
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Some(open_fee_rate),
+        market_close_grace: None,
+        multi_collateral: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        update_msg(Decimal::one()),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("open_fee_rate must be smaller than 1")
+    );
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        update_msg(Decimal::percent(1)),
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        msg: to_binary(&Cw20HookMsg::OpenPosition {
+            asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            collateral_ratio: Decimal::percent(150),
+            short_params: None,
+        })
+        .unwrap(),
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000000u128),
+    });
+    let env = mock_env_with_block_time(1000);
+    let info = mock_info("asset0001", &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "open_position"),
+            attr("position_idx", "1"),
+            attr("mint_amount", "329999asset0000"), // 990000 * 0.5 / 1.5
+            attr("collateral_amount", "990000asset0001"),
+            attr("is_short", "false"),
+            attr("open_fee", "10000asset0001"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Mint {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(329999u128),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0001".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "collector0000".to_string(),
+                    amount: Uint128::from(10000u128),
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Position {
+            position_idx: Uint128::from(1u128),
+        },
+    )
+    .unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert_eq!(position.collateral.amount, Uint128::from(990000u128));
+}
+
+#[test]
+fn position_liquidatable() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::one()),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(200),
+        short_params: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let position_query = QueryMsg::Position {
+        position_idx: Uint128::from(1u128),
+    };
+    let res = query(deps.as_ref(), mock_env(), position_query.clone()).unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert!(!position.liquidatable);
+
+    // asset price doubles, ratio drops to 100%
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::percent(200)),
+    ]);
+    let res = query(deps.as_ref(), mock_env(), position_query).unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert!(position.liquidatable);
+}
+
+#[test]
+fn withdraw_at_boundary_collateral_ratio() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::one()),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // 5 uusd collateral mints 3 asset tokens
+    let msg = ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(5u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(150),
+        short_params: None,
+    };
+    let env = mock_env_with_block_time(1000);
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(5u128),
+        }],
+    );
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    // 3 * 1.5 = 4.5 collateral is required, rounded up to 5,
+    // so withdrawing 1 would leave the position at 4 / 3 < 1.5
+    let msg = ExecuteMsg::Withdraw {
+        position_idx: Uint128::from(1u128),
+        collateral: Some(Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1u128),
+        }),
+    };
+    let env = mock_env_with_block_time(1000);
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Cannot withdraw collateral over than minimum collateral ratio")
+    );
+}
+
+#[test]
+fn liquidation_scan() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::one()),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // open positions at 200%, 400% and 400%
+    for collateral_ratio in [200u64, 400u64, 400u64] {
+        let msg = ExecuteMsg::OpenPosition {
+            collateral: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(1000000u128),
+            },
+            asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            collateral_ratio: Decimal::percent(collateral_ratio),
+            short_params: None,
+        };
+        let info = mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        );
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    // asset price doubles, ratios drop to 100% and 200%
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::percent(200)),
+    ]);
+
+    let res: LiquidationScanResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::LiquidationScan {
+                asset_token: "asset0000".to_string(),
+                start_after: None,
+                limit: Some(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        LiquidationScanResponse {
+            asset_token: "asset0000".to_string(),
+            asset_price: Some(Decimal::percent(200)),
+            positions: vec![
+                LiquidationScanItem {
+                    idx: Uint128::from(1u128),
+                    owner: "addr0000".to_string(),
+                    collateral: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uusd".to_string(),
+                        },
+                        amount: Uint128::from(1000000u128),
+                    },
+                    asset: Asset {
+                        info: AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                        amount: Uint128::from(500000u128),
+                    },
+                    collateral_ratio: Some(Decimal::one()),
+                    liquidatable: true,
+                },
+                LiquidationScanItem {
+                    idx: Uint128::from(2u128),
+                    owner: "addr0000".to_string(),
+                    collateral: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uusd".to_string(),
+                        },
+                        amount: Uint128::from(1000000u128),
+                    },
+                    asset: Asset {
+                        info: AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                        amount: Uint128::from(250000u128),
+                    },
+                    collateral_ratio: Some(Decimal::percent(200)),
+                    liquidatable: false,
+                },
+            ],
+        }
+    );
+
+    // next page
+    let res: LiquidationScanResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::LiquidationScan {
+                asset_token: "asset0000".to_string(),
+                start_after: Some(Uint128::from(2u128)),
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.positions.len(), 1);
+    assert_eq!(res.positions[0].idx, Uint128::from(3u128));
+}
+
+#[test]
+fn position_collateral_serialization() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::one()),
+        (&"asset0001".to_string(), &Decimal::one()),
+    ]);
+    deps.querier.with_collateral_infos(&[(
+        &"asset0001".to_string(),
+        &Decimal::one(),
+        &Decimal::one(),
+        &false,
+    )]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // native collateral
+    let msg = ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(200),
+        short_params: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    );
+    let _res = execute(deps.as_mut(), mock_env_with_block_time(1000), info, msg).unwrap();
+
+    // token collateral
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        msg: to_binary(&Cw20HookMsg::OpenPosition {
+            asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            collateral_ratio: Decimal::percent(200),
+            short_params: None,
+        })
+        .unwrap(),
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000u128),
+    });
+    let info = mock_info("asset0001", &[]);
+    let _res = execute(deps.as_mut(), mock_env_with_block_time(1000), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Positions {
+            owner_addr: Some("addr0000".to_string()),
+            asset_token: None,
+            collateral_info: None,
+            start_after: None,
+            limit: None,
+            order_by: Some(OrderBy::Asc),
+        },
+    )
+    .unwrap();
+    let json = String::from_utf8(res.to_vec()).unwrap();
+    assert!(
+        json.contains(r#""collateral":{"info":{"native_token":{"denom":"uusd"}},"amount":"1000"}"#)
+    );
+    assert!(json.contains(
+        r#""collateral":{"info":{"token":{"contract_addr":"asset0001"}},"amount":"1000"}"#
+    ));
+
+    let positions: PositionsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        positions
+            .positions
+            .into_iter()
+            .map(|p| p.collateral.info)
+            .collect::<Vec<AssetInfo>>(),
+        vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn max_initial_ltv() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::one()),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // only the owner can set the cap
+    let msg = ExecuteMsg::SetMaxInitialLtv {
+        asset_token: "asset0000".to_string(),
+        max_initial_ltv: Some(Decimal::percent(50)),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::SetMaxInitialLtv {
+            asset_token: "asset0000".to_string(),
+            max_initial_ltv: Some(Decimal::zero()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("max_initial_ltv must be greater than 0 and at most 1")
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "set_max_initial_ltv"),
+            attr("asset_token", "asset0000"),
+            attr("max_initial_ltv", "0.5"),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AssetConfig {
+            asset_token: "asset0000".to_string(),
+        },
+    )
+    .unwrap();
+    let asset_config: AssetConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(asset_config.max_initial_ltv, Some(Decimal::percent(50)));
+
+    let open_position_msg = |collateral_ratio| ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio,
+        short_params: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    );
+
+    // above the min collateral ratio, but over-leveraged
+    let res = execute(
+        deps.as_mut(),
+        mock_env_with_block_time(1000),
+        info.clone(),
+        open_position_msg(Decimal::percent(160)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Loan-to-value exceeds the max initial ltv of the asset")
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_with_block_time(1000),
+        info,
+        open_position_msg(Decimal::percent(200)),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2], attr("mint_amount", "500asset0000"));
+
+    // minting more would exceed the cap
+    let msg = ExecuteMsg::Mint {
+        position_idx: Uint128::from(1u128),
+        asset: Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            amount: Uint128::from(1u128),
+        },
+        short_params: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env_with_block_time(1000), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Loan-to-value exceeds the max initial ltv of the asset")
+    );
+}
+
+#[test]
+fn position_alert() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::one()),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(200),
+        short_params: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    );
+    let _res = execute(deps.as_mut(), mock_env_with_block_time(1000), info, msg).unwrap();
+
+    let msg = ExecuteMsg::SetPositionAlert {
+        position_idx: Uint128::from(1u128),
+        ratio: Some(Decimal::percent(170)),
+    };
+    let info = mock_info("addr0001", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "set_position_alert"),
+            attr("position_idx", "1"),
+            attr("ratio", "1.7"),
+        ]
+    );
+
+    let position_query = QueryMsg::Position {
+        position_idx: Uint128::from(1u128),
+    };
+    let res = query(deps.as_ref(), mock_env(), position_query.clone()).unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert_eq!(position.alert_ratio, Some(Decimal::percent(170)));
+
+    // clear the alert
+    let msg = ExecuteMsg::SetPositionAlert {
+        position_idx: Uint128::from(1u128),
+        ratio: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), position_query).unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert_eq!(position.alert_ratio, None);
+}
+
+#[test]
+fn market_close_grace() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::one()),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let update_config_msg = |market_close_grace| ExecuteMsg::UpdateConfig {
+        owner: None,
+        oracle: None,
+        collector: None,
+        collateral_oracle: None,
+        terraswap_factory: None,
+        lock: None,
+        token_code_id: None,
+        protocol_fee_rate: None,
+        staking: None,
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: None,
+        market_close_grace: Some(market_close_grace),
+        multi_collateral: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        update_config_msg(61),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("market_close_grace can not be greater than 60")
+    );
+
+    let open_position_msg = ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(200),
+        short_params: None,
+    };
+    let sender_info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    );
+
+    // the price is 10 seconds past the expire time
+    let now = mock_env().block.time.seconds();
+    deps.querier
+        .with_oracle_price_last_updated("asset0000", now - PRICE_EXPIRE_TIME - 10);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        sender_info.clone(),
+        open_position_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Querier contract error: Price is too old")
+    );
+
+    // accepted at the grace boundary
+    let _res = execute(deps.as_mut(), mock_env(), info, update_config_msg(10)).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.market_close_grace, 10);
+
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        sender_info.clone(),
+        open_position_msg.clone(),
+    )
+    .unwrap();
+
+    // rejected one second past the grace
+    deps.querier
+        .with_oracle_price_last_updated("asset0000", now - PRICE_EXPIRE_TIME - 11);
+    let res = execute(deps.as_mut(), mock_env(), sender_info, open_position_msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Querier contract error: Price is too old")
+    );
+}
+
+#[test]
+fn suspended_collateral() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::one()),
+    ]);
+    deps.querier.with_collateral_infos(&[(
+        &"collateral0000".to_string(),
+        &Decimal::one(),
+        &Decimal::one(),
+        &false,
+    )]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::zero(),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // open collateral0000-asset0000 position, minting 5000 asset
+    let open_position_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        msg: to_binary(&Cw20HookMsg::OpenPosition {
+            asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            collateral_ratio: Decimal::percent(200),
+            short_params: None,
+        })
+        .unwrap(),
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(10000u128),
+    });
+    let info = mock_info("collateral0000", &[]);
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        open_position_msg.clone(),
+    )
+    .unwrap();
+
+    deps.querier.with_suspended_collateral("collateral0000");
+
+    // new positions, deposits and mints are rejected
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), open_position_msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("The collateral asset provided is suspended")
+    );
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            position_idx: Uint128::from(1u128),
+        })
+        .unwrap(),
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000u128),
+    });
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("The collateral asset provided is suspended")
+    );
+
+    let msg = ExecuteMsg::Mint {
+        position_idx: Uint128::from(1u128),
+        asset: Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            amount: Uint128::from(100u128),
+        },
+        short_params: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("The collateral asset provided is suspended")
+    );
+
+    // the owner can still withdraw the excess collateral
+    let msg = ExecuteMsg::Withdraw {
+        position_idx: Uint128::from(1u128),
+        collateral: Some(Asset {
+            info: AssetInfo::Token {
+                contract_addr: "collateral0000".to_string(),
+            },
+            amount: Uint128::from(1000u128),
+        }),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // and the position can still be auctioned once undercollateralized
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::percent(150)),
+    ]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::Auction {
+            position_idx: Uint128::from(1u128),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages[1],
+        // 1000 * 1.5 / 0.8 (discounted)
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "collateral0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0001".to_string(),
+                amount: Uint128::from(1875u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })),
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Position {
+            position_idx: Uint128::from(1u128),
+        },
+    )
+    .unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert_eq!(position.collateral.amount, Uint128::from(7125u128));
+    assert_eq!(position.asset.amount, Uint128::from(4000u128));
+}
+
+#[test]
+fn multi_collateral_position() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::zero(),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::one()),
+    ]);
+    deps.querier.with_collateral_infos(&[(
+        &"asset0001".to_string(),
+        &Decimal::percent(50),
+        &Decimal::one(),
+        &false,
+    )]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let open_position_msg = ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(200),
+        short_params: None,
+    };
+    let env = mock_env_with_block_time(1000);
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let _res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        open_position_msg.clone(),
+    )
+    .unwrap();
+
+    let deposit_msg = |position_idx: u128, amount: u128| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr0000".to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::Deposit {
+                position_idx: Uint128::from(position_idx),
+            })
+            .unwrap(),
+        })
+    };
+
+    // positions opened before multi_collateral is enabled only take their collateral
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("asset0001", &[]),
+        deposit_msg(1, 1000000),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("Wrong collateral"));
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        oracle: None,
+        collector: None,
+        collateral_oracle: None,
+        terraswap_factory: None,
+        lock: None,
+        token_code_id: None,
+        protocol_fee_rate: None,
+        staking: None,
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: None,
+        market_close_grace: None,
+        multi_collateral: Some(true),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert!(config.multi_collateral);
+
+    // open uusd-asset0000 position 2 at 200%, minting 500000 asset
+    let _res = execute(deps.as_mut(), env.clone(), info, open_position_msg).unwrap();
+
+    // add 1000000 asset0001, worth 500000 uusd
+    let _res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("asset0001", &[]),
+        deposit_msg(2, 1000000),
+    )
+    .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Position {
+            position_idx: Uint128::from(2u128),
+        },
+    )
+    .unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        position.collateral,
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        }
+    );
+    assert_eq!(
+        position.extra_collaterals,
+        vec![Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        }]
+    );
+
+    // the combined 1500000 uusd backs at most 1000000 asset at 150%
+    let mint_msg = |amount: u128| ExecuteMsg::Mint {
+        position_idx: Uint128::from(2u128),
+        asset: Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            amount: Uint128::from(amount),
+        },
+        short_params: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        mint_msg(500001),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Cannot mint asset over than min collateral ratio")
+    );
+    let _res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        mint_msg(400000),
+    )
+    .unwrap();
+
+    // 900000 asset requires 1350000 uusd, so at most 300000 asset0001 can be withdrawn
+    let withdraw_msg = |amount: u128| ExecuteMsg::Withdraw {
+        position_idx: Uint128::from(2u128),
+        collateral: Some(Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            amount: Uint128::from(amount),
+        }),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        withdraw_msg(300002),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Cannot withdraw collateral over than minimum collateral ratio")
+    );
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        withdraw_msg(200000),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset0001".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(200000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Position {
+            position_idx: Uint128::from(2u128),
+        },
+    )
+    .unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert_eq!(position.asset.amount, Uint128::from(900000u128));
+    assert_eq!(
+        position.extra_collaterals[0].amount,
+        Uint128::from(800000u128)
+    );
+    assert!(!position.liquidatable);
+
+    // at 1.1, 900000 asset requires 1485000 uusd > 1400000 combined collateral
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::percent(110)),
+    ]);
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::LiquidationScan {
+            asset_token: "asset0000".to_string(),
+            start_after: Some(Uint128::from(1u128)),
+            limit: None,
+        },
+    )
+    .unwrap();
+    let scan: LiquidationScanResponse = from_binary(&res).unwrap();
+    assert_eq!(scan.positions[0].idx, Uint128::from(2u128));
+    assert_eq!(
+        scan.positions[0].collateral_ratio,
+        Some(Decimal::from_ratio(1400000u128, 990000u128))
+    );
+    assert!(scan.positions[0].liquidatable);
+
+    // the auction sells the uusd collateral, asset0001 takes its place
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(900000u128),
+        msg: to_binary(&Cw20HookMsg::Auction {
+            position_idx: Uint128::from(2u128),
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), env, mock_info("asset0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes[3],
+        attr("return_collateral_amount", "992000uusd")
+    );
+    assert_eq!(
+        res.attributes[4],
+        attr("liquidated_amount", "727273asset0000")
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Position {
+            position_idx: Uint128::from(2u128),
+        },
+    )
+    .unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        position.collateral,
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            amount: Uint128::from(800000u128),
+        }
+    );
+    assert_eq!(position.extra_collaterals, vec![]);
+    assert_eq!(position.asset.amount, Uint128::from(172727u128));
+
+    // the position is now indexed by its new collateral
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Positions {
+            owner_addr: None,
+            asset_token: None,
+            collateral_info: Some(AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            }),
+            start_after: None,
+            limit: None,
+            order_by: None,
+        },
+    )
+    .unwrap();
+    let positions: PositionsResponse = from_binary(&res).unwrap();
+    assert_eq!(positions.positions.len(), 1);
+    assert_eq!(positions.positions[0].idx, Uint128::from(2u128));
+}
+
+#[test]
+fn multi_collateral_position_deprecated_burn() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::zero(),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::one()),
+    ]);
+    deps.querier.with_collateral_infos(&[(
+        &"asset0001".to_string(),
+        &Decimal::percent(50),
+        &Decimal::one(),
+        &false,
+    )]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        oracle: None,
+        collector: None,
+        collateral_oracle: None,
+        terraswap_factory: None,
+        lock: None,
+        token_code_id: None,
+        protocol_fee_rate: None,
+        staking: None,
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: None,
+        market_close_grace: None,
+        multi_collateral: Some(true),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // open uusd-asset0000 position at 200%, minting 500000 asset, and add 1000000 asset0001
+    let msg = ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(200),
+        short_params: None,
+    };
+    let env = mock_env_with_block_time(1000);
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000000u128),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            position_idx: Uint128::from(1u128),
+        })
+        .unwrap(),
+    });
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("asset0001", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterMigration {
+        asset_token: "asset0000".to_string(),
+        end_price: Decimal::one(),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    // burning a sixth of the combined 1500000 uusd value returns a sixth of each collateral
+    let burn_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(250000u128),
+        msg: to_binary(&Cw20HookMsg::Burn {
+            position_idx: Uint128::from(1u128),
+        })
+        .unwrap(),
+    });
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("asset0000", &[]),
+        burn_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "burn"),
+            attr("position_idx", "1"),
+            attr("burn_amount", "250000asset0000"),
+            attr("protocol_fee", "1666uusd,1666asset0001"),
+            attr("refund_collateral_amount", "165000uusd,165000asset0001"),
+        ]
+    );
+    assert_eq!(
+        res.messages[3..],
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0001".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(165000u128),
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0001".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0001".to_string(),
+                    amount: Uint128::from(165000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Position {
+            position_idx: Uint128::from(1u128),
+        },
+    )
+    .unwrap();
+    let position: PositionResponse = from_binary(&res).unwrap();
+    assert_eq!(position.collateral.amount, Uint128::from(833334u128));
+    assert_eq!(
+        position.extra_collaterals[0].amount,
+        Uint128::from(833334u128)
+    );
+    assert_eq!(position.asset.amount, Uint128::from(250000u128));
+
+    // once the debt is burned, the owner withdraws all collaterals and closes the position
+    let _res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("asset0000", &[]),
+        burn_msg,
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        position_idx: Uint128::from(1u128),
+        collateral: None,
+    };
+    let res = execute(deps.as_mut(), env, mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(666668u128),
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0001".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(666668u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    let _res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Position {
+            position_idx: Uint128::from(1u128),
+        },
+    )
+    .unwrap_err();
 }
@@ -50,6 +50,9 @@ fn pre_ipo_assets() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
     let creator_env = mock_env();
     let creator_info = mock_info("addr0000", &[]);
@@ -283,6 +286,9 @@ fn pre_ipo_assets() {
             min_collateral_ratio: Decimal::percent(150),
             end_price: None,
             ipo_params: None,
+            price_override: None,
+            max_initial_ltv: None,
+            lock_period: None,
         }
     );
 
@@ -2,13 +2,14 @@ use crate::contract::{execute, instantiate, query};
 use crate::testing::mock_querier::mock_dependencies;
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, from_binary, to_binary, BankMsg, BlockInfo, Coin, CosmosMsg, Decimal, Env, SubMsg,
-    Timestamp, Uint128, WasmMsg,
+    attr, from_binary, to_binary, BankMsg, BlockInfo, Coin, CosmosMsg, Decimal, Env, StdError,
+    SubMsg, Timestamp, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use mirror_protocol::lock::ExecuteMsg as LockExecuteMsg;
 use mirror_protocol::mint::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, PositionResponse, QueryMsg, ShortParams,
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, PositionResponse, PositionsResponse, QueryMsg,
+    ShortParams,
 };
 use mirror_protocol::staking::ExecuteMsg as StakingExecuteMsg;
 use terraswap::{
@@ -52,6 +53,9 @@ fn open_short_position() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
 
     let env = mock_env();
@@ -158,6 +162,7 @@ fn open_short_position() {
                 msg: to_binary(&LockExecuteMsg::LockPositionFundsHook {
                     position_idx: Uint128::from(1u128),
                     receiver: "addr0000".to_string(),
+                    lock_period: None,
                 })
                 .unwrap(),
             })),
@@ -188,6 +193,7 @@ fn open_short_position() {
         PositionResponse {
             idx: Uint128::from(1u128),
             owner: "addr0000".to_string(),
+            extra_collaterals: vec![],
             asset: Asset {
                 info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
@@ -201,8 +207,68 @@ fn open_short_position() {
                 amount: Uint128::from(1000000u128),
             },
             is_short: true,
+            liquidatable: false,
+            alert_ratio: None,
         }
     );
+
+    // the asset lock period is passed to the lock contract
+    let msg = ExecuteMsg::SetLockPeriod {
+        asset_token: "asset0000".to_string(),
+        lock_period: Some(86400u64),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "set_lock_period"),
+            attr("asset_token", "asset0000"),
+            attr("lock_period", "86400"),
+        ]
+    );
+
+    let msg = ExecuteMsg::OpenPosition {
+        collateral: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        },
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(150),
+        short_params: Some(ShortParams {
+            belief_price: None,
+            max_spread: None,
+        }),
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), mock_env_with_block_time(1000), info, msg).unwrap();
+    assert_eq!(
+        res.messages[2],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "lock0000".to_string(),
+            funds: vec![],
+            msg: to_binary(&LockExecuteMsg::LockPositionFundsHook {
+                position_idx: Uint128::from(2u128),
+                receiver: "addr0000".to_string(),
+                lock_period: Some(86400u64),
+            })
+            .unwrap(),
+        }))
+    );
 }
 
 #[test]
@@ -227,6 +293,9 @@ fn mint_short_position() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
 
     let env = mock_env();
@@ -343,6 +412,7 @@ fn mint_short_position() {
                 msg: to_binary(&LockExecuteMsg::LockPositionFundsHook {
                     position_idx: Uint128::from(1u128),
                     receiver: "addr0000".to_string(),
+                    lock_period: None,
                 })
                 .unwrap(),
             })),
@@ -382,6 +452,9 @@ fn burn_short_position() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -518,6 +591,9 @@ fn auction_short_position() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -605,6 +681,7 @@ fn auction_short_position() {
             attr("liquidated_amount", "100asset0000"),
             attr("tax_amount", "0uusd"),
             attr("protocol_fee", "1uusd"),
+            attr("liquidation_incentive", "0uusd"),
         ]
     );
 
@@ -668,6 +745,9 @@ fn close_short_position() {
         base_denom,
         token_code_id: TOKEN_CODE_ID,
         protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -757,3 +837,141 @@ fn close_short_position() {
             .unwrap(),
         }))))
 }
+
+#[test]
+fn query_short_positions() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_oracle_price(&[
+        (&"uusd".to_string(), &Decimal::one()),
+        (&"asset0000".to_string(), &Decimal::percent(100)),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle: "oracle0000".to_string(),
+        collector: "collector0000".to_string(),
+        collateral_oracle: "collateraloracle0000".to_string(),
+        staking: "staking0000".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        lock: "lock0000".to_string(),
+        base_denom: "uusd".to_string(),
+        token_code_id: TOKEN_CODE_ID,
+        protocol_fee_rate: Decimal::percent(1),
+        max_positions_per_owner: None,
+        liquidation_incentive: None,
+        open_fee_rate: Decimal::zero(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0000".to_string(),
+        auction_discount: Decimal::percent(20),
+        min_collateral_ratio: Decimal::percent(150),
+        ipo_params: None,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // register terraswap pair
+    deps.querier.with_terraswap_pair(&[(
+        &"uusd".to_string(),
+        &"asset0000".to_string(),
+        &"pair0000".to_string(),
+    )]);
+
+    let collateral = Asset {
+        info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        amount: Uint128::from(1000000u128),
+    };
+    let env = mock_env_with_block_time(1000);
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+
+    // short position
+    let msg = ExecuteMsg::OpenPosition {
+        collateral: collateral.clone(),
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(150),
+        short_params: Some(ShortParams {
+            belief_price: None,
+            max_spread: None,
+        }),
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // non-short position
+    let msg = ExecuteMsg::OpenPosition {
+        collateral,
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        collateral_ratio: Decimal::percent(150),
+        short_params: None,
+    };
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ShortPositions {
+            owner_addr: "addr0000".to_string(),
+            start_after: None,
+            limit: None,
+            order_by: None,
+        },
+    )
+    .unwrap();
+    let positions: PositionsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        positions,
+        PositionsResponse {
+            positions: vec![PositionResponse {
+                idx: Uint128::from(1u128),
+                owner: "addr0000".to_string(),
+                extra_collaterals: vec![],
+                asset: Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: "asset0000".to_string(),
+                    },
+                    amount: Uint128::from(666666u128),
+                },
+                collateral: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::from(1000000u128),
+                },
+                is_short: true,
+                liquidatable: false,
+                alert_ratio: None,
+            }],
+        }
+    );
+
+    // owner without short positions
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ShortPositions {
+            owner_addr: "addr0001".to_string(),
+            start_after: None,
+            limit: None,
+            order_by: None,
+        },
+    )
+    .unwrap();
+    let positions: PositionsResponse = from_binary(&res).unwrap();
+    assert_eq!(positions, PositionsResponse { positions: vec![] });
+}
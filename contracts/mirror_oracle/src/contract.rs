@@ -7,17 +7,21 @@ use cosmwasm_std::{
 };
 
 use crate::math::decimal_division;
+use crate::migration::{migrate_config, migrate_prices};
 use crate::state::{
     read_config, read_feeder, read_price, read_prices, store_config, store_feeder, store_price,
     Config, PriceInfo,
 };
 
-use mirror_protocol::common::OrderBy;
+use mirror_protocol::common::{OrderBy, OwnerInfo};
 use mirror_protocol::oracle::{
-    ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, MigrateMsg, PriceResponse,
-    PricesResponse, PricesResponseElem, QueryMsg,
+    ConfigResponse, ExecuteMsg, FeederResponse, FeederStatusResponse, InstantiateMsg, MigrateMsg,
+    PriceResponse, PricesResponse, PricesResponseElem, QueryMsg, RawPricesResponse,
 };
 
+/// Seconds after which a price is considered stale, unless feeds are rate limited to a longer interval
+pub const PRICE_MAX_AGE: u64 = 60;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -29,7 +33,9 @@ pub fn instantiate(
         deps.storage,
         &Config {
             owner: deps.api.addr_canonicalize(&msg.owner)?,
+            pending_owner: None,
             base_asset: msg.base_asset,
+            min_feed_interval: msg.min_feed_interval,
         },
     )?;
 
@@ -39,11 +45,17 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
     match msg {
-        ExecuteMsg::UpdateConfig { owner } => try_update_config(deps, info, owner),
+        ExecuteMsg::UpdateConfig {
+            owner,
+            min_feed_interval,
+        } => try_update_config(deps, info, owner, min_feed_interval),
+        ExecuteMsg::ProposeOwner { owner } => try_propose_owner(deps, info, owner),
+        ExecuteMsg::AcceptOwnership {} => try_accept_ownership(deps, info),
         ExecuteMsg::RegisterAsset {
             asset_token,
             feeder,
-        } => try_register_asset(deps, info, asset_token, feeder),
+            initial_price,
+        } => try_register_asset(deps, env, info, asset_token, feeder, initial_price),
         ExecuteMsg::FeedPrice { prices } => try_feed_price(deps, env, info, prices),
     }
 }
@@ -52,25 +64,65 @@ pub fn try_update_config(
     deps: DepsMut,
     info: MessageInfo,
     owner: Option<String>,
+    min_feed_interval: Option<u64>,
 ) -> StdResult<Response> {
     let mut config: Config = read_config(deps.storage)?;
     if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
         return Err(StdError::generic_err("unauthorized"));
     }
 
+    // the new owner still has to accept the ownership
     if let Some(owner) = owner {
-        config.owner = deps.api.addr_canonicalize(&owner)?;
+        config.pending_owner = Some(deps.api.addr_canonicalize(&owner)?);
+    }
+
+    if let Some(min_feed_interval) = min_feed_interval {
+        config.min_feed_interval = min_feed_interval;
     }
 
     store_config(deps.storage, &config)?;
     Ok(Response::default())
 }
 
+pub fn try_propose_owner(deps: DepsMut, info: MessageInfo, owner: String) -> StdResult<Response> {
+    let mut config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    config.pending_owner = Some(deps.api.addr_canonicalize(&owner)?);
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_owner"),
+        attr("pending_owner", owner),
+    ]))
+}
+
+pub fn try_accept_ownership(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let mut config: Config = read_config(deps.storage)?;
+    let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if config.pending_owner != Some(sender_raw.clone()) {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    config.owner = sender_raw;
+    config.pending_owner = None;
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "accept_ownership"),
+        attr("owner", info.sender.as_str()),
+    ]))
+}
+
 pub fn try_register_asset(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     asset_token: String,
     feeder: String,
+    initial_price: Option<Decimal>,
 ) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
     if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
@@ -81,14 +133,23 @@ pub fn try_register_asset(
 
     // check if it is a new asset
     if read_feeder(deps.storage, &asset_token_raw).is_err() {
-        store_price(
-            deps.storage,
-            &asset_token_raw,
-            &PriceInfo {
+        let price_info = match initial_price {
+            Some(price) => PriceInfo {
+                price,
+                last_updated_time: env.block.time.seconds(),
+                is_initial: true,
+            },
+            None => PriceInfo {
                 price: Decimal::zero(),
                 last_updated_time: 0u64,
+                is_initial: false,
             },
-        )?;
+        };
+        store_price(deps.storage, &asset_token_raw, &price_info)?;
+    } else if initial_price.is_some() {
+        return Err(StdError::generic_err(
+            "initial_price can only be set for a new asset",
+        ));
     }
 
     // update/store feeder
@@ -107,6 +168,7 @@ pub fn try_feed_price(
     info: MessageInfo,
     prices: Vec<(String, Decimal)>,
 ) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
     let feeder_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
 
     let mut attributes = vec![attr("action", "price_feed")];
@@ -120,9 +182,17 @@ pub fn try_feed_price(
             return Err(StdError::generic_err("unauthorized"));
         }
 
+        // the registration price does not count towards the feed interval
         let mut state: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+        if !state.is_initial
+            && env.block.time.seconds() < state.last_updated_time + config.min_feed_interval
+        {
+            return Err(StdError::generic_err("Price feed is too frequent"));
+        }
+
         state.last_updated_time = env.block.time.seconds();
         state.price = price.1;
+        state.is_initial = false;
 
         store_price(deps.storage, &asset_token_raw, &state)?;
     }
@@ -131,9 +201,10 @@ pub fn try_feed_price(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Owner {} => to_binary(&query_owner(deps)?),
         QueryMsg::Feeder { asset_token } => to_binary(&query_feeder(deps, asset_token)?),
         QueryMsg::Price {
             base_asset,
@@ -144,14 +215,36 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             limit,
             order_by,
         } => to_binary(&query_prices(deps, start_after, limit, order_by)?),
+        QueryMsg::RawPrices { assets } => to_binary(&query_raw_prices(deps, assets)?),
+        QueryMsg::FeederStatus { asset_token } => {
+            to_binary(&query_feeder_status(deps, env, asset_token)?)
+        }
     }
 }
 
+fn query_owner(deps: Deps) -> StdResult<OwnerInfo> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(OwnerInfo {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        pending_owner: config
+            .pending_owner
+            .map(|pending_owner| deps.api.addr_humanize(&pending_owner))
+            .transpose()?
+            .map(|pending_owner| pending_owner.to_string()),
+    })
+}
+
 fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let state = read_config(deps.storage)?;
     let resp = ConfigResponse {
         owner: deps.api.addr_humanize(&state.owner)?.to_string(),
+        pending_owner: state
+            .pending_owner
+            .map(|pending_owner| deps.api.addr_humanize(&pending_owner))
+            .transpose()?
+            .map(|pending_owner| pending_owner.to_string()),
         base_asset: state.base_asset,
+        min_feed_interval: state.min_feed_interval,
     };
 
     Ok(resp)
@@ -167,25 +260,37 @@ fn query_feeder(deps: Deps, asset_token: String) -> StdResult<FeederResponse> {
     Ok(resp)
 }
 
-fn query_price(deps: Deps, base: String, quote: String) -> StdResult<PriceResponse> {
+fn query_feeder_status(
+    deps: Deps,
+    env: Env,
+    asset_token: String,
+) -> StdResult<FeederStatusResponse> {
     let config: Config = read_config(deps.storage)?;
-    let quote_price = if config.base_asset == quote {
-        PriceInfo {
-            price: Decimal::one(),
-            last_updated_time: u64::MAX,
-        }
-    } else {
-        read_price(deps.storage, &deps.api.addr_canonicalize(quote.as_str())?)?
-    };
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let feeder = read_feeder(deps.storage, &asset_token_raw)?;
+
+    let price_info: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+    let age = env
+        .block
+        .time
+        .seconds()
+        .saturating_sub(price_info.last_updated_time);
+    let healthy = price_info.last_updated_time != 0
+        && !price_info.is_initial
+        && age <= PRICE_MAX_AGE.max(config.min_feed_interval);
+
+    Ok(FeederStatusResponse {
+        asset_token,
+        feeder: deps.api.addr_humanize(&feeder)?.to_string(),
+        last_updated_time: price_info.last_updated_time,
+        healthy,
+    })
+}
 
-    let base_price = if config.base_asset == base {
-        PriceInfo {
-            price: Decimal::one(),
-            last_updated_time: u64::MAX,
-        }
-    } else {
-        read_price(deps.storage, &deps.api.addr_canonicalize(base.as_str())?)?
-    };
+fn query_price(deps: Deps, base: String, quote: String) -> StdResult<PriceResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let quote_price = read_asset_price(deps, &config, &quote)?;
+    let base_price = read_asset_price(deps, &config, &base)?;
 
     Ok(PriceResponse {
         rate: decimal_division(base_price.price, quote_price.price),
@@ -194,6 +299,18 @@ fn query_price(deps: Deps, base: String, quote: String) -> StdResult<PriceRespon
     })
 }
 
+fn read_asset_price(deps: Deps, config: &Config, asset: &str) -> StdResult<PriceInfo> {
+    if config.base_asset == asset {
+        Ok(PriceInfo {
+            price: Decimal::one(),
+            last_updated_time: u64::MAX,
+            is_initial: false,
+        })
+    } else {
+        read_price(deps.storage, &deps.api.addr_canonicalize(asset)?)
+    }
+}
+
 fn query_prices(
     deps: Deps,
     start_after: Option<String>,
@@ -211,7 +328,28 @@ fn query_prices(
     Ok(PricesResponse { prices })
 }
 
+fn query_raw_prices(deps: Deps, assets: Vec<String>) -> StdResult<RawPricesResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let prices: Vec<PricesResponseElem> = assets
+        .into_iter()
+        .map(|asset_token| {
+            let price_info = read_asset_price(deps, &config, &asset_token)?;
+            Ok(PricesResponseElem {
+                asset_token,
+                price: price_info.price,
+                last_updated_time: price_info.last_updated_time,
+                is_initial: price_info.is_initial,
+            })
+        })
+        .collect::<StdResult<Vec<PricesResponseElem>>>()?;
+
+    Ok(RawPricesResponse { prices })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    migrate_config(deps.storage, msg.min_feed_interval)?;
+    migrate_prices(deps.storage)?;
+
     Ok(Response::default())
 }
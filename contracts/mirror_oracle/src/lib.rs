@@ -1,5 +1,6 @@
 pub mod contract;
 pub mod math;
+pub mod migration;
 pub mod state;
 
 #[cfg(test)]
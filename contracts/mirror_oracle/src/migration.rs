@@ -0,0 +1,118 @@
+use cosmwasm_std::{CanonicalAddr, Decimal, Order, StdResult, Storage};
+use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlySingleton, Singleton};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::{Config, PriceInfo, KEY_CONFIG, PREFIX_PRICE};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub owner: CanonicalAddr,
+    pub base_asset: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyPriceInfo {
+    pub price: Decimal,
+    pub last_updated_time: u64,
+}
+
+pub fn migrate_config(storage: &mut dyn Storage, min_feed_interval: u64) -> StdResult<()> {
+    let legacy_store: ReadonlySingleton<LegacyConfig> = singleton_read(storage, KEY_CONFIG);
+    let legacy_config: LegacyConfig = legacy_store.load()?;
+    let config = Config {
+        owner: legacy_config.owner,
+        pending_owner: None,
+        base_asset: legacy_config.base_asset,
+        min_feed_interval,
+    };
+    let mut store: Singleton<Config> = singleton(storage, KEY_CONFIG);
+    store.save(&config)?;
+    Ok(())
+}
+
+pub fn migrate_prices(storage: &mut dyn Storage) -> StdResult<()> {
+    let legacy_prices_bucket: Bucket<LegacyPriceInfo> = Bucket::new(storage, PREFIX_PRICE);
+
+    let mut prices: Vec<(Vec<u8>, LegacyPriceInfo)> = vec![];
+    for item in legacy_prices_bucket.range(None, None, Order::Ascending) {
+        prices.push(item?);
+    }
+
+    let mut new_prices_bucket: Bucket<PriceInfo> = Bucket::new(storage, PREFIX_PRICE);
+    for (asset_token, price_info) in prices.into_iter() {
+        new_prices_bucket.save(
+            &asset_token,
+            &PriceInfo {
+                price: price_info.price,
+                last_updated_time: price_info.last_updated_time,
+                is_initial: false,
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod migrate_tests {
+    use crate::state::{read_config, read_price};
+
+    use super::*;
+    use cosmwasm_std::{testing::mock_dependencies, Api};
+
+    pub fn config_old_store(storage: &mut dyn Storage) -> Singleton<'_, LegacyConfig> {
+        Singleton::new(storage, KEY_CONFIG)
+    }
+
+    #[test]
+    fn test_config_migration() {
+        let mut deps = mock_dependencies(&[]);
+        let mut legacy_config_store = config_old_store(&mut deps.storage);
+        legacy_config_store
+            .save(&LegacyConfig {
+                owner: deps.api.addr_canonicalize("owner0000").unwrap(),
+                base_asset: "uusd".to_string(),
+            })
+            .unwrap();
+
+        migrate_config(&mut deps.storage, 30).unwrap();
+
+        let config: Config = read_config(&deps.storage).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                owner: deps.api.addr_canonicalize("owner0000").unwrap(),
+                pending_owner: None,
+                base_asset: "uusd".to_string(),
+                min_feed_interval: 30,
+            }
+        )
+    }
+
+    #[test]
+    fn test_prices_migration() {
+        let mut deps = mock_dependencies(&[]);
+        let asset_token = deps.api.addr_canonicalize("asset0000").unwrap();
+        Bucket::<LegacyPriceInfo>::new(&mut deps.storage, PREFIX_PRICE)
+            .save(
+                asset_token.as_slice(),
+                &LegacyPriceInfo {
+                    price: Decimal::percent(120),
+                    last_updated_time: 1000,
+                },
+            )
+            .unwrap();
+
+        migrate_prices(&mut deps.storage).unwrap();
+
+        assert_eq!(
+            read_price(&deps.storage, &asset_token).unwrap(),
+            PriceInfo {
+                price: Decimal::percent(120),
+                last_updated_time: 1000,
+                is_initial: false,
+            }
+        );
+    }
+}
@@ -8,14 +8,16 @@ use mirror_protocol::common::OrderBy;
 use mirror_protocol::oracle::PricesResponseElem;
 
 static PREFIX_FEEDER: &[u8] = b"feeder";
-static PREFIX_PRICE: &[u8] = b"price";
+pub static PREFIX_PRICE: &[u8] = b"price";
 
-static KEY_CONFIG: &[u8] = b"config";
+pub static KEY_CONFIG: &[u8] = b"config";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: CanonicalAddr,
+    pub pending_owner: Option<CanonicalAddr>,
     pub base_asset: String,
+    pub min_feed_interval: u64, // minimum seconds between two accepted feeds of an asset
 }
 
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -45,6 +47,7 @@ pub fn read_feeder(storage: &dyn Storage, asset_token: &CanonicalAddr) -> StdRes
 pub struct PriceInfo {
     pub price: Decimal,
     pub last_updated_time: u64,
+    pub is_initial: bool,
 }
 
 pub fn store_price(
@@ -73,9 +76,9 @@ pub fn read_prices(
     let price_bucket: ReadonlyBucket<PriceInfo> = ReadonlyBucket::new(deps.storage, PREFIX_PRICE);
 
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let (start, end, order_by) = match order_by {
-        Some(OrderBy::Asc) => (calc_range_start(start_after), None, OrderBy::Asc),
-        _ => (None, calc_range_end(start_after), OrderBy::Desc),
+    let (start, end, order_by) = match OrderBy::from_opt(order_by) {
+        OrderBy::Asc => (calc_range_start(start_after), None, OrderBy::Asc),
+        OrderBy::Desc => (None, calc_range_end(start_after), OrderBy::Desc),
     };
 
     price_bucket
@@ -89,6 +92,7 @@ pub fn read_prices(
                 asset_token,
                 price: v.price,
                 last_updated_time: v.last_updated_time,
+                is_initial: v.is_initial,
             })
         })
         .collect()
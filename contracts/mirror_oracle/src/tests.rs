@@ -1,10 +1,10 @@
 use crate::contract::{execute, instantiate, query};
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-use cosmwasm_std::{from_binary, Decimal, StdError};
-use mirror_protocol::common::OrderBy;
+use cosmwasm_std::{attr, from_binary, Decimal, StdError};
+use mirror_protocol::common::{OrderBy, OwnerInfo};
 use mirror_protocol::oracle::{
-    ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, PriceResponse, PricesResponse,
-    PricesResponseElem, QueryMsg,
+    ConfigResponse, ExecuteMsg, FeederResponse, FeederStatusResponse, InstantiateMsg,
+    PriceResponse, PricesResponse, PricesResponseElem, QueryMsg, RawPricesResponse,
 };
 
 #[test]
@@ -14,6 +14,7 @@ fn proper_initialization() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         base_asset: "base0000".to_string(),
+        min_feed_interval: 0,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -36,6 +37,7 @@ fn update_config() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         base_asset: "base0000".to_string(),
+        min_feed_interval: 0,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -45,21 +47,41 @@ fn update_config() {
     let info = mock_info("owner0000", &[]);
     let msg = ExecuteMsg::UpdateConfig {
         owner: Some("owner0001".to_string()),
+        min_feed_interval: Some(60),
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(0, res.messages.len());
 
+    // the owner is only proposed until it accepts
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!("owner0000", config.owner);
+    assert_eq!(Some("owner0001".to_string()), config.pending_owner);
+
+    let info = mock_info("owner0001", &[]);
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap();
+
     // it worked, let's query the state
     let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
     let config: ConfigResponse = from_binary(&res).unwrap();
 
     assert_eq!("owner0001", config.owner);
     assert_eq!("base0000", config.base_asset);
+    assert_eq!(60, config.min_feed_interval);
 
     // Unauthorized err
     let info = mock_info("owner0000", &[]);
-    let msg = ExecuteMsg::UpdateConfig { owner: None };
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        min_feed_interval: None,
+    };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
     match res {
@@ -68,12 +90,104 @@ fn update_config() {
     }
 }
 
+#[test]
+fn transfer_ownership() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        min_feed_interval: 0,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // only the owner can propose
+    let msg = ExecuteMsg::ProposeOwner {
+        owner: "owner0001".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "propose_owner"),
+            attr("pending_owner", "owner0001"),
+        ]
+    );
+
+    // the owner does not change until the proposal is accepted
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!("owner0000", config.owner);
+    assert_eq!(Some("owner0001".to_string()), config.pending_owner);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Owner {}).unwrap();
+    let owner_info: OwnerInfo = from_binary(&res).unwrap();
+    assert_eq!(
+        owner_info,
+        OwnerInfo {
+            owner: "owner0000".to_string(),
+            pending_owner: Some("owner0001".to_string()),
+        }
+    );
+
+    // only the pending owner can accept
+    let msg = ExecuteMsg::AcceptOwnership {};
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner0001", &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "accept_ownership"),
+            attr("owner", "owner0001"),
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!("owner0001", config.owner);
+    assert_eq!(None, config.pending_owner);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Owner {}).unwrap();
+    let owner_info: OwnerInfo = from_binary(&res).unwrap();
+    assert_eq!(
+        owner_info,
+        OwnerInfo {
+            owner: "owner0001".to_string(),
+            pending_owner: None,
+        }
+    );
+}
+
 #[test]
 fn update_price() {
     let mut deps = mock_dependencies(&[]);
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         base_asset: "base0000".to_string(),
+        min_feed_interval: 0,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -83,6 +197,7 @@ fn update_price() {
     let msg = ExecuteMsg::RegisterAsset {
         asset_token: "mAAPL".to_string(),
         feeder: "addr0000".to_string(),
+        initial_price: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -95,6 +210,7 @@ fn update_price() {
     let msg = ExecuteMsg::RegisterAsset {
         asset_token: "mAAPL".to_string(),
         feeder: "addr0001".to_string(),
+        initial_price: None,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -104,6 +220,7 @@ fn update_price() {
     let msg = ExecuteMsg::RegisterAsset {
         asset_token: "mAAPL".to_string(),
         feeder: "addr0000".to_string(),
+        initial_price: None,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -151,6 +268,7 @@ fn feed_price() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         base_asset: "base0000".to_string(),
+        min_feed_interval: 0,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -167,6 +285,7 @@ fn feed_price() {
     let msg = ExecuteMsg::RegisterAsset {
         asset_token: "mAAPL".to_string(),
         feeder: "addr0000".to_string(),
+        initial_price: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -179,6 +298,7 @@ fn feed_price() {
     let msg = ExecuteMsg::RegisterAsset {
         asset_token: "mAAPL".to_string(),
         feeder: "addr0000".to_string(),
+        initial_price: None,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -186,6 +306,7 @@ fn feed_price() {
     let msg = ExecuteMsg::RegisterAsset {
         asset_token: "mGOGL".to_string(),
         feeder: "addr0000".to_string(),
+        initial_price: None,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -280,11 +401,13 @@ fn feed_price() {
                     asset_token: "mAAPL".to_string(),
                     price: Decimal::from_ratio(12u128, 10u128),
                     last_updated_time: env.block.time.seconds(),
+                    is_initial: false,
                 },
                 PricesResponseElem {
                     asset_token: "mGOGL".to_string(),
                     price: Decimal::from_ratio(22u128, 10u128),
                     last_updated_time: env.block.time.seconds(),
+                    is_initial: false,
                 }
             ],
         }
@@ -302,3 +425,319 @@ fn feed_price() {
         _ => panic!("Must return unauthorized error"),
     }
 }
+
+#[test]
+fn query_raw_prices() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        min_feed_interval: 0,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["mAAPL", "mGOGL"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: "addr0000".to_string(),
+            initial_price: None,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![
+            ("mAAPL".to_string(), Decimal::from_ratio(12u128, 10u128)),
+            ("mGOGL".to_string(), Decimal::from_ratio(22u128, 10u128)),
+        ],
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env = mock_env();
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::RawPrices {
+            assets: vec![
+                "mGOGL".to_string(),
+                "mAAPL".to_string(),
+                "base0000".to_string(),
+            ],
+        },
+    )
+    .unwrap();
+    let prices_res: RawPricesResponse = from_binary(&res).unwrap();
+
+    assert_eq!(
+        prices_res,
+        RawPricesResponse {
+            prices: vec![
+                PricesResponseElem {
+                    asset_token: "mGOGL".to_string(),
+                    price: Decimal::from_ratio(22u128, 10u128),
+                    last_updated_time: env.block.time.seconds(),
+                    is_initial: false,
+                },
+                PricesResponseElem {
+                    asset_token: "mAAPL".to_string(),
+                    price: Decimal::from_ratio(12u128, 10u128),
+                    last_updated_time: env.block.time.seconds(),
+                    is_initial: false,
+                },
+                PricesResponseElem {
+                    asset_token: "base0000".to_string(),
+                    price: Decimal::one(),
+                    last_updated_time: u64::MAX,
+                    is_initial: false,
+                },
+            ],
+        }
+    );
+
+    // unregistered asset
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::RawPrices {
+            assets: vec!["mAAPL".to_string(), "mTSLA".to_string()],
+        },
+    );
+    assert!(res.is_err());
+}
+
+#[test]
+fn feed_price_min_interval() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        min_feed_interval: 60,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "mAAPL".to_string(),
+        feeder: "addr0000".to_string(),
+        initial_price: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("mAAPL".to_string(), Decimal::from_ratio(12u128, 10u128))],
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // feed again before the interval has passed
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(59);
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("mAAPL".to_string(), Decimal::from_ratio(13u128, 10u128))],
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg.clone()).unwrap_err();
+    assert_eq!(res, StdError::generic_err("Price feed is too frequent"));
+
+    // feed after the interval
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(60);
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Price {
+            base_asset: "mAAPL".to_string(),
+            quote_asset: "base0000".to_string(),
+        },
+    )
+    .unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        price_res,
+        PriceResponse {
+            rate: Decimal::from_ratio(13u128, 10u128),
+            last_updated_base: env.block.time.seconds(),
+            last_updated_quote: u64::MAX,
+        }
+    );
+}
+
+#[test]
+fn query_feeder_status() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        min_feed_interval: 0,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "mAAPL".to_string(),
+        feeder: "addr0000".to_string(),
+        initial_price: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // nothing fed yet
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::FeederStatus {
+            asset_token: "mAAPL".to_string(),
+        },
+    )
+    .unwrap();
+    let status: FeederStatusResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        status,
+        FeederStatusResponse {
+            asset_token: "mAAPL".to_string(),
+            feeder: "addr0000".to_string(),
+            last_updated_time: 0,
+            healthy: false,
+        }
+    );
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("mAAPL".to_string(), Decimal::from_ratio(12u128, 10u128))],
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let fed_time = mock_env().block.time.seconds();
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(60);
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::FeederStatus {
+            asset_token: "mAAPL".to_string(),
+        },
+    )
+    .unwrap();
+    let status: FeederStatusResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        status,
+        FeederStatusResponse {
+            asset_token: "mAAPL".to_string(),
+            feeder: "addr0000".to_string(),
+            last_updated_time: fed_time,
+            healthy: true,
+        }
+    );
+
+    // the feeder stopped updating
+    env.block.time = env.block.time.plus_seconds(1);
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::FeederStatus {
+            asset_token: "mAAPL".to_string(),
+        },
+    )
+    .unwrap();
+    let status: FeederStatusResponse = from_binary(&res).unwrap();
+    assert!(!status.healthy);
+    assert_eq!(status.last_updated_time, fed_time);
+}
+
+#[test]
+fn register_with_initial_price() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        min_feed_interval: 60,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "mAAPL".to_string(),
+        feeder: "addr0000".to_string(),
+        initial_price: Some(Decimal::from_ratio(15u128, 10u128)),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // the price is available before any feed
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Price {
+            base_asset: "mAAPL".to_string(),
+            quote_asset: "base0000".to_string(),
+        },
+    )
+    .unwrap();
+    let price: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price.rate, Decimal::from_ratio(15u128, 10u128));
+    assert_eq!(price.last_updated_base, mock_env().block.time.seconds());
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RawPrices {
+            assets: vec!["mAAPL".to_string()],
+        },
+    )
+    .unwrap();
+    let raw_prices: RawPricesResponse = from_binary(&res).unwrap();
+    assert!(raw_prices.prices[0].is_initial);
+
+    // the initial price can not be reset once the asset is registered
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "mAAPL".to_string(),
+        feeder: "addr0001".to_string(),
+        initial_price: Some(Decimal::one()),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("initial_price can only be set for a new asset")
+    );
+
+    // the first feed overwrites it without waiting for min_feed_interval
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("mAAPL".to_string(), Decimal::from_ratio(12u128, 10u128))],
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RawPrices {
+            assets: vec!["mAAPL".to_string()],
+        },
+    )
+    .unwrap();
+    let raw_prices: RawPricesResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        raw_prices.prices,
+        vec![PricesResponseElem {
+            asset_token: "mAAPL".to_string(),
+            price: Decimal::from_ratio(12u128, 10u128),
+            last_updated_time: mock_env().block.time.seconds(),
+            is_initial: false,
+        }]
+    );
+}
@@ -1,21 +1,34 @@
-use crate::rewards::{adjust_premium, deposit_reward, query_reward_info, withdraw_reward};
+use crate::math::RewardIndex;
+use crate::migration::{
+    migrate_config, migrate_reward_infos, migrate_staking_token_index, REWARD_INFO_VERSION,
+};
+use crate::querier::query_lp_value;
+use crate::rewards::{
+    adjust_premium, claim_vested, deposit_reward, query_bond_simulation, query_pool_apr,
+    query_reward_info, withdraw_reward,
+};
 use crate::staking::{
-    auto_stake, auto_stake_hook, bond, decrease_short_token, increase_short_token, unbond,
+    auto_stake, auto_stake_hook, bond, claim_unbonded, decrease_short_token, force_unbond_pool,
+    increase_short_token, unbond,
 };
 use crate::state::{
-    read_config, read_pool_info, store_config, store_pool_info, Config, MigrationParams, PoolInfo,
+    read_config, read_paused_rewards, read_pool_info, read_pool_infos, read_reward_info_version,
+    read_staking_token_index, remove_paused_reward, store_config, store_pool_info,
+    store_reward_info_version, store_staking_token_index, Config, MigrationParams, PoolInfo,
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, from_binary, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
-    StdError, StdResult, Uint128,
+    attr, from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg,
 };
+use mirror_protocol::common::OwnerInfo;
 use mirror_protocol::staking::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolInfoResponse, QueryMsg,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolInfoResponse,
+    QueryMsg, TotalBondedResponse, TotalBondedResponseItem,
 };
 
-use cw20::Cw20ReceiveMsg;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -24,6 +37,12 @@ pub fn instantiate(
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
+    let reward_token = if let Some(reward_token) = msg.reward_token {
+        deps.api.addr_validate(&reward_token)?
+    } else {
+        deps.api.addr_validate(&msg.mirror_token)?
+    };
+
     store_config(
         deps.storage,
         &Config {
@@ -35,20 +54,38 @@ pub fn instantiate(
             base_denom: msg.base_denom,
             premium_min_update_interval: msg.premium_min_update_interval,
             short_reward_contract: deps.api.addr_canonicalize(&msg.short_reward_contract)?,
+            distribution_paused: false,
+            reward_token: deps.api.addr_canonicalize(reward_token.as_str())?,
+            unbond_period: 0,
+            max_reward_share_per_staker: Decimal::one(),
+            claim_cooldown: 0,
+            reward_vesting_period: 0,
+            max_oracle_price_age: 0,
+            max_price_deviation: Decimal::zero(),
         },
     )?;
 
+    // a new contract has no legacy reward infos to migrate
+    store_reward_info_version(deps.storage, REWARD_INFO_VERSION)?;
+
     Ok(Response::default())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
     match msg {
-        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::UpdateConfig {
             owner,
             premium_min_update_interval,
             short_reward_contract,
+            distribution_paused,
+            unbond_period,
+            max_reward_share_per_staker,
+            claim_cooldown,
+            reward_vesting_period,
+            max_oracle_price_age,
+            max_price_deviation,
         } => {
             let owner_addr = if let Some(owner_addr) = owner {
                 Some(deps.api.addr_validate(&owner_addr)?)
@@ -67,6 +104,13 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                 owner_addr,
                 premium_min_update_interval,
                 short_reward_contract_addr,
+                distribution_paused,
+                unbond_period,
+                max_reward_share_per_staker,
+                claim_cooldown,
+                reward_vesting_period,
+                max_oracle_price_age,
+                max_price_deviation,
             )
         }
         ExecuteMsg::RegisterAsset {
@@ -93,21 +137,34 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                 api.addr_validate(&new_staking_token)?,
             )
         }
+        ExecuteMsg::MigrateRewardInfos { limit } => execute_migrate_reward_infos(deps, info, limit),
+        ExecuteMsg::ForceUnbondPool { asset_token, limit } => {
+            let asset_addr = deps.api.addr_validate(&asset_token)?;
+            force_unbond_pool(deps, info, asset_addr, limit)
+        }
         ExecuteMsg::Unbond {
             asset_token,
             amount,
         } => {
             let api = deps.api;
-            unbond(deps, info.sender, api.addr_validate(&asset_token)?, amount)
+            unbond(
+                deps,
+                env,
+                info.sender,
+                api.addr_validate(&asset_token)?,
+                amount,
+            )
         }
+        ExecuteMsg::ClaimUnbonded {} => claim_unbonded(deps, env, info),
         ExecuteMsg::Withdraw { asset_token } => {
             let asset_addr = if let Some(asset_addr) = asset_token {
                 Some(deps.api.addr_validate(&asset_addr)?)
             } else {
                 None
             };
-            withdraw_reward(deps, info, asset_addr)
+            withdraw_reward(deps, env, info, asset_addr)
         }
+        ExecuteMsg::ClaimVested {} => claim_vested(deps, env, info),
         ExecuteMsg::AdjustPremium { asset_tokens } => adjust_premium(deps, env, asset_tokens),
         ExecuteMsg::IncreaseShortToken {
             staker_addr,
@@ -163,6 +220,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
 
 pub fn receive_cw20(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> StdResult<Response> {
@@ -201,7 +259,7 @@ pub fn receive_cw20(
             let config: Config = read_config(deps.storage)?;
 
             // only reward token contract can execute this message
-            if config.mirror_token != deps.api.addr_canonicalize(info.sender.as_str())? {
+            if config.reward_token != deps.api.addr_canonicalize(info.sender.as_str())? {
                 return Err(StdError::generic_err("unauthorized"));
             }
 
@@ -214,18 +272,27 @@ pub fn receive_cw20(
                 return Err(StdError::generic_err("rewards amount miss matched"));
             }
 
-            deposit_reward(deps, rewards, rewards_amount)
+            let depositor = deps.api.addr_validate(&cw20_msg.sender)?;
+            deposit_reward(deps, env, depositor, rewards, rewards_amount)
         }
         Err(_) => Err(StdError::generic_err("invalid cw20 hook message")),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_config(
     deps: DepsMut,
     info: MessageInfo,
     owner: Option<Addr>,
     premium_min_update_interval: Option<u64>,
     short_reward_contract: Option<Addr>,
+    distribution_paused: Option<bool>,
+    unbond_period: Option<u64>,
+    max_reward_share_per_staker: Option<Decimal>,
+    claim_cooldown: Option<u64>,
+    reward_vesting_period: Option<u64>,
+    max_oracle_price_age: Option<u64>,
+    max_price_deviation: Option<Decimal>,
 ) -> StdResult<Response> {
     let mut config: Config = read_config(deps.storage)?;
 
@@ -246,8 +313,58 @@ pub fn update_config(
             deps.api.addr_canonicalize(short_reward_contract.as_str())?;
     }
 
+    // the rewards held while paused are refunded, so the paused interval is never paid out
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if let Some(distribution_paused) = distribution_paused {
+        if config.distribution_paused && !distribution_paused {
+            for (depositor, amount) in read_paused_rewards(deps.storage)? {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: deps.api.addr_humanize(&config.reward_token)?.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: deps.api.addr_humanize(&depositor)?.to_string(),
+                        amount,
+                    })?,
+                    funds: vec![],
+                }));
+                remove_paused_reward(deps.storage, &depositor);
+            }
+        }
+        config.distribution_paused = distribution_paused;
+    }
+
+    if let Some(unbond_period) = unbond_period {
+        config.unbond_period = unbond_period;
+    }
+
+    if let Some(max_reward_share_per_staker) = max_reward_share_per_staker {
+        if max_reward_share_per_staker.is_zero() || max_reward_share_per_staker > Decimal::one() {
+            return Err(StdError::generic_err(
+                "max_reward_share_per_staker must be greater than 0 and at most 1",
+            ));
+        }
+        config.max_reward_share_per_staker = max_reward_share_per_staker;
+    }
+
+    if let Some(claim_cooldown) = claim_cooldown {
+        config.claim_cooldown = claim_cooldown;
+    }
+
+    if let Some(reward_vesting_period) = reward_vesting_period {
+        config.reward_vesting_period = reward_vesting_period;
+    }
+
+    if let Some(max_oracle_price_age) = max_oracle_price_age {
+        config.max_oracle_price_age = max_oracle_price_age;
+    }
+
+    if let Some(max_price_deviation) = max_price_deviation {
+        config.max_price_deviation = max_price_deviation;
+    }
+
     store_config(deps.storage, &config)?;
-    Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![attr("action", "update_config")]))
 }
 
 fn register_asset(
@@ -267,15 +384,17 @@ fn register_asset(
         return Err(StdError::generic_err("Asset was already registered"));
     }
 
+    let staking_token_raw = deps.api.addr_canonicalize(staking_token.as_str())?;
+    store_staking_token_index(deps.storage, &staking_token_raw, &asset_token_raw)?;
     store_pool_info(
         deps.storage,
         &asset_token_raw,
         &PoolInfo {
-            staking_token: deps.api.addr_canonicalize(staking_token.as_str())?,
+            staking_token: staking_token_raw,
             total_bond_amount: Uint128::zero(),
             total_short_amount: Uint128::zero(),
-            reward_index: Decimal::zero(),
-            short_reward_index: Decimal::zero(),
+            reward_index: RewardIndex::zero(),
+            short_reward_index: RewardIndex::zero(),
             pending_reward: Uint128::zero(),
             short_pending_reward: Uint128::zero(),
             premium_rate: Decimal::zero(),
@@ -321,6 +440,7 @@ fn deprecate_staking_token(
     });
     pool_info.staking_token = deps.api.addr_canonicalize(new_staking_token.as_str())?;
 
+    store_staking_token_index(deps.storage, &pool_info.staking_token, &asset_token_raw)?;
     store_pool_info(deps.storage, &asset_token_raw, &pool_info)?;
 
     Ok(Response::new().add_attributes(vec![
@@ -334,18 +454,65 @@ fn deprecate_staking_token(
     ]))
 }
 
+fn execute_migrate_reward_infos(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    if read_reward_info_version(deps.storage)? >= REWARD_INFO_VERSION {
+        return Err(StdError::generic_err("Reward infos were already migrated"));
+    }
+
+    let (merged_count, removed_count, has_more) =
+        migrate_reward_infos(deps.storage, deps.api, limit)?;
+    if !has_more {
+        store_reward_info_version(deps.storage, REWARD_INFO_VERSION)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate_reward_infos"),
+        attr("merged_count", merged_count.to_string()),
+        attr("removed_count", removed_count.to_string()),
+        attr("has_more", has_more.to_string()),
+    ]))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Owner {} => to_binary(&query_owner(deps)?),
         QueryMsg::PoolInfo { asset_token } => to_binary(&query_pool_info(deps, asset_token)?),
         QueryMsg::RewardInfo {
             staker_addr,
             asset_token,
         } => to_binary(&query_reward_info(deps, staker_addr, asset_token)?),
+        QueryMsg::PoolApr { asset_token } => to_binary(&query_pool_apr(deps, asset_token)?),
+        QueryMsg::BondSimulation {
+            asset_token,
+            amount,
+        } => to_binary(&query_bond_simulation(deps, asset_token, amount)?),
+        QueryMsg::PoolByStakingToken { staking_token } => {
+            to_binary(&query_pool_by_staking_token(deps, staking_token)?)
+        }
+        QueryMsg::TotalBonded {} => to_binary(&query_total_bonded(deps)?),
     }
 }
 
+pub fn query_owner(deps: Deps) -> StdResult<OwnerInfo> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(OwnerInfo {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        pending_owner: None,
+    })
+}
+
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let state = read_config(deps.storage)?;
     let resp = ConfigResponse {
@@ -363,6 +530,14 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
             .api
             .addr_humanize(&state.short_reward_contract)?
             .to_string(),
+        distribution_paused: state.distribution_paused,
+        reward_token: deps.api.addr_humanize(&state.reward_token)?.to_string(),
+        unbond_period: state.unbond_period,
+        max_reward_share_per_staker: state.max_reward_share_per_staker,
+        claim_cooldown: state.claim_cooldown,
+        reward_vesting_period: state.reward_vesting_period,
+        max_oracle_price_age: state.max_oracle_price_age,
+        max_price_deviation: state.max_price_deviation,
     };
 
     Ok(resp)
@@ -379,8 +554,8 @@ pub fn query_pool_info(deps: Deps, asset_token: String) -> StdResult<PoolInfoRes
             .to_string(),
         total_bond_amount: pool_info.total_bond_amount,
         total_short_amount: pool_info.total_short_amount,
-        reward_index: pool_info.reward_index,
-        short_reward_index: pool_info.short_reward_index,
+        reward_index: pool_info.reward_index.to_decimal(),
+        short_reward_index: pool_info.short_reward_index.to_decimal(),
         pending_reward: pool_info.pending_reward,
         short_pending_reward: pool_info.short_pending_reward,
         premium_rate: pool_info.premium_rate,
@@ -394,16 +569,62 @@ pub fn query_pool_info(deps: Deps, asset_token: String) -> StdResult<PoolInfoRes
         }),
         migration_index_snapshot: pool_info
             .migration_params
-            .map(|params| params.index_snapshot),
+            .map(|params| params.index_snapshot.to_decimal()),
+    })
+}
+
+pub fn query_pool_by_staking_token(
+    deps: Deps,
+    staking_token: String,
+) -> StdResult<PoolInfoResponse> {
+    let staking_token_raw = deps.api.addr_canonicalize(&staking_token)?;
+    let asset_token_raw = read_staking_token_index(deps.storage, &staking_token_raw)
+        .map_err(|_| StdError::generic_err("No pool found for the staking token"))?;
+    query_pool_info(deps, deps.api.addr_humanize(&asset_token_raw)?.to_string())
+}
+
+pub fn query_total_bonded(deps: Deps) -> StdResult<TotalBondedResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
+
+    let mut total_bond_value = Uint128::zero();
+    let pools = read_pool_infos(deps.storage)?
+        .into_iter()
+        .map(|(asset_token_raw, pool_info)| {
+            let asset_token = deps.api.addr_humanize(&asset_token_raw)?;
+            // pools without a pair are reported unvalued
+            let pool_bond_value = query_lp_value(
+                deps,
+                terraswap_factory.clone(),
+                asset_token.clone(),
+                config.base_denom.clone(),
+                pool_info.total_bond_amount,
+            )
+            .ok();
+            total_bond_value += pool_bond_value.unwrap_or_default();
+
+            Ok(TotalBondedResponseItem {
+                asset_token: asset_token.to_string(),
+                total_bond_amount: pool_info.total_bond_amount,
+                total_bond_value: pool_bond_value,
+            })
+        })
+        .collect::<StdResult<Vec<TotalBondedResponseItem>>>()?;
+
+    Ok(TotalBondedResponse {
+        pools,
+        total_bond_value,
     })
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
     // change the oracle address for the tefi oracle address
-    let mut config: Config = read_config(deps.storage)?;
-    config.oracle_contract = deps.api.addr_canonicalize(&msg.tefi_oracle_contract)?;
-    store_config(deps.storage, &config)?;
+    migrate_config(
+        deps.storage,
+        deps.api.addr_canonicalize(&msg.tefi_oracle_contract)?,
+    )?;
+    migrate_staking_token_index(deps.storage)?;
 
     Ok(Response::default())
 }
@@ -1,5 +1,6 @@
 pub mod contract;
 mod math;
+mod migration;
 mod querier;
 mod rewards;
 mod staking;
@@ -1,6 +1,14 @@
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{Decimal, StdError, Uint128};
+use schemars::JsonSchema;
+use serde::{de, ser, Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::ops;
+use std::str::FromStr;
 
 const DECIMAL_FRACTIONAL: Uint128 = Uint128::new(1_000_000_000u128);
+const REWARD_INDEX_DECIMAL_PLACES: usize = 36;
+const REWARD_INDEX_FRACTIONAL: u128 = 1_000_000_000_000_000_000_000_000_000_000_000_000u128;
 
 /// return a / b
 pub fn decimal_division(a: Decimal, b: Decimal) -> Decimal {
@@ -15,3 +23,143 @@ pub fn decimal_subtraction(a: Decimal, b: Decimal) -> Decimal {
         DECIMAL_FRACTIONAL,
     )
 }
+
+/// Reward per bond accumulator with 36 decimal places, an 18 decimals index truncates
+/// away small deposits over a large total bond. Serialized as a decimal string like
+/// Decimal, so the indexes stored as Decimal read as is.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
+pub struct RewardIndex(#[schemars(with = "String")] Uint256);
+
+impl RewardIndex {
+    pub fn zero() -> RewardIndex {
+        RewardIndex(Uint256::zero())
+    }
+
+    /// index increment for distributing `reward` over `total_bond`
+    pub fn from_ratio(reward: Uint128, total_bond: Uint128) -> RewardIndex {
+        RewardIndex(Uint256::from(reward).multiply_ratio(
+            Uint256::from(REWARD_INDEX_FRACTIONAL),
+            Uint256::from(total_bond),
+        ))
+    }
+
+    /// the index truncated to the 18 decimal places of Decimal
+    pub fn to_decimal(self) -> Decimal {
+        let truncation = Uint256::from(10u128.pow(REWARD_INDEX_DECIMAL_PLACES as u32 - 18));
+        let truncated = self.0.multiply_ratio(Uint256::one(), truncation) * truncation;
+        Decimal::from_str(&RewardIndex(truncated).to_string()).unwrap()
+    }
+}
+
+impl From<Decimal> for RewardIndex {
+    fn from(val: Decimal) -> Self {
+        RewardIndex::from_str(&val.to_string()).unwrap()
+    }
+}
+
+impl ops::Add for RewardIndex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        RewardIndex(self.0 + rhs.0)
+    }
+}
+
+/// reward accrued by the bond amount over the index
+impl ops::Mul<RewardIndex> for Uint128 {
+    type Output = Uint128;
+
+    fn mul(self, rhs: RewardIndex) -> Self::Output {
+        Uint256::from(self)
+            .multiply_ratio(rhs.0, Uint256::from(REWARD_INDEX_FRACTIONAL))
+            .into()
+    }
+}
+
+impl FromStr for RewardIndex {
+    type Err = StdError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parts = input.split('.');
+        let whole = Uint256::from_str(parts.next().unwrap())
+            .map_err(|_| StdError::generic_err("Error parsing whole"))?;
+        let fractional = match parts.next() {
+            Some(fractional) if fractional.len() <= REWARD_INDEX_DECIMAL_PLACES => {
+                Uint256::from_str(&format!(
+                    "{:0<width$}",
+                    fractional,
+                    width = REWARD_INDEX_DECIMAL_PLACES
+                ))
+                .map_err(|_| StdError::generic_err("Error parsing fractional"))?
+            }
+            Some(_) => {
+                return Err(StdError::generic_err(
+                    "Cannot parse more than 36 fractional digits",
+                ))
+            }
+            None => Uint256::zero(),
+        };
+        if parts.next().is_some() {
+            return Err(StdError::generic_err("Unexpected number of dots"));
+        }
+
+        Ok(RewardIndex(
+            whole * Uint256::from(REWARD_INDEX_FRACTIONAL) + fractional,
+        ))
+    }
+}
+
+impl fmt::Display for RewardIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fractional_unit = Uint256::from(REWARD_INDEX_FRACTIONAL);
+        let whole = self.0.multiply_ratio(Uint256::one(), fractional_unit);
+        let fractional = self.0 - whole * fractional_unit;
+
+        if fractional.is_zero() {
+            write!(f, "{}", whole)
+        } else {
+            let fractional_string = format!(
+                "{:0>width$}",
+                fractional.to_string(),
+                width = REWARD_INDEX_DECIMAL_PLACES
+            );
+            write!(f, "{}.{}", whole, fractional_string.trim_end_matches('0'))
+        }
+    }
+}
+
+impl Serialize for RewardIndex {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for RewardIndex {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(RewardIndexVisitor)
+    }
+}
+
+struct RewardIndexVisitor;
+
+impl<'de> de::Visitor<'de> for RewardIndexVisitor {
+    type Value = RewardIndex;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("string-encoded decimal")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        RewardIndex::from_str(v)
+            .map_err(|e| E::custom(format!("Error parsing reward index '{}': {}", v, e)))
+    }
+}
@@ -0,0 +1,550 @@
+use cosmwasm_std::{Api, CanonicalAddr, Decimal, Order, StdResult, Storage, Uint128};
+use cosmwasm_storage::{singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::rewards::before_share_change;
+use crate::state::{
+    read_is_migrated, read_pool_info, read_reward_info_cursor, read_staged_totals,
+    remove_reward_info_cursor, remove_staged_totals, rewards_read, rewards_store, store_config,
+    store_pool_info, store_pool_staker, store_reward_info_cursor, store_staged_totals,
+    store_staking_token_index, Config, PoolInfo, RewardInfo, RewardInfoCursor, KEY_CONFIG,
+    PREFIX_POOL_INFO, PREFIX_REWARD, PREFIX_SHORT_REWARD,
+};
+
+pub const REWARD_INFO_VERSION: u64 = 1;
+
+const MAX_MIGRATION_LIMIT: u32 = 100;
+const DEFAULT_MIGRATION_LIMIT: u32 = 30;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub owner: CanonicalAddr,
+    pub mirror_token: CanonicalAddr,
+    pub mint_contract: CanonicalAddr,
+    pub oracle_contract: CanonicalAddr,
+    pub terraswap_factory: CanonicalAddr,
+    pub base_denom: String,
+    pub premium_min_update_interval: u64,
+    pub short_reward_contract: CanonicalAddr,
+}
+
+pub fn migrate_config(storage: &mut dyn Storage, oracle_contract: CanonicalAddr) -> StdResult<()> {
+    let legacy_store: ReadonlySingleton<LegacyConfig> = singleton_read(storage, KEY_CONFIG);
+    let legacy_config: LegacyConfig = legacy_store.load()?;
+    let config = Config {
+        owner: legacy_config.owner,
+        // rewards were always paid in MIR before the reward token was configurable
+        reward_token: legacy_config.mirror_token.clone(),
+        mirror_token: legacy_config.mirror_token,
+        mint_contract: legacy_config.mint_contract,
+        oracle_contract,
+        terraswap_factory: legacy_config.terraswap_factory,
+        base_denom: legacy_config.base_denom,
+        premium_min_update_interval: legacy_config.premium_min_update_interval,
+        short_reward_contract: legacy_config.short_reward_contract,
+        distribution_paused: false,
+        unbond_period: 0,
+        max_reward_share_per_staker: Decimal::one(),
+        claim_cooldown: 0,
+        reward_vesting_period: 0,
+        max_oracle_price_age: 0,
+        max_price_deviation: Decimal::zero(),
+    };
+    store_config(storage, &config)
+}
+
+/// Merges reward infos stored under non-normalized keys into a single entry per
+/// (staker, asset), drops orphaned entries and indexes the bonded stakers of each pool,
+/// going over at most `limit` entries per call. The pool totals are summed up along the
+/// way and replaced once every entry was migrated. Returns the number of merged and
+/// removed entries, and whether entries are left for the next call.
+pub fn migrate_reward_infos(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    limit: Option<u32>,
+) -> StdResult<(u64, u64, bool)> {
+    let limit = limit
+        .unwrap_or(DEFAULT_MIGRATION_LIMIT)
+        .min(MAX_MIGRATION_LIMIT) as usize;
+    let mut cursor = read_reward_info_cursor(storage)?.unwrap_or(RewardInfoCursor {
+        is_short: false,
+        last_key: None,
+    });
+    let mut merged_count = 0u64;
+    let mut removed_count = 0u64;
+    let mut migrated_count = 0usize;
+
+    loop {
+        let prefix = if cursor.is_short {
+            PREFIX_SHORT_REWARD
+        } else {
+            PREFIX_REWARD
+        };
+        let start = cursor.last_key.as_ref().map(|key| {
+            let mut start = key.clone();
+            start.push(0);
+            start
+        });
+        let keys = ReadonlyBucket::<RewardInfo>::new(storage, prefix)
+            .range(start.as_deref(), None, Order::Ascending)
+            .take(limit - migrated_count)
+            .map(|item| item.map(|(k, _)| k))
+            .collect::<StdResult<Vec<Vec<u8>>>>()?;
+
+        for key in keys.into_iter() {
+            // loaded one by one, as merging can update the entries further in the batch
+            let reward_info: RewardInfo = ReadonlyBucket::new(storage, prefix).load(&key)?;
+            let (merged, removed) =
+                migrate_reward_info(storage, api, &key, reward_info, cursor.is_short)?;
+            merged_count += merged;
+            removed_count += removed;
+            migrated_count += 1;
+            cursor.last_key = Some(key);
+        }
+
+        if migrated_count == limit {
+            store_reward_info_cursor(storage, &cursor)?;
+            return Ok((merged_count, removed_count, true));
+        }
+
+        if cursor.is_short {
+            break;
+        }
+        cursor = RewardInfoCursor {
+            is_short: true,
+            last_key: None,
+        };
+    }
+
+    let mut pools: Vec<(CanonicalAddr, PoolInfo)> = vec![];
+    for item in
+        Bucket::<PoolInfo>::new(storage, PREFIX_POOL_INFO).range(None, None, Order::Ascending)
+    {
+        let (k, pool_info) = item?;
+        pools.push((CanonicalAddr::from(k), pool_info));
+    }
+
+    for (asset_token, mut pool_info) in pools.into_iter() {
+        let staged_totals = read_staged_totals(storage, &asset_token)?;
+        pool_info.total_bond_amount = staged_totals.total_bond_amount;
+        pool_info.total_short_amount = staged_totals.total_short_amount;
+        store_pool_info(storage, &asset_token, &pool_info)?;
+        remove_staged_totals(storage, &asset_token);
+    }
+    remove_reward_info_cursor(storage);
+
+    Ok((merged_count, removed_count, false))
+}
+
+/// Moves a reward info to its normalized key, merging it into the entry already stored
+/// there. Entries are counted in the staged totals once the range went past their key.
+fn migrate_reward_info(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    key: &[u8],
+    mut reward_info: RewardInfo,
+    is_short: bool,
+) -> StdResult<(u64, u64)> {
+    // keys are the length prefixed staker followed by the asset token
+    let staker_len = u16::from_be_bytes([key[0], key[1]]) as usize;
+    let staker = CanonicalAddr::from(key[2..2 + staker_len].to_vec());
+    let asset_token = CanonicalAddr::from(key[2 + staker_len..].to_vec());
+
+    rewards_store(storage, &staker, is_short).remove(asset_token.as_slice());
+    if !is_short {
+        store_pool_staker(storage, &asset_token, &staker, false)?;
+    }
+
+    let normalized = normalize(api, &staker).zip(normalize(api, &asset_token));
+    let pool_info = match normalized {
+        Some((_, ref asset_token)) => read_pool_info(storage, asset_token).ok(),
+        None => None,
+    };
+    let (staker, asset_token, pool_info) = match (normalized, pool_info) {
+        (Some((staker, asset_token)), Some(pool_info)) => (staker, asset_token, pool_info),
+        _ => return Ok((0, 1)),
+    };
+
+    let is_migrated = read_is_migrated(storage, &asset_token, &staker);
+    let pool_index = if is_short {
+        pool_info.short_reward_index
+    } else if pool_info.migration_params.is_some() && !is_migrated {
+        pool_info.migration_params.clone().unwrap().index_snapshot
+    } else {
+        pool_info.reward_index
+    };
+    before_share_change(pool_index, reward_info.bond_amount, &mut reward_info)?;
+    let bond_amount = reward_info.bond_amount;
+
+    let normalized_key = reward_info_key(&staker, &asset_token);
+    let mut merged_count = 0u64;
+    if normalized_key.as_slice() != key {
+        if let Some(mut existing) =
+            rewards_read(storage, &staker, is_short).may_load(asset_token.as_slice())?
+        {
+            before_share_change(pool_index, existing.bond_amount, &mut existing)?;
+            reward_info.bond_amount += existing.bond_amount;
+            reward_info.pending_reward += existing.pending_reward;
+            merged_count = 1;
+        }
+    }
+
+    // an entry further in the range is migrated when the range reaches it
+    if normalized_key.as_slice() > key {
+        rewards_store(storage, &staker, is_short).save(asset_token.as_slice(), &reward_info)?;
+        return Ok((merged_count, 0));
+    }
+
+    if reward_info.bond_amount.is_zero() && reward_info.pending_reward.is_zero() {
+        rewards_store(storage, &staker, is_short).remove(asset_token.as_slice());
+        return Ok((merged_count, 1));
+    }
+
+    rewards_store(storage, &staker, is_short).save(asset_token.as_slice(), &reward_info)?;
+    if !is_short {
+        store_pool_staker(
+            storage,
+            &asset_token,
+            &staker,
+            !reward_info.bond_amount.is_zero(),
+        )?;
+    }
+
+    // positions on a deprecated staking token are not part of the pool total
+    if is_short || pool_info.migration_params.is_none() || is_migrated {
+        stage_total_change(storage, &asset_token, is_short, bond_amount, true)?;
+    }
+
+    Ok((merged_count, 0))
+}
+
+/// Keeps the totals of an unfinished reward infos migration in line with the bonds
+/// and unbonds on entries it already went over
+pub fn stage_bond_change(
+    storage: &mut dyn Storage,
+    staker: &CanonicalAddr,
+    asset_token: &CanonicalAddr,
+    is_short: bool,
+    amount: Uint128,
+    is_increase: bool,
+) -> StdResult<()> {
+    let cursor = match read_reward_info_cursor(storage)? {
+        Some(cursor) => cursor,
+        None => return Ok(()),
+    };
+
+    let is_migrated = match (cursor.is_short, is_short) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => cursor
+            .last_key
+            .is_some_and(|last_key| reward_info_key(staker, asset_token) <= last_key),
+    };
+    if is_migrated {
+        stage_total_change(storage, asset_token, is_short, amount, is_increase)?;
+    }
+
+    Ok(())
+}
+
+fn stage_total_change(
+    storage: &mut dyn Storage,
+    asset_token: &CanonicalAddr,
+    is_short: bool,
+    amount: Uint128,
+    is_increase: bool,
+) -> StdResult<()> {
+    let mut staged_totals = read_staged_totals(storage, asset_token)?;
+    let total = if is_short {
+        &mut staged_totals.total_short_amount
+    } else {
+        &mut staged_totals.total_bond_amount
+    };
+    *total = if is_increase {
+        *total + amount
+    } else {
+        total.checked_sub(amount)?
+    };
+    store_staged_totals(storage, asset_token, &staged_totals)
+}
+
+fn reward_info_key(staker: &CanonicalAddr, asset_token: &CanonicalAddr) -> Vec<u8> {
+    [
+        &(staker.len() as u16).to_be_bytes(),
+        staker.as_slice(),
+        asset_token.as_slice(),
+    ]
+    .concat()
+}
+
+/// Indexes all registered pools by their current and deprecated staking tokens
+pub fn migrate_staking_token_index(storage: &mut dyn Storage) -> StdResult<()> {
+    let mut pools: Vec<(CanonicalAddr, PoolInfo)> = vec![];
+    for item in ReadonlyBucket::<PoolInfo>::new(storage, PREFIX_POOL_INFO).range(
+        None,
+        None,
+        Order::Ascending,
+    ) {
+        let (k, pool_info) = item?;
+        pools.push((CanonicalAddr::from(k), pool_info));
+    }
+
+    for (asset_token, pool_info) in pools.into_iter() {
+        if let Some(params) = pool_info.migration_params {
+            store_staking_token_index(storage, &params.deprecated_staking_token, &asset_token)?;
+        }
+        store_staking_token_index(storage, &pool_info.staking_token, &asset_token)?;
+    }
+
+    Ok(())
+}
+
+fn normalize(api: &dyn Api, addr: &CanonicalAddr) -> Option<CanonicalAddr> {
+    api.addr_humanize(addr)
+        .and_then(|addr| api.addr_canonicalize(addr.as_str()))
+        .ok()
+}
+
+#[cfg(test)]
+mod migration_tests {
+    use super::*;
+    use crate::math::RewardIndex;
+    use crate::state::{
+        read_config, read_pool_info, read_pool_stakers, read_staking_token_index, rewards_read,
+        MigrationParams,
+    };
+    use cosmwasm_std::testing::mock_dependencies;
+    use cosmwasm_std::{to_vec, Api};
+    use cosmwasm_storage::{singleton, to_length_prefixed};
+
+    #[test]
+    fn test_config_migration() {
+        let mut deps = mock_dependencies(&[]);
+        let legacy_config = LegacyConfig {
+            owner: deps.api.addr_canonicalize("owner").unwrap(),
+            mirror_token: deps.api.addr_canonicalize("mirror").unwrap(),
+            mint_contract: deps.api.addr_canonicalize("mint").unwrap(),
+            oracle_contract: deps.api.addr_canonicalize("oracle").unwrap(),
+            terraswap_factory: deps.api.addr_canonicalize("factory").unwrap(),
+            base_denom: "uusd".to_string(),
+            premium_min_update_interval: 3600,
+            short_reward_contract: deps.api.addr_canonicalize("short_reward").unwrap(),
+        };
+        singleton(&mut deps.storage, KEY_CONFIG)
+            .save(&legacy_config)
+            .unwrap();
+
+        let tefi_oracle = deps.api.addr_canonicalize("tefi_oracle").unwrap();
+        migrate_config(&mut deps.storage, tefi_oracle.clone()).unwrap();
+
+        let config = read_config(&deps.storage).unwrap();
+        assert_eq!(config.oracle_contract, tefi_oracle);
+        assert!(!config.distribution_paused);
+        assert_eq!(
+            config.reward_token,
+            deps.api.addr_canonicalize("mirror").unwrap()
+        );
+        assert_eq!(config.unbond_period, 0);
+        assert_eq!(config.max_reward_share_per_staker, Decimal::one());
+        assert_eq!(config.claim_cooldown, 0);
+        assert_eq!(config.reward_vesting_period, 0);
+    }
+
+    #[test]
+    fn test_config_migration_from_baseline_layout() {
+        let mut deps = mock_dependencies(&[]);
+        let addr = |addr: &str| {
+            String::from_utf8(to_vec(&deps.api.addr_canonicalize(addr).unwrap()).unwrap()).unwrap()
+        };
+
+        // raw config as stored by the deployed contract
+        let raw_config = format!(
+            r#"{{"owner":{},"mirror_token":{},"mint_contract":{},"oracle_contract":{},"terraswap_factory":{},"base_denom":"uusd","premium_min_update_interval":3600,"short_reward_contract":{}}}"#,
+            addr("owner"),
+            addr("mirror"),
+            addr("mint"),
+            addr("oracle"),
+            addr("factory"),
+            addr("short_reward"),
+        );
+        deps.storage
+            .set(&to_length_prefixed(KEY_CONFIG), raw_config.as_bytes());
+
+        let tefi_oracle = deps.api.addr_canonicalize("tefi_oracle").unwrap();
+        migrate_config(&mut deps.storage, tefi_oracle.clone()).unwrap();
+
+        let config = read_config(&deps.storage).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                owner: deps.api.addr_canonicalize("owner").unwrap(),
+                mirror_token: deps.api.addr_canonicalize("mirror").unwrap(),
+                mint_contract: deps.api.addr_canonicalize("mint").unwrap(),
+                oracle_contract: tefi_oracle,
+                terraswap_factory: deps.api.addr_canonicalize("factory").unwrap(),
+                base_denom: "uusd".to_string(),
+                premium_min_update_interval: 3600,
+                short_reward_contract: deps.api.addr_canonicalize("short_reward").unwrap(),
+                distribution_paused: false,
+                reward_token: deps.api.addr_canonicalize("mirror").unwrap(),
+                unbond_period: 0,
+                max_reward_share_per_staker: Decimal::one(),
+                claim_cooldown: 0,
+                reward_vesting_period: 0,
+                max_oracle_price_age: 0,
+                max_price_deviation: Decimal::zero(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_reward_infos_migration() {
+        let mut deps = mock_dependencies(&[]);
+        let asset_token = deps.api.addr_canonicalize("asset").unwrap();
+        let staker = deps.api.addr_canonicalize("addr0000").unwrap();
+        let staker_2 = deps.api.addr_canonicalize("addr0001").unwrap();
+        // humanizes to the same address, but is stored under a different key
+        let duplicated_staker = deps.api.addr_canonicalize("addr\u{0}0000").unwrap();
+        let unknown_asset = deps.api.addr_canonicalize("unknown").unwrap();
+
+        store_pool_info(
+            &mut deps.storage,
+            &asset_token,
+            &PoolInfo {
+                staking_token: deps.api.addr_canonicalize("staking").unwrap(),
+                pending_reward: Uint128::zero(),
+                short_pending_reward: Uint128::zero(),
+                total_bond_amount: Uint128::new(1000u128),
+                total_short_amount: Uint128::zero(),
+                reward_index: Decimal::percent(50).into(),
+                short_reward_index: RewardIndex::zero(),
+                premium_rate: Decimal::zero(),
+                short_reward_weight: Decimal::zero(),
+                premium_updated_time: 0,
+                migration_params: None,
+            },
+        )
+        .unwrap();
+
+        let reward_info = |index: Decimal, bond_amount, pending_reward| RewardInfo {
+            index: index.into(),
+            bond_amount: Uint128::new(bond_amount),
+            pending_reward: Uint128::new(pending_reward),
+            last_claim_time: 0,
+        };
+        rewards_store(&mut deps.storage, &staker, false)
+            .save(
+                asset_token.as_slice(),
+                &reward_info(Decimal::zero(), 100, 5),
+            )
+            .unwrap();
+        rewards_store(&mut deps.storage, &duplicated_staker, false)
+            .save(
+                asset_token.as_slice(),
+                &reward_info(Decimal::percent(50), 200, 10),
+            )
+            .unwrap();
+        rewards_store(&mut deps.storage, &staker_2, false)
+            .save(
+                asset_token.as_slice(),
+                &reward_info(Decimal::percent(50), 50, 0),
+            )
+            .unwrap();
+        rewards_store(&mut deps.storage, &staker_2, false)
+            .save(
+                unknown_asset.as_slice(),
+                &reward_info(Decimal::zero(), 10, 0),
+            )
+            .unwrap();
+
+        // the pool totals are kept until every entry is migrated
+        let (merged_count, removed_count, has_more) =
+            migrate_reward_infos(&mut deps.storage, &deps.api, Some(2)).unwrap();
+        assert!(has_more);
+        let pool_info = read_pool_info(&deps.storage, &asset_token).unwrap();
+        assert_eq!(pool_info.total_bond_amount, Uint128::new(1000u128));
+
+        // bonds on migrated entries are staged along, the others are counted when reached
+        stage_bond_change(
+            &mut deps.storage,
+            &staker_2,
+            &asset_token,
+            false,
+            Uint128::new(20u128),
+            true,
+        )
+        .unwrap();
+
+        let res = migrate_reward_infos(&mut deps.storage, &deps.api, None).unwrap();
+        assert!(!res.2);
+        assert_eq!((merged_count + res.0, removed_count + res.1), (1, 1));
+        assert!(read_reward_info_cursor(&deps.storage).unwrap().is_none());
+
+        // the duplicate is merged, accruing the rewards of the first entry
+        assert_eq!(
+            rewards_read(&deps.storage, &staker, false)
+                .load(asset_token.as_slice())
+                .unwrap(),
+            reward_info(Decimal::percent(50), 300, 65),
+        );
+        assert!(rewards_read(&deps.storage, &duplicated_staker, false)
+            .may_load(asset_token.as_slice())
+            .unwrap()
+            .is_none());
+        assert!(rewards_read(&deps.storage, &staker_2, false)
+            .may_load(unknown_asset.as_slice())
+            .unwrap()
+            .is_none());
+
+        let pool_info = read_pool_info(&deps.storage, &asset_token).unwrap();
+        assert_eq!(pool_info.total_bond_amount, Uint128::new(350u128));
+        assert_eq!(pool_info.total_short_amount, Uint128::zero());
+
+        // the bonded stakers are indexed by pool
+        let stakers = read_pool_stakers(&deps.storage, &asset_token, None).unwrap();
+        assert_eq!(stakers.len(), 2);
+        assert!(stakers.contains(&staker) && stakers.contains(&staker_2));
+    }
+
+    #[test]
+    fn test_staking_token_index_migration() {
+        let mut deps = mock_dependencies(&[]);
+        let asset_token = deps.api.addr_canonicalize("asset").unwrap();
+        let staking_token = deps.api.addr_canonicalize("staking").unwrap();
+        let deprecated_staking_token = deps.api.addr_canonicalize("old_staking").unwrap();
+
+        store_pool_info(
+            &mut deps.storage,
+            &asset_token,
+            &PoolInfo {
+                staking_token: staking_token.clone(),
+                pending_reward: Uint128::zero(),
+                short_pending_reward: Uint128::zero(),
+                total_bond_amount: Uint128::zero(),
+                total_short_amount: Uint128::zero(),
+                reward_index: RewardIndex::zero(),
+                short_reward_index: RewardIndex::zero(),
+                premium_rate: Decimal::zero(),
+                short_reward_weight: Decimal::zero(),
+                premium_updated_time: 0,
+                migration_params: Some(MigrationParams {
+                    index_snapshot: RewardIndex::zero(),
+                    deprecated_staking_token: deprecated_staking_token.clone(),
+                }),
+            },
+        )
+        .unwrap();
+
+        migrate_staking_token_index(&mut deps.storage).unwrap();
+
+        assert_eq!(
+            read_staking_token_index(&deps.storage, &staking_token).unwrap(),
+            asset_token
+        );
+        assert_eq!(
+            read_staking_token_index(&deps.storage, &deprecated_staking_token).unwrap(),
+            asset_token
+        );
+    }
+}
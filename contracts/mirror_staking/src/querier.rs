@@ -1,4 +1,5 @@
 use crate::math::{decimal_division, decimal_subtraction};
+use crate::state::Config;
 use cosmwasm_std::{
     to_binary, Addr, Decimal, Deps, QuerierWrapper, QueryRequest, StdResult, Uint128, WasmQuery,
 };
@@ -9,18 +10,21 @@ use terraswap::{
     querier::query_pair_info,
 };
 
+/// Returns the premium of the terraswap price over the oracle price, and whether the asset
+/// lacks a valid price feed, which is the case for a missing, stale or deviating oracle price
 pub fn compute_premium_rate(
     deps: Deps,
-    oracle_contract: Addr,
-    factory_contract: Addr,
+    config: &Config,
     asset_token: Addr,
-    base_denom: String,
+    block_time: u64,
 ) -> StdResult<(Decimal, bool)> {
     let pair_info: PairInfo = query_pair_info(
         &deps.querier,
-        factory_contract,
+        deps.api.addr_humanize(&config.terraswap_factory)?,
         &[
-            AssetInfo::NativeToken { denom: base_denom },
+            AssetInfo::NativeToken {
+                denom: config.base_denom.clone(),
+            },
             AssetInfo::Token {
                 contract_addr: asset_token.to_string(),
             },
@@ -44,9 +48,26 @@ pub fn compute_premium_rate(
         Decimal::from_ratio(pool.assets[1].amount, pool.assets[0].amount)
     };
 
-    let oracle_price: Decimal = query_price(deps, oracle_contract, asset_token.to_string())?;
+    let (oracle_price, last_updated) = query_price(
+        deps,
+        deps.api.addr_humanize(&config.oracle_contract)?,
+        asset_token.to_string(),
+    )?;
 
     if oracle_price.is_zero() {
+        return Ok((Decimal::zero(), true));
+    }
+
+    let price_age = block_time.saturating_sub(last_updated);
+    let price_deviation = if terraswap_price > oracle_price {
+        decimal_subtraction(terraswap_price, oracle_price)
+    } else {
+        decimal_subtraction(oracle_price, terraswap_price)
+    };
+    if (config.max_oracle_price_age > 0 && price_age > config.max_oracle_price_age)
+        || (!config.max_price_deviation.is_zero()
+            && decimal_division(price_deviation, oracle_price) > config.max_price_deviation)
+    {
         Ok((Decimal::zero(), true))
     } else if terraswap_price > oracle_price {
         Ok((
@@ -61,6 +82,53 @@ pub fn compute_premium_rate(
     }
 }
 
+/// Returns the value of the given amount of LP tokens in base denom,
+/// computed as twice the share of the base denom reserve
+pub fn query_lp_value(
+    deps: Deps,
+    factory_contract: Addr,
+    asset_token: Addr,
+    base_denom: String,
+    lp_amount: Uint128,
+) -> StdResult<Uint128> {
+    let pair_info: PairInfo = query_pair_info(
+        &deps.querier,
+        factory_contract,
+        &[
+            AssetInfo::NativeToken {
+                denom: base_denom.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: asset_token.to_string(),
+            },
+        ],
+    )?;
+
+    let pool: PoolResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: pair_info.contract_addr,
+        msg: to_binary(&PairQueryMsg::Pool {})?,
+    }))?;
+
+    if pool.total_share.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    let base_reserve: Uint128 = pool
+        .assets
+        .iter()
+        .find(|asset| {
+            asset.info
+                == AssetInfo::NativeToken {
+                    denom: base_denom.clone(),
+                }
+        })
+        .map(|asset| asset.amount)
+        .unwrap_or_default();
+
+    let lp_amount: Uint128 = lp_amount.checked_mul(Uint128::from(2u128))?;
+    Ok(base_reserve.multiply_ratio(lp_amount, pool.total_share))
+}
+
 pub fn compute_short_reward_weight(
     querier: &QuerierWrapper,
     short_reward_contract: Addr,
@@ -74,7 +142,7 @@ pub fn compute_short_reward_weight(
     Ok(res.short_reward_weight)
 }
 
-pub fn query_price(deps: Deps, oracle: Addr, quote_asset: String) -> StdResult<Decimal> {
+pub fn query_price(deps: Deps, oracle: Addr, quote_asset: String) -> StdResult<(Decimal, u64)> {
     let res: PriceResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: oracle.to_string(),
         msg: to_binary(&OracleQueryMsg::Price {
@@ -83,5 +151,5 @@ pub fn query_price(deps: Deps, oracle: Addr, quote_asset: String) -> StdResult<D
         })?,
     }))?;
 
-    Ok(res.rate)
+    Ok((res.rate, res.last_updated))
 }
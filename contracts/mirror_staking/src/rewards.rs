@@ -3,19 +3,22 @@ use cosmwasm_std::{
     Order, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
 };
 
-use crate::querier::{compute_premium_rate, compute_short_reward_weight};
+use crate::math::RewardIndex;
+use crate::querier::{compute_premium_rate, compute_short_reward_weight, query_lp_value};
 use crate::state::{
-    read_config, read_is_migrated, read_pool_info, rewards_read, rewards_store, store_pool_info,
-    Config, PoolInfo, RewardInfo,
+    read_config, read_is_migrated, read_paused_reward, read_pool_info, read_reward_emission,
+    read_vestings, rewards_read, rewards_store, store_paused_reward, store_pool_info,
+    store_reward_emission, store_vestings, Config, PoolInfo, RewardEmission, RewardInfo,
+    VestingInfo,
+};
+use mirror_protocol::staking::{
+    BondSimulationResponse, PoolAprResponse, RewardInfoResponse, RewardInfoResponseItem,
 };
-use mirror_protocol::staking::{RewardInfoResponse, RewardInfoResponseItem};
 
 use cw20::Cw20ExecuteMsg;
 
 pub fn adjust_premium(deps: DepsMut, env: Env, asset_tokens: Vec<String>) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
-    let oracle_contract = deps.api.addr_humanize(&config.oracle_contract)?;
-    let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
     let short_reward_contract = deps.api.addr_humanize(&config.short_reward_contract)?;
     for asset_token in asset_tokens.iter() {
         let asset_token_raw = deps.api.addr_canonicalize(asset_token)?;
@@ -32,13 +35,12 @@ pub fn adjust_premium(deps: DepsMut, env: Env, asset_tokens: Vec<String>) -> Std
 
         let (premium_rate, no_price_feed) = compute_premium_rate(
             deps.as_ref(),
-            oracle_contract.clone(),
-            terraswap_factory.clone(),
+            &config,
             asset_token_addr,
-            config.base_denom.to_string(),
+            env.block.time.seconds(),
         )?;
 
-        // if asset does not have price feed, set short reward weight directly to zero
+        // if asset does not have a valid price feed, set short reward weight directly to zero
         let short_reward_weight = if no_price_feed {
             Decimal::zero()
         } else {
@@ -63,9 +65,27 @@ pub fn adjust_premium(deps: DepsMut, env: Env, asset_tokens: Vec<String>) -> Std
 // deposit_reward must be from reward token contract
 pub fn deposit_reward(
     deps: DepsMut,
+    env: Env,
+    depositor: Addr,
     rewards: Vec<(String, Uint128)>,
     rewards_amount: Uint128,
 ) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+
+    // while distribution is paused the reward indexes are frozen, deposits are accepted so
+    // the sender keeps its schedule, and held to be refunded to it once unpaused
+    if config.distribution_paused {
+        let depositor_raw = deps.api.addr_canonicalize(depositor.as_str())?;
+        let paused_reward = read_paused_reward(deps.storage, &depositor_raw)?;
+        store_paused_reward(deps.storage, &depositor_raw, paused_reward + rewards_amount)?;
+
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "deposit_reward"),
+            attr("rewards_amount", rewards_amount.to_string()),
+            attr("paused", "true"),
+        ]));
+    }
+
     for (asset_token, amount) in rewards.iter() {
         let asset_token_raw: CanonicalAddr = deps.api.addr_canonicalize(asset_token)?;
         let mut pool_info: PoolInfo = read_pool_info(deps.storage, &asset_token_raw)?;
@@ -77,12 +97,28 @@ pub fn deposit_reward(
         let mut short_reward = total_reward * pool_info.short_reward_weight;
         let mut normal_reward = total_reward.checked_sub(short_reward).unwrap();
 
+        // keep track of the emission rate, used to compute the pool apr
+        let reward_emission = read_reward_emission(deps.storage, &asset_token_raw)?;
+        store_reward_emission(
+            deps.storage,
+            &asset_token_raw,
+            &RewardEmission {
+                last_deposit_time: env.block.time.seconds(),
+                reward_amount: normal_reward,
+                reward_interval: if reward_emission.last_deposit_time == 0 {
+                    0
+                } else {
+                    env.block.time.seconds() - reward_emission.last_deposit_time
+                },
+            },
+        )?;
+
         if pool_info.total_bond_amount.is_zero() {
             pool_info.pending_reward += normal_reward;
         } else {
             normal_reward += pool_info.pending_reward;
             let normal_reward_per_bond =
-                Decimal::from_ratio(normal_reward, pool_info.total_bond_amount);
+                RewardIndex::from_ratio(normal_reward, pool_info.total_bond_amount);
             pool_info.reward_index = pool_info.reward_index + normal_reward_per_bond;
             pool_info.pending_reward = Uint128::zero();
         }
@@ -92,7 +128,7 @@ pub fn deposit_reward(
         } else {
             short_reward += pool_info.short_pending_reward;
             let short_reward_per_bond =
-                Decimal::from_ratio(short_reward, pool_info.total_short_amount);
+                RewardIndex::from_ratio(short_reward, pool_info.total_short_amount);
             pool_info.short_reward_index = pool_info.short_reward_index + short_reward_per_bond;
             pool_info.short_pending_reward = Uint128::zero();
         }
@@ -109,19 +145,162 @@ pub fn deposit_reward(
 // withdraw all rewards or single reward depending on asset_token
 pub fn withdraw_reward(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     asset_token: Option<Addr>,
 ) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
     let staker_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
+
     let asset_token = asset_token.map(|a| deps.api.addr_canonicalize(a.as_str()).unwrap());
-    let normal_reward = _withdraw_reward(deps.storage, &staker_addr, &asset_token, false)?;
-    let short_reward = _withdraw_reward(deps.storage, &staker_addr, &asset_token, true)?;
+    if let Some(claimable_time) =
+        claim_cooldown_end(deps.storage, &config, &env, &staker_addr, &asset_token)?
+    {
+        return Err(StdError::generic_err(format!(
+            "Rewards can only be claimed after {}",
+            claimable_time
+        )));
+    }
+
+    let (amount, message, vesting_end_time) =
+        claim_reward(deps.storage, deps.api, &env, &staker_addr, &asset_token)?;
+
+    let mut attributes = vec![
+        attr("action", "withdraw"),
+        attr("amount", amount.to_string()),
+    ];
+    if let Some(vesting_end_time) = vesting_end_time {
+        attributes.push(attr("vesting_end_time", vesting_end_time.to_string()));
+    }
+
+    Ok(Response::new()
+        .add_messages(message)
+        .add_attributes(attributes))
+}
+
+/// returns the time the staker can claim rewards of the asset again,
+/// None when not in cooldown
+fn reward_cooldown_end(config: &Config, env: &Env, reward_info: &RewardInfo) -> Option<u64> {
+    if config.claim_cooldown == 0 || reward_info.last_claim_time == 0 {
+        return None;
+    }
+
+    Some(reward_info.last_claim_time + config.claim_cooldown)
+        .filter(|claimable_time| env.block.time.seconds() < *claimable_time)
+}
+
+/// returns the earliest time the staker can claim rewards again when all its rewards,
+/// of the asset or of every asset, are in cooldown; None when some can be claimed
+fn claim_cooldown_end(
+    storage: &dyn Storage,
+    config: &Config,
+    env: &Env,
+    staker_addr: &CanonicalAddr,
+    asset_token: &Option<CanonicalAddr>,
+) -> StdResult<Option<u64>> {
+    let mut claimable_time: Option<u64> = None;
+    for is_short in [false, true] {
+        let rewards_bucket = rewards_read(storage, staker_addr, is_short);
+        let reward_infos: Vec<RewardInfo> = if let Some(asset_token) = asset_token {
+            rewards_bucket
+                .may_load(asset_token.as_slice())?
+                .into_iter()
+                .collect()
+        } else {
+            rewards_bucket
+                .range(None, None, Order::Ascending)
+                .map(|item| Ok(item?.1))
+                .collect::<StdResult<Vec<RewardInfo>>>()?
+        };
+
+        for reward_info in reward_infos.iter() {
+            match reward_cooldown_end(config, env, reward_info) {
+                None => return Ok(None),
+                Some(time) => {
+                    claimable_time = Some(claimable_time.map_or(time, |t| std::cmp::min(t, time)))
+                }
+            }
+        }
+    }
+
+    Ok(claimable_time)
+}
+
+/// withdraws the long and short rewards of the staker, either transferred to the staker or
+/// released linearly over the vesting period, rewards of assets in claim cooldown are left
+/// pending; returns the withdrawn amount along with the transfer message or the vesting end time
+pub fn claim_reward(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    staker_addr: &CanonicalAddr,
+    asset_token: &Option<CanonicalAddr>,
+) -> StdResult<(Uint128, Option<CosmosMsg>, Option<u64>)> {
+    let config: Config = read_config(storage)?;
+    let normal_reward = _withdraw_reward(storage, env, staker_addr, asset_token, false)?;
+    let short_reward = _withdraw_reward(storage, env, staker_addr, asset_token, true)?;
 
     let amount = normal_reward + short_reward;
+
+    // the rewards are released linearly over the vesting period
+    if config.reward_vesting_period > 0 {
+        let start_time = env.block.time.seconds();
+        let end_time = start_time + config.reward_vesting_period;
+        if !amount.is_zero() {
+            let mut vestings = read_vestings(storage, staker_addr)?;
+            vestings.push(VestingInfo {
+                amount,
+                claimed_amount: Uint128::zero(),
+                start_time,
+                end_time,
+            });
+            store_vestings(storage, staker_addr, &vestings)?;
+        }
+
+        return Ok((amount, None, Some(end_time)));
+    }
+
+    let message = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: api.addr_humanize(&config.reward_token)?.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: api.addr_humanize(staker_addr)?.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    });
+
+    Ok((amount, Some(message), None))
+}
+
+pub fn claim_vested(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    let mut amount = Uint128::zero();
+    let mut vestings: Vec<VestingInfo> = vec![];
+    for mut vesting in read_vestings(deps.storage, &staker_addr_raw)? {
+        let elapsed =
+            std::cmp::min(env.block.time.seconds(), vesting.end_time) - vesting.start_time;
+        let vested_amount = vesting
+            .amount
+            .multiply_ratio(elapsed, vesting.end_time - vesting.start_time);
+
+        amount += vested_amount.checked_sub(vesting.claimed_amount)?;
+        vesting.claimed_amount = vested_amount;
+        if vesting.claimed_amount < vesting.amount {
+            vestings.push(vesting);
+        }
+    }
+
+    if amount.is_zero() {
+        return Err(StdError::generic_err("No vested rewards to claim"));
+    }
+
+    store_vestings(deps.storage, &staker_addr_raw, &vestings)?;
+
     Ok(Response::new()
         .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: deps.api.addr_humanize(&config.mirror_token)?.to_string(),
+            contract_addr: deps.api.addr_humanize(&config.reward_token)?.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
                 recipient: info.sender.to_string(),
                 amount,
@@ -129,13 +308,15 @@ pub fn withdraw_reward(
             funds: vec![],
         }))
         .add_attributes(vec![
-            attr("action", "withdraw"),
+            attr("action", "claim_vested"),
+            attr("staker_addr", info.sender.as_str()),
             attr("amount", amount.to_string()),
         ]))
 }
 
 fn _withdraw_reward(
     storage: &mut dyn Storage,
+    env: &Env,
     staker_addr: &CanonicalAddr,
     asset_token: &Option<CanonicalAddr>,
     is_short: bool,
@@ -160,27 +341,48 @@ fn _withdraw_reward(
             .collect::<StdResult<Vec<(CanonicalAddr, RewardInfo)>>>()?
     };
 
+    let config: Config = read_config(storage)?;
     let mut amount: Uint128 = Uint128::zero();
     for reward_pair in reward_pairs {
         let (asset_token_raw, mut reward_info) = reward_pair;
-        let pool_info: PoolInfo = read_pool_info(storage, &asset_token_raw)?;
+        if reward_cooldown_end(&config, env, &reward_info).is_some() {
+            continue;
+        }
+
+        let mut pool_info: PoolInfo = read_pool_info(storage, &asset_token_raw)?;
 
         // Withdraw reward to pending reward
         // if the lp token was migrated, and the user did not close their position yet, cap the reward at the snapshot
-        let pool_index = if is_short {
-            pool_info.short_reward_index
+        let (pool_index, max_bond) = if is_short {
+            (
+                pool_info.short_reward_index,
+                max_reward_bond(&config, &pool_info, is_short),
+            )
         } else if pool_info.migration_params.is_some()
             && !read_is_migrated(storage, &asset_token_raw, staker_addr)
         {
-            pool_info.migration_params.unwrap().index_snapshot
+            (
+                pool_info.migration_params.clone().unwrap().index_snapshot,
+                reward_info.bond_amount,
+            )
         } else {
-            pool_info.reward_index
+            (
+                pool_info.reward_index,
+                max_reward_bond(&config, &pool_info, is_short),
+            )
         };
 
-        before_share_change(pool_index, &mut reward_info)?;
+        let excess_reward = before_share_change(pool_index, max_bond, &mut reward_info)?;
+        if !excess_reward.is_zero() {
+            redistribute_excess_reward(&mut pool_info, &mut reward_info, excess_reward, is_short);
+            store_pool_info(storage, &asset_token_raw, &pool_info)?;
+        }
 
         amount += reward_info.pending_reward;
         reward_info.pending_reward = Uint128::zero();
+        if config.claim_cooldown > 0 {
+            reward_info.last_claim_time = env.block.time.seconds();
+        }
 
         // Update rewards info
         if reward_info.bond_amount.is_zero() {
@@ -194,14 +396,123 @@ fn _withdraw_reward(
     Ok(amount)
 }
 
-// withdraw reward to pending reward
-pub fn before_share_change(pool_index: Decimal, reward_info: &mut RewardInfo) -> StdResult<()> {
-    let pending_reward = (reward_info.bond_amount * pool_index)
+// withdraw reward to pending reward, only `max_bond` of the bond amount accrues rewards;
+// returns the reward forfeited by the bond above it
+pub fn before_share_change(
+    pool_index: RewardIndex,
+    max_bond: Uint128,
+    reward_info: &mut RewardInfo,
+) -> StdResult<Uint128> {
+    let accrued_reward = (reward_info.bond_amount * pool_index)
         .checked_sub(reward_info.bond_amount * reward_info.index)?;
+    let reward_bond = std::cmp::min(reward_info.bond_amount, max_bond);
+    let pending_reward = (reward_bond * pool_index).checked_sub(reward_bond * reward_info.index)?;
 
     reward_info.index = pool_index;
     reward_info.pending_reward += pending_reward;
-    Ok(())
+    Ok(accrued_reward.checked_sub(pending_reward)?)
+}
+
+/// redistributes the reward forfeited by a staker above the max reward bond over the bond
+/// of the other stakers; the staker index moves along, so none of it comes back to the
+/// staker. Without other stakers, it is left pending for the next deposit.
+/// Must run right after `before_share_change`, before the bond amounts change.
+pub fn redistribute_excess_reward(
+    pool_info: &mut PoolInfo,
+    reward_info: &mut RewardInfo,
+    excess_reward: Uint128,
+    is_short: bool,
+) {
+    let (total_amount, pool_index, pending_reward) = if is_short {
+        (
+            pool_info.total_short_amount,
+            &mut pool_info.short_reward_index,
+            &mut pool_info.short_pending_reward,
+        )
+    } else {
+        (
+            pool_info.total_bond_amount,
+            &mut pool_info.reward_index,
+            &mut pool_info.pending_reward,
+        )
+    };
+
+    let other_amount = total_amount.checked_sub(reward_info.bond_amount).unwrap();
+    if other_amount.is_zero() {
+        *pending_reward += excess_reward;
+        return;
+    }
+
+    *pool_index = *pool_index + RewardIndex::from_ratio(excess_reward, other_amount);
+    reward_info.index = *pool_index;
+}
+
+/// largest bond amount accruing rewards, given the max reward share per staker.
+/// The cap is applied when the staker rewards are settled (on bond, unbond and withdraw),
+/// against the pool total at that time, before the staker's own change. The rewards
+/// accrued since the previous settlement above the cap are redistributed over the bond
+/// of the other stakers.
+pub fn max_reward_bond(config: &Config, pool_info: &PoolInfo, is_short: bool) -> Uint128 {
+    let total_amount = if is_short {
+        pool_info.total_short_amount
+    } else {
+        pool_info.total_bond_amount
+    };
+
+    total_amount * config.max_reward_share_per_staker
+}
+
+pub fn query_pool_apr(deps: Deps, asset_token: String) -> StdResult<PoolAprResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let pool_info: PoolInfo = read_pool_info(deps.storage, &asset_token_raw)?;
+    let reward_emission = read_reward_emission(deps.storage, &asset_token_raw)?;
+
+    let total_bond_value = query_lp_value(
+        deps,
+        deps.api.addr_humanize(&config.terraswap_factory)?,
+        deps.api.addr_validate(&asset_token)?,
+        config.base_denom,
+        pool_info.total_bond_amount,
+    )?;
+
+    Ok(PoolAprResponse {
+        asset_token,
+        reward_amount: reward_emission.reward_amount,
+        reward_interval: reward_emission.reward_interval,
+        total_bond_amount: pool_info.total_bond_amount,
+        total_bond_value,
+    })
+}
+
+pub fn query_bond_simulation(
+    deps: Deps,
+    asset_token: String,
+    amount: Uint128,
+) -> StdResult<BondSimulationResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let pool_info: PoolInfo = read_pool_info(deps.storage, &asset_token_raw)?;
+    let reward_emission = read_reward_emission(deps.storage, &asset_token_raw)?;
+
+    let total_bond_amount = pool_info.total_bond_amount + amount;
+    let share = if total_bond_amount.is_zero() {
+        Decimal::zero()
+    } else {
+        std::cmp::min(
+            Decimal::from_ratio(amount, total_bond_amount),
+            config.max_reward_share_per_staker,
+        )
+    };
+
+    Ok(BondSimulationResponse {
+        asset_token,
+        bond_amount: amount,
+        total_bond_amount,
+        share,
+        reward_amount: reward_emission.reward_amount * share,
+        reward_interval: reward_emission.reward_interval,
+    })
 }
 
 pub fn query_reward_info(
@@ -236,6 +547,7 @@ fn _read_reward_infos(
     asset_token: &Option<String>,
     is_short: bool,
 ) -> StdResult<Vec<RewardInfoResponseItem>> {
+    let config: Config = read_config(storage)?;
     let rewards_bucket = rewards_read(storage, staker_addr, is_short);
     let reward_infos: Vec<RewardInfoResponseItem> = if let Some(asset_token) = asset_token {
         let asset_token_raw = api.addr_canonicalize(asset_token.as_str())?;
@@ -243,20 +555,22 @@ fn _read_reward_infos(
         if let Some(mut reward_info) = rewards_bucket.may_load(asset_token_raw.as_slice())? {
             let pool_info = read_pool_info(storage, &asset_token_raw)?;
 
-            let (pool_index, should_migrate) = if is_short {
-                (pool_info.short_reward_index, None)
+            let max_bond = max_reward_bond(&config, &pool_info, is_short);
+            let (pool_index, max_bond, should_migrate) = if is_short {
+                (pool_info.short_reward_index, max_bond, None)
             } else if pool_info.migration_params.is_some()
                 && !read_is_migrated(storage, &asset_token_raw, staker_addr)
             {
                 (
                     pool_info.migration_params.unwrap().index_snapshot,
+                    reward_info.bond_amount,
                     Some(true),
                 )
             } else {
-                (pool_info.reward_index, None)
+                (pool_info.reward_index, max_bond, None)
             };
 
-            before_share_change(pool_index, &mut reward_info)?;
+            before_share_change(pool_index, max_bond, &mut reward_info)?;
 
             vec![RewardInfoResponseItem {
                 asset_token: asset_token.clone(),
@@ -277,20 +591,22 @@ fn _read_reward_infos(
                 let mut reward_info = v;
 
                 let pool_info = read_pool_info(storage, &asset_token_raw)?;
-                let (pool_index, should_migrate) = if is_short {
-                    (pool_info.short_reward_index, None)
+                let max_bond = max_reward_bond(&config, &pool_info, is_short);
+                let (pool_index, max_bond, should_migrate) = if is_short {
+                    (pool_info.short_reward_index, max_bond, None)
                 } else if pool_info.migration_params.is_some()
                     && !read_is_migrated(storage, &asset_token_raw, staker_addr)
                 {
                     (
                         pool_info.migration_params.unwrap().index_snapshot,
+                        reward_info.bond_amount,
                         Some(true),
                     )
                 } else {
-                    (pool_info.reward_index, None)
+                    (pool_info.reward_index, max_bond, None)
                 };
 
-                before_share_change(pool_index, &mut reward_info)?;
+                before_share_change(pool_index, max_bond, &mut reward_info)?;
 
                 Ok(RewardInfoResponseItem {
                     asset_token: api.addr_humanize(&asset_token_raw)?.to_string(),
@@ -1,12 +1,15 @@
 use cosmwasm_std::{
-    attr, to_binary, Addr, CanonicalAddr, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, Storage, Uint128, WasmMsg,
+    attr, to_binary, Addr, Attribute, CanonicalAddr, Coin, CosmosMsg, Decimal, DepsMut, Env,
+    MessageInfo, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
 };
 
-use crate::rewards::before_share_change;
+use crate::math::RewardIndex;
+use crate::migration::{stage_bond_change, REWARD_INFO_VERSION};
+use crate::rewards::{before_share_change, max_reward_bond, redistribute_excess_reward};
 use crate::state::{
-    read_config, read_is_migrated, read_pool_info, rewards_read, rewards_store, store_is_migrated,
-    store_pool_info, Config, PoolInfo, RewardInfo,
+    read_config, read_is_migrated, read_pool_info, read_pool_stakers, read_reward_info_version,
+    read_unbondings, rewards_read, rewards_store, store_is_migrated, store_pool_info,
+    store_pool_staker, store_unbondings, Config, PoolInfo, RewardInfo, UnbondingInfo,
 };
 
 use cw20::Cw20ExecuteMsg;
@@ -31,16 +34,23 @@ pub fn bond(
         false,
     )?;
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "bond"),
-        attr("staker_addr", staker_addr.as_str()),
-        attr("asset_token", asset_token.as_str()),
-        attr("amount", amount.to_string()),
-    ]))
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "bond"),
+            attr("staker_addr", staker_addr.as_str()),
+            attr("asset_token", asset_token.as_str()),
+            attr("amount", amount.to_string()),
+        ])
+        .add_attributes(bond_amount_attributes(
+            deps.storage,
+            &staker_addr_raw,
+            &asset_token_raw,
+        )?))
 }
 
 pub fn unbond(
     deps: DepsMut,
+    env: Env,
     staker_addr: Addr,
     asset_token: Addr,
     amount: Uint128,
@@ -56,6 +66,35 @@ pub fn unbond(
     )?;
     let staking_token_addr: Addr = deps.api.addr_humanize(&staking_token)?;
 
+    // rewards already stopped accruing for the unbonded amount,
+    // the LP tokens are queued until the unbond period is over
+    let config: Config = read_config(deps.storage)?;
+    if config.unbond_period > 0 {
+        let release_time = env.block.time.seconds() + config.unbond_period;
+        let mut unbondings = read_unbondings(deps.storage, &staker_addr_raw)?;
+        unbondings.push(UnbondingInfo {
+            staking_token,
+            amount,
+            release_time,
+        });
+        store_unbondings(deps.storage, &staker_addr_raw, &unbondings)?;
+
+        return Ok(Response::new()
+            .add_attributes(vec![
+                attr("action", "unbond"),
+                attr("staker_addr", staker_addr.as_str()),
+                attr("asset_token", asset_token.as_str()),
+                attr("amount", amount.to_string()),
+                attr("staking_token", staking_token_addr.as_str()),
+                attr("release_time", release_time.to_string()),
+            ])
+            .add_attributes(bond_amount_attributes(
+                deps.storage,
+                &staker_addr_raw,
+                &asset_token_raw,
+            )?));
+    }
+
     Ok(Response::new()
         .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: deps.api.addr_humanize(&staking_token)?.to_string(),
@@ -71,7 +110,138 @@ pub fn unbond(
             attr("asset_token", asset_token.as_str()),
             attr("amount", amount.to_string()),
             attr("staking_token", staking_token_addr.as_str()),
-        ]))
+        ])
+        .add_attributes(bond_amount_attributes(
+            deps.storage,
+            &staker_addr_raw,
+            &asset_token_raw,
+        )?))
+}
+
+/// Unbonds the stakers of a pool, sending back their LP tokens. Their rewards of the pool are
+/// settled into their pending rewards, left for them to withdraw under the usual claim
+/// cooldown and vesting rules. Short bonds are left in place, as they track the mint
+/// positions and are removed when those are closed.
+pub fn force_unbond_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_token: Addr,
+    limit: Option<u32>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    // the pool staker index is built by the reward infos migration
+    if read_reward_info_version(deps.storage)? < REWARD_INFO_VERSION {
+        return Err(StdError::generic_err("Reward infos must be migrated first"));
+    }
+
+    let asset_token_raw: CanonicalAddr = deps.api.addr_canonicalize(asset_token.as_str())?;
+    read_pool_info(deps.storage, &asset_token_raw)?;
+
+    let stakers = read_pool_stakers(deps.storage, &asset_token_raw, limit)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for staker_addr_raw in stakers.iter() {
+        let bond_amount = rewards_read(deps.storage, staker_addr_raw, false)
+            .load(asset_token_raw.as_slice())?
+            .bond_amount;
+        // settles the staker rewards into its pending reward
+        let staking_token: CanonicalAddr = _decrease_bond_amount(
+            deps.storage,
+            staker_addr_raw,
+            &asset_token_raw,
+            bond_amount,
+            false,
+        )?;
+
+        let staker_addr: Addr = deps.api.addr_humanize(staker_addr_raw)?;
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(&staking_token)?.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: staker_addr.to_string(),
+                amount: bond_amount,
+            })?,
+            funds: vec![],
+        }));
+    }
+
+    let has_more = !read_pool_stakers(deps.storage, &asset_token_raw, Some(1))?.is_empty();
+    let pool_info: PoolInfo = read_pool_info(deps.storage, &asset_token_raw)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "force_unbond_pool"),
+        attr("asset_token", asset_token.as_str()),
+        attr("unbonded_count", stakers.len().to_string()),
+        attr("total_bond_amount", pool_info.total_bond_amount.to_string()),
+        attr("has_more", has_more.to_string()),
+    ]))
+}
+
+/// pool total and staker bond amounts after a bond or unbond
+fn bond_amount_attributes(
+    storage: &dyn Storage,
+    staker_addr: &CanonicalAddr,
+    asset_token: &CanonicalAddr,
+) -> StdResult<Vec<Attribute>> {
+    let pool_info: PoolInfo = read_pool_info(storage, asset_token)?;
+    let bond_amount = rewards_read(storage, staker_addr, false)
+        .may_load(asset_token.as_slice())?
+        .map(|reward_info| reward_info.bond_amount)
+        .unwrap_or_default();
+
+    Ok(vec![
+        attr("total_bond_amount", pool_info.total_bond_amount.to_string()),
+        attr("bond_amount", bond_amount.to_string()),
+    ])
+}
+
+pub fn claim_unbonded(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let (matured, pending): (Vec<UnbondingInfo>, Vec<UnbondingInfo>) =
+        read_unbondings(deps.storage, &staker_addr_raw)?
+            .into_iter()
+            .partition(|unbonding| unbonding.release_time <= env.block.time.seconds());
+
+    if matured.is_empty() {
+        return Err(StdError::generic_err("No unbonded tokens to claim"));
+    }
+
+    store_unbondings(deps.storage, &staker_addr_raw, &pending)?;
+
+    // aggregate the claimable amount per staking token
+    let mut claims: Vec<(CanonicalAddr, Uint128)> = vec![];
+    for unbonding in matured {
+        if let Some(claim) = claims
+            .iter_mut()
+            .find(|(staking_token, _)| *staking_token == unbonding.staking_token)
+        {
+            claim.1 += unbonding.amount;
+        } else {
+            claims.push((unbonding.staking_token, unbonding.amount));
+        }
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut claimed_amount = Uint128::zero();
+    for (staking_token, amount) in claims {
+        claimed_amount += amount;
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(&staking_token)?.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }));
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "claim_unbonded"),
+        attr("staker_addr", info.sender.as_str()),
+        attr("amount", claimed_amount.to_string()),
+    ]))
 }
 
 // only mint contract can execute the operation
@@ -299,9 +469,10 @@ fn _increase_bond_amount(
     let mut reward_info: RewardInfo = rewards_read(storage, staker_addr, is_short)
         .load(asset_token.as_slice())
         .unwrap_or_else(|_| RewardInfo {
-            index: Decimal::zero(),
+            index: RewardIndex::zero(),
             bond_amount: Uint128::zero(),
             pending_reward: Uint128::zero(),
+            last_claim_time: 0,
         });
 
     // check if the position should be migrated
@@ -323,7 +494,10 @@ fn _increase_bond_amount(
     };
 
     // Withdraw reward to pending reward; before changing share
-    before_share_change(pool_index, &mut reward_info)?;
+    let config: Config = read_config(storage)?;
+    let max_bond = max_reward_bond(&config, &pool_info, is_short);
+    let excess_reward = before_share_change(pool_index, max_bond, &mut reward_info)?;
+    redistribute_excess_reward(&mut pool_info, &mut reward_info, excess_reward, is_short);
 
     // Increase total short or bond amount
     if is_short {
@@ -333,8 +507,12 @@ fn _increase_bond_amount(
     }
 
     reward_info.bond_amount += amount;
+    stage_bond_change(storage, staker_addr, asset_token, is_short, amount, true)?;
 
     rewards_store(storage, staker_addr, is_short).save(asset_token.as_slice(), &reward_info)?;
+    if !is_short {
+        store_pool_staker(storage, asset_token, staker_addr, true)?;
+    }
     store_pool_info(storage, asset_token, &pool_info)?;
 
     Ok(())
@@ -375,7 +553,16 @@ fn _decrease_bond_amount(
     };
 
     // Distribute reward to pending reward; before changing share
-    before_share_change(pool_index, &mut reward_info)?;
+    let config: Config = read_config(storage)?;
+    let max_bond = if should_migrate {
+        reward_info.bond_amount
+    } else {
+        max_reward_bond(&config, &pool_info, is_short)
+    };
+    let excess_reward = before_share_change(pool_index, max_bond, &mut reward_info)?;
+    if !excess_reward.is_zero() {
+        redistribute_excess_reward(&mut pool_info, &mut reward_info, excess_reward, is_short);
+    }
 
     // Decrease total short or bond amount
     if is_short {
@@ -384,6 +571,9 @@ fn _decrease_bond_amount(
         // if it should migrate, we dont need to decrease from the current total bond amount
         pool_info.total_bond_amount = pool_info.total_bond_amount.checked_sub(amount)?;
     }
+    if is_short || !should_migrate {
+        stage_bond_change(storage, staker_addr, asset_token, is_short, amount, false)?;
+    }
 
     // Update rewards info
     reward_info.bond_amount = reward_info.bond_amount.checked_sub(amount)?;
@@ -397,6 +587,14 @@ fn _decrease_bond_amount(
     } else {
         rewards_store(storage, staker_addr, is_short).save(asset_token.as_slice(), &reward_info)?;
     }
+    if !is_short {
+        store_pool_staker(
+            storage,
+            asset_token,
+            staker_addr,
+            !reward_info.bond_amount.is_zero(),
+        )?;
+    }
 
     // Update pool info
     store_pool_info(storage, asset_token, &pool_info)?;
@@ -1,16 +1,30 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{CanonicalAddr, Decimal, StdResult, Storage, Uint128};
+use cosmwasm_std::{CanonicalAddr, Decimal, Order, StdResult, Storage, Uint128};
 use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
 
+use crate::math::RewardIndex;
+
 pub static KEY_CONFIG: &[u8] = b"config";
 pub static PREFIX_POOL_INFO: &[u8] = b"pool_info";
+static KEY_REWARD_INFO_VERSION: &[u8] = b"reward_info_version";
+static KEY_REWARD_INFO_CURSOR: &[u8] = b"reward_info_cursor";
 
-static PREFIX_REWARD: &[u8] = b"reward";
-static PREFIX_SHORT_REWARD: &[u8] = b"short_reward";
+pub static PREFIX_REWARD: &[u8] = b"reward";
+pub static PREFIX_SHORT_REWARD: &[u8] = b"short_reward";
 
 static PREFIX_IS_MIGRATED: &[u8] = b"is_migrated";
+static PREFIX_UNBONDING: &[u8] = b"unbonding";
+static PREFIX_REWARD_EMISSION: &[u8] = b"reward_emission";
+static PREFIX_STAKING_TOKEN_INDEX: &[u8] = b"staking_token_index";
+static PREFIX_VESTING: &[u8] = b"vesting";
+static PREFIX_POOL_STAKER: &[u8] = b"pool_staker";
+static PREFIX_STAGED_TOTALS: &[u8] = b"staged_totals";
+static PREFIX_PAUSED_REWARD: &[u8] = b"paused_reward";
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
@@ -22,6 +36,16 @@ pub struct Config {
     pub base_denom: String,
     pub premium_min_update_interval: u64,
     pub short_reward_contract: CanonicalAddr,
+    pub distribution_paused: bool,
+    pub reward_token: CanonicalAddr,
+    pub unbond_period: u64, // zero means the LP tokens are returned on unbond
+    pub max_reward_share_per_staker: Decimal, // one means no cap
+    pub claim_cooldown: u64, // zero means rewards can be claimed anytime
+    pub reward_vesting_period: u64, // zero means withdrawn rewards are paid instantly
+    // short rewards are suspended when the oracle price is older or deviates more from the
+    // terraswap price than these bounds, zero means no bound
+    pub max_oracle_price_age: u64,
+    pub max_price_deviation: Decimal,
 }
 
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -32,6 +56,67 @@ pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
     singleton_read(storage, KEY_CONFIG).load()
 }
 
+pub fn store_reward_info_version(storage: &mut dyn Storage, version: u64) -> StdResult<()> {
+    singleton(storage, KEY_REWARD_INFO_VERSION).save(&version)
+}
+
+pub fn read_reward_info_version(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(singleton_read(storage, KEY_REWARD_INFO_VERSION)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+/// position of an unfinished reward infos migration, the long reward infos are
+/// migrated before the short ones
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardInfoCursor {
+    pub is_short: bool,
+    pub last_key: Option<Vec<u8>>, // none means no entry was migrated yet
+}
+
+pub fn store_reward_info_cursor(
+    storage: &mut dyn Storage,
+    cursor: &RewardInfoCursor,
+) -> StdResult<()> {
+    singleton(storage, KEY_REWARD_INFO_CURSOR).save(cursor)
+}
+
+pub fn remove_reward_info_cursor(storage: &mut dyn Storage) {
+    singleton::<RewardInfoCursor>(storage, KEY_REWARD_INFO_CURSOR).remove()
+}
+
+pub fn read_reward_info_cursor(storage: &dyn Storage) -> StdResult<Option<RewardInfoCursor>> {
+    singleton_read(storage, KEY_REWARD_INFO_CURSOR).may_load()
+}
+
+/// pool totals summed up by an unfinished reward infos migration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct StagedTotals {
+    pub total_bond_amount: Uint128,
+    pub total_short_amount: Uint128,
+}
+
+pub fn store_staged_totals(
+    storage: &mut dyn Storage,
+    asset_token: &CanonicalAddr,
+    staged_totals: &StagedTotals,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_STAGED_TOTALS).save(asset_token.as_slice(), staged_totals)
+}
+
+pub fn remove_staged_totals(storage: &mut dyn Storage, asset_token: &CanonicalAddr) {
+    Bucket::<StagedTotals>::new(storage, PREFIX_STAGED_TOTALS).remove(asset_token.as_slice())
+}
+
+pub fn read_staged_totals(
+    storage: &dyn Storage,
+    asset_token: &CanonicalAddr,
+) -> StdResult<StagedTotals> {
+    Ok(ReadonlyBucket::new(storage, PREFIX_STAGED_TOTALS)
+        .may_load(asset_token.as_slice())?
+        .unwrap_or_default())
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PoolInfo {
     pub staking_token: CanonicalAddr,
@@ -39,8 +124,8 @@ pub struct PoolInfo {
     pub short_pending_reward: Uint128, // not distributed amount due to zero bonding
     pub total_bond_amount: Uint128,
     pub total_short_amount: Uint128,
-    pub reward_index: Decimal,
-    pub short_reward_index: Decimal,
+    pub reward_index: RewardIndex,
+    pub short_reward_index: RewardIndex,
     pub premium_rate: Decimal,
     pub short_reward_weight: Decimal,
     pub premium_updated_time: u64,
@@ -49,7 +134,7 @@ pub struct PoolInfo {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrationParams {
-    pub index_snapshot: Decimal,
+    pub index_snapshot: RewardIndex,
     pub deprecated_staking_token: CanonicalAddr,
 }
 
@@ -65,11 +150,40 @@ pub fn read_pool_info(storage: &dyn Storage, asset_token: &CanonicalAddr) -> Std
     ReadonlyBucket::new(storage, PREFIX_POOL_INFO).load(asset_token.as_slice())
 }
 
+pub fn read_pool_infos(storage: &dyn Storage) -> StdResult<Vec<(CanonicalAddr, PoolInfo)>> {
+    ReadonlyBucket::new(storage, PREFIX_POOL_INFO)
+        .range(None, None, Order::Ascending)
+        .map(|item| {
+            let (k, pool_info) = item?;
+            Ok((CanonicalAddr::from(k), pool_info))
+        })
+        .collect()
+}
+
+/// indexes the asset of a pool by its (current or deprecated) staking token
+pub fn store_staking_token_index(
+    storage: &mut dyn Storage,
+    staking_token: &CanonicalAddr,
+    asset_token: &CanonicalAddr,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_STAKING_TOKEN_INDEX).save(staking_token.as_slice(), asset_token)
+}
+
+pub fn read_staking_token_index(
+    storage: &dyn Storage,
+    staking_token: &CanonicalAddr,
+) -> StdResult<CanonicalAddr> {
+    ReadonlyBucket::new(storage, PREFIX_STAKING_TOKEN_INDEX).load(staking_token.as_slice())
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RewardInfo {
-    pub index: Decimal,
+    pub index: RewardIndex,
     pub bond_amount: Uint128,
     pub pending_reward: Uint128,
+    // rewards of the asset are claimable again after the claim cooldown, zero if never claimed
+    #[serde(default)]
+    pub last_claim_time: u64,
 }
 
 /// returns a bucket with all rewards owned by this owner (query it by owner)
@@ -99,6 +213,71 @@ pub fn rewards_read<'a>(
     }
 }
 
+/// indexes the stakers with a (non short) bond in the pool, so they can be
+/// listed without scanning the reward infos of every pool
+pub fn store_pool_staker(
+    storage: &mut dyn Storage,
+    asset_token: &CanonicalAddr,
+    staker: &CanonicalAddr,
+    is_bonded: bool,
+) -> StdResult<()> {
+    let mut pool_staker_bucket: Bucket<bool> =
+        Bucket::multilevel(storage, &[PREFIX_POOL_STAKER, asset_token.as_slice()]);
+    if is_bonded {
+        pool_staker_bucket.save(staker.as_slice(), &true)
+    } else {
+        pool_staker_bucket.remove(staker.as_slice());
+        Ok(())
+    }
+}
+
+/// returns up to `limit` stakers with a bond in the pool
+pub fn read_pool_stakers(
+    storage: &dyn Storage,
+    asset_token: &CanonicalAddr,
+    limit: Option<u32>,
+) -> StdResult<Vec<CanonicalAddr>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    ReadonlyBucket::<bool>::multilevel(storage, &[PREFIX_POOL_STAKER, asset_token.as_slice()])
+        .range(None, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, _) = item?;
+            Ok(CanonicalAddr::from(k))
+        })
+        .collect()
+}
+
+/// rewards deposited while distribution is paused, held per depositor until unpaused
+pub fn store_paused_reward(
+    storage: &mut dyn Storage,
+    depositor: &CanonicalAddr,
+    amount: Uint128,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_PAUSED_REWARD).save(depositor.as_slice(), &amount)
+}
+
+pub fn remove_paused_reward(storage: &mut dyn Storage, depositor: &CanonicalAddr) {
+    Bucket::<Uint128>::new(storage, PREFIX_PAUSED_REWARD).remove(depositor.as_slice())
+}
+
+pub fn read_paused_reward(storage: &dyn Storage, depositor: &CanonicalAddr) -> StdResult<Uint128> {
+    Ok(ReadonlyBucket::new(storage, PREFIX_PAUSED_REWARD)
+        .may_load(depositor.as_slice())?
+        .unwrap_or_default())
+}
+
+pub fn read_paused_rewards(storage: &dyn Storage) -> StdResult<Vec<(CanonicalAddr, Uint128)>> {
+    ReadonlyBucket::<Uint128>::new(storage, PREFIX_PAUSED_REWARD)
+        .range(None, None, Order::Ascending)
+        .map(|item| {
+            let (k, amount) = item?;
+            Ok((CanonicalAddr::from(k), amount))
+        })
+        .collect()
+}
+
 pub fn store_is_migrated(
     storage: &mut dyn Storage,
     asset_token: &CanonicalAddr,
@@ -117,3 +296,89 @@ pub fn read_is_migrated(
         .load(asset_token.as_slice())
         .unwrap_or(false)
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondingInfo {
+    pub staking_token: CanonicalAddr,
+    pub amount: Uint128,
+    pub release_time: u64,
+}
+
+pub fn store_unbondings(
+    storage: &mut dyn Storage,
+    staker: &CanonicalAddr,
+    unbondings: &[UnbondingInfo],
+) -> StdResult<()> {
+    let mut unbonding_bucket: Bucket<Vec<UnbondingInfo>> = Bucket::new(storage, PREFIX_UNBONDING);
+    if unbondings.is_empty() {
+        unbonding_bucket.remove(staker.as_slice());
+        Ok(())
+    } else {
+        unbonding_bucket.save(staker.as_slice(), &unbondings.to_vec())
+    }
+}
+
+pub fn read_unbondings(
+    storage: &dyn Storage,
+    staker: &CanonicalAddr,
+) -> StdResult<Vec<UnbondingInfo>> {
+    let unbonding_bucket: ReadonlyBucket<Vec<UnbondingInfo>> =
+        ReadonlyBucket::new(storage, PREFIX_UNBONDING);
+    Ok(unbonding_bucket
+        .may_load(staker.as_slice())?
+        .unwrap_or_default())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingInfo {
+    pub amount: Uint128,
+    pub claimed_amount: Uint128,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+pub fn store_vestings(
+    storage: &mut dyn Storage,
+    staker: &CanonicalAddr,
+    vestings: &[VestingInfo],
+) -> StdResult<()> {
+    let mut vesting_bucket: Bucket<Vec<VestingInfo>> = Bucket::new(storage, PREFIX_VESTING);
+    if vestings.is_empty() {
+        vesting_bucket.remove(staker.as_slice());
+        Ok(())
+    } else {
+        vesting_bucket.save(staker.as_slice(), &vestings.to_vec())
+    }
+}
+
+pub fn read_vestings(storage: &dyn Storage, staker: &CanonicalAddr) -> StdResult<Vec<VestingInfo>> {
+    let vesting_bucket: ReadonlyBucket<Vec<VestingInfo>> =
+        ReadonlyBucket::new(storage, PREFIX_VESTING);
+    Ok(vesting_bucket
+        .may_load(staker.as_slice())?
+        .unwrap_or_default())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct RewardEmission {
+    pub last_deposit_time: u64,
+    pub reward_amount: Uint128,
+    pub reward_interval: u64,
+}
+
+pub fn store_reward_emission(
+    storage: &mut dyn Storage,
+    asset_token: &CanonicalAddr,
+    reward_emission: &RewardEmission,
+) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_REWARD_EMISSION).save(asset_token.as_slice(), reward_emission)
+}
+
+pub fn read_reward_emission(
+    storage: &dyn Storage,
+    asset_token: &CanonicalAddr,
+) -> StdResult<RewardEmission> {
+    Ok(ReadonlyBucket::new(storage, PREFIX_REWARD_EMISSION)
+        .may_load(asset_token.as_slice())?
+        .unwrap_or_default())
+}
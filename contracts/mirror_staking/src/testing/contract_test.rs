@@ -1,6 +1,8 @@
 use crate::contract::{execute, instantiate, query};
+use crate::state::store_reward_info_version;
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
 use cosmwasm_std::{attr, from_binary, Addr, Decimal, StdError, Uint128};
+use mirror_protocol::common::OwnerInfo;
 use mirror_protocol::staking::{
     ConfigResponse, ExecuteMsg, InstantiateMsg, PoolInfoResponse, QueryMsg,
 };
@@ -18,6 +20,7 @@ fn proper_initialization() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -38,9 +41,27 @@ fn proper_initialization() {
             base_denom: "uusd".to_string(),
             premium_min_update_interval: 3600,
             short_reward_contract: Addr::unchecked("short_reward").to_string(),
+            distribution_paused: false,
+            reward_token: "reward".to_string(),
+            unbond_period: 0,
+            max_reward_share_per_staker: Decimal::one(),
+            claim_cooldown: 0,
+            reward_vesting_period: 0,
+            max_oracle_price_age: 0,
+            max_price_deviation: Decimal::zero(),
         },
         config
     );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Owner {}).unwrap();
+    let owner_info: OwnerInfo = from_binary(&res).unwrap();
+    assert_eq!(
+        owner_info,
+        OwnerInfo {
+            owner: "owner".to_string(),
+            pending_owner: None,
+        }
+    );
 }
 
 #[test]
@@ -56,6 +77,7 @@ fn update_config() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: Addr::unchecked("short_reward").to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -67,6 +89,13 @@ fn update_config() {
         owner: Some("owner2".to_string()),
         premium_min_update_interval: Some(7200),
         short_reward_contract: Some(Addr::unchecked("new_short_reward").to_string()),
+        distribution_paused: Some(true),
+        unbond_period: Some(100),
+        max_reward_share_per_staker: None,
+        claim_cooldown: None,
+        reward_vesting_period: None,
+        max_oracle_price_age: None,
+        max_price_deviation: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -85,6 +114,14 @@ fn update_config() {
             base_denom: "uusd".to_string(),
             premium_min_update_interval: 7200,
             short_reward_contract: Addr::unchecked("new_short_reward").to_string(),
+            distribution_paused: true,
+            reward_token: "reward".to_string(),
+            unbond_period: 100,
+            max_reward_share_per_staker: Decimal::one(),
+            claim_cooldown: 0,
+            reward_vesting_period: 0,
+            max_oracle_price_age: 0,
+            max_price_deviation: Decimal::zero(),
         },
         config
     );
@@ -95,6 +132,13 @@ fn update_config() {
         owner: None,
         premium_min_update_interval: Some(7200),
         short_reward_contract: None,
+        distribution_paused: None,
+        unbond_period: None,
+        max_reward_share_per_staker: None,
+        claim_cooldown: None,
+        reward_vesting_period: None,
+        max_oracle_price_age: None,
+        max_price_deviation: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
@@ -117,6 +161,7 @@ fn test_register() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: Addr::unchecked("short_reward").to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -174,4 +219,86 @@ fn test_register() {
             migration_index_snapshot: None,
         }
     );
+
+    // resolve the pool from its LP token
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PoolByStakingToken {
+            staking_token: "staking".to_string(),
+        },
+    )
+    .unwrap();
+    let pool_by_staking_token: PoolInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(pool_by_staking_token, pool_info);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PoolByStakingToken {
+            staking_token: "unknown".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("No pool found for the staking token")
+    );
+}
+
+#[test]
+fn test_migrate_reward_infos_once() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::MigrateRewardInfos { limit: None };
+
+    // a new contract starts migrated
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Reward infos were already migrated")
+    );
+
+    // contract deployed before the reward infos version
+    store_reward_info_version(deps.as_mut().storage, 0).unwrap();
+
+    // unauthorized
+    let info = mock_info("addr", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate_reward_infos"),
+            attr("merged_count", "0"),
+            attr("removed_count", "0"),
+            attr("has_more", "false"),
+        ]
+    );
+
+    // can only run once
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Reward infos were already migrated")
+    );
 }
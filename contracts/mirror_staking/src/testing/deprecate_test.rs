@@ -23,6 +23,7 @@ fn test_deprecate() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -14,7 +14,9 @@ pub struct WasmMockQuerier {
     base: MockQuerier<TerraQueryWrapper>,
     pair_addr: Addr,
     pool_assets: [Asset; 2],
+    pool_total_share: Uint128,
     oracle_price: Decimal,
+    oracle_price_last_updated: u64,
     token_balance: Uint128,
     tax: (Decimal, Uint128),
     short_reward_weight: Decimal,
@@ -107,7 +109,7 @@ impl WasmMockQuerier {
                 MockQueryMsg::Pool {} => {
                     SystemResult::Ok(ContractResult::from(to_binary(&PoolResponse {
                         assets: self.pool_assets.clone(),
-                        total_share: Uint128::zero(),
+                        total_share: self.pool_total_share,
                     })))
                 }
                 MockQueryMsg::Price {
@@ -115,7 +117,7 @@ impl WasmMockQuerier {
                     timeframe: _,
                 } => SystemResult::Ok(ContractResult::from(to_binary(&PriceResponse {
                     rate: self.oracle_price,
-                    last_updated: 100,
+                    last_updated: self.oracle_price_last_updated,
                 }))),
                 MockQueryMsg::Balance { address: _ } => {
                     SystemResult::Ok(ContractResult::from(to_binary(&cw20::BalanceResponse {
@@ -160,7 +162,9 @@ impl WasmMockQuerier {
                     amount: Uint128::zero(),
                 },
             ],
+            pool_total_share: Uint128::zero(),
             oracle_price: Decimal::zero(),
+            oracle_price_last_updated: 100,
             token_balance: Uint128::zero(),
             tax: (Decimal::percent(1), Uint128::new(1000000)),
             short_reward_weight: Decimal::percent(20),
@@ -175,10 +179,18 @@ impl WasmMockQuerier {
         self.pool_assets = pool_assets;
     }
 
+    pub fn with_pool_total_share(&mut self, pool_total_share: Uint128) {
+        self.pool_total_share = pool_total_share;
+    }
+
     pub fn with_oracle_price(&mut self, oracle_price: Decimal) {
         self.oracle_price = oracle_price;
     }
 
+    pub fn with_oracle_price_last_updated(&mut self, last_updated: u64) {
+        self.oracle_price_last_updated = last_updated;
+    }
+
     pub fn with_token_balance(&mut self, token_balance: Uint128) {
         self.token_balance = token_balance;
     }
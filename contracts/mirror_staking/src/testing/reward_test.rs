@@ -1,15 +1,19 @@
 use crate::contract::{execute, instantiate, query};
+use crate::math::RewardIndex;
 use crate::state::{read_pool_info, rewards_read, store_pool_info, PoolInfo, RewardInfo};
 use crate::testing::mock_querier::mock_dependencies_with_querier;
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Api, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg,
+    attr, from_binary, from_slice, to_binary, to_vec, Addr, Api, CosmosMsg, Decimal, OwnedDeps,
+    StdError, SubMsg, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use mirror_protocol::staking::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolInfoResponse, QueryMsg, RewardInfoResponse,
-    RewardInfoResponseItem,
+    BondSimulationResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolAprResponse,
+    PoolInfoResponse, QueryMsg, RewardInfoResponse, RewardInfoResponseItem, TotalBondedResponse,
+    TotalBondedResponseItem,
 };
+use std::str::FromStr;
 use terraswap::asset::{Asset, AssetInfo};
 
 #[test]
@@ -25,6 +29,7 @@ fn test_deposit_reward() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -117,8 +122,8 @@ fn test_deposit_reward() {
         &mut deps.storage,
         &asset_token_raw,
         &PoolInfo {
-            reward_index: Decimal::zero(),
-            short_reward_index: Decimal::zero(),
+            reward_index: RewardIndex::zero(),
+            short_reward_index: RewardIndex::zero(),
             premium_rate: Decimal::percent(10),
             short_reward_weight: Decimal::percent(40),
             ..pool_info
@@ -165,6 +170,7 @@ fn test_deposit_reward_when_no_bonding() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -271,6 +277,320 @@ fn test_deposit_reward_when_no_bonding() {
     );
 }
 
+#[test]
+fn test_deposit_tiny_reward_over_large_bond() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset".to_string(),
+        staking_token: "staking".to_string(),
+    };
+
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // 10^24 tokens are bonded in total
+    let stakers = [
+        ("addr0000", 400_000_000_000_000_000_000_000u128, 399_999u128),
+        ("addr0001", 600_000_000_000_000_000_000_000u128, 599_999u128),
+    ];
+    for (staker, amount, _) in stakers.iter() {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.to_string(),
+            amount: Uint128::new(*amount),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                asset_token: "asset".to_string(),
+            })
+            .unwrap(),
+        });
+        let info = mock_info("staking", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    // 999999 / 10^24 per bond, truncated to zero by an 18 decimals index
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(999_999u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(999_999u128))],
+        })
+        .unwrap(),
+    });
+    let info = mock_info("reward", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // only the rounding down of each staker reward is left undistributed
+    for (staker, _, reward) in stakers.iter() {
+        let msg = ExecuteMsg::Withdraw {
+            asset_token: Some("asset".to_string()),
+        };
+        let info = mock_info(staker, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "reward".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: staker.to_string(),
+                    amount: Uint128::new(*reward),
+                })
+                .unwrap(),
+                funds: vec![],
+            }))]
+        );
+    }
+
+    let pool_info =
+        read_pool_info(&deps.storage, &deps.api.addr_canonicalize("asset").unwrap()).unwrap();
+    assert_eq!(
+        pool_info.reward_index,
+        RewardIndex::from_str("0.000000000000000000999999").unwrap()
+    );
+    assert_eq!(pool_info.pending_reward, Uint128::zero());
+
+    // the index is reported with the 18 decimals of Decimal
+    let res: PoolInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PoolInfo {
+                asset_token: "asset".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_index, Decimal::zero());
+}
+
+#[test]
+fn test_reward_index_reads_stored_decimal() {
+    let stored = to_vec(&Decimal::from_ratio(1u128, 3u128)).unwrap();
+    let index: RewardIndex = from_slice(&stored).unwrap();
+    assert_eq!(index, Decimal::from_ratio(1u128, 3u128).into());
+    assert_eq!(index.to_decimal(), Decimal::from_ratio(1u128, 3u128));
+
+    let index = RewardIndex::from_ratio(Uint128::new(1u128), Uint128::new(3u128));
+    assert_eq!(index.to_string(), format!("0.{}", "3".repeat(36)));
+    assert_eq!(
+        from_slice::<RewardIndex>(&to_vec(&index).unwrap()).unwrap(),
+        index
+    );
+    assert_eq!(index.to_decimal(), Decimal::from_ratio(1u128, 3u128));
+}
+
+#[test]
+fn test_deposit_reward_when_distribution_paused() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset".to_string(),
+        staking_token: "staking".to_string(),
+    };
+
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            asset_token: "asset".to_string(),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("staking", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // pause distribution
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        premium_min_update_interval: None,
+        short_reward_contract: None,
+        distribution_paused: Some(true),
+        unbond_period: None,
+        max_reward_share_per_staker: None,
+        claim_cooldown: None,
+        reward_vesting_period: None,
+        max_oracle_price_age: None,
+        max_price_deviation: None,
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // factory deposit 100 reward tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(100u128))],
+        })
+        .unwrap(),
+    });
+    let info = mock_info("reward", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit_reward"),
+            attr("rewards_amount", "100"),
+            attr("paused", "true"),
+        ]
+    );
+
+    // reward index is frozen
+    let res: PoolInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PoolInfo {
+                asset_token: "asset".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let res_cmp = res.clone();
+    assert_eq!(
+        res_cmp,
+        PoolInfoResponse {
+            reward_index: Decimal::zero(),
+            pending_reward: Uint128::zero(),
+            ..res
+        }
+    );
+
+    // unbonding keeps working while paused
+    let msg = ExecuteMsg::Unbond {
+        asset_token: "asset".to_string(),
+        amount: Uint128::new(50u128),
+    };
+    let info = mock_info("addr", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfo {
+                asset_token: None,
+                staker_addr: "addr".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.reward_infos,
+        vec![RewardInfoResponseItem {
+            asset_token: "asset".to_string(),
+            pending_reward: Uint128::zero(),
+            bond_amount: Uint128::new(50u128),
+            is_short: false,
+            should_migrate: None,
+        }]
+    );
+
+    // unpause distribution, the rewards of the paused interval go back to the factory
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        premium_min_update_interval: None,
+        short_reward_contract: None,
+        distribution_paused: Some(false),
+        unbond_period: None,
+        max_reward_share_per_staker: None,
+        claim_cooldown: None,
+        reward_vesting_period: None,
+        max_oracle_price_age: None,
+        max_price_deviation: None,
+    };
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "reward".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "factory".to_string(),
+                amount: Uint128::new(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // refunded only once
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert!(res.messages.is_empty());
+
+    // only the deposits after the pause are accrued
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(100u128))],
+        })
+        .unwrap(),
+    });
+    let info = mock_info("reward", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfo {
+                asset_token: None,
+                staker_addr: "addr".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.reward_infos,
+        vec![RewardInfoResponseItem {
+            asset_token: "asset".to_string(),
+            pending_reward: Uint128::new(100u128),
+            bond_amount: Uint128::new(50u128),
+            is_short: false,
+            should_migrate: None,
+        }]
+    );
+}
+
 #[test]
 fn test_before_share_changes() {
     let mut deps = mock_dependencies(&[]);
@@ -284,6 +604,7 @@ fn test_before_share_changes() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -354,7 +675,8 @@ fn test_before_share_changes() {
         RewardInfo {
             pending_reward: Uint128::zero(),
             bond_amount: Uint128::new(100u128),
-            index: Decimal::zero(),
+            index: RewardIndex::zero(),
+            last_claim_time: 0,
         },
         reward_info
     );
@@ -377,7 +699,8 @@ fn test_before_share_changes() {
         RewardInfo {
             pending_reward: Uint128::new(80u128),
             bond_amount: Uint128::new(200u128),
-            index: Decimal::from_ratio(80u128, 100u128),
+            index: Decimal::from_ratio(80u128, 100u128).into(),
+            last_claim_time: 0,
         },
         reward_info
     );
@@ -408,7 +731,8 @@ fn test_before_share_changes() {
         RewardInfo {
             pending_reward: Uint128::new(160u128),
             bond_amount: Uint128::new(100u128),
-            index: Decimal::from_ratio(120u128, 100u128),
+            index: Decimal::from_ratio(120u128, 100u128).into(),
+            last_claim_time: 0,
         },
         reward_info
     );
@@ -427,6 +751,7 @@ fn test_withdraw() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -500,7 +825,7 @@ fn test_withdraw() {
 }
 
 #[test]
-fn withdraw_multiple_rewards() {
+fn test_withdraw_max_reward_share_per_staker() {
     let mut deps = mock_dependencies(&[]);
 
     let msg = InstantiateMsg {
@@ -512,6 +837,7 @@ fn withdraw_multiple_rewards() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -525,64 +851,726 @@ fn withdraw_multiple_rewards() {
     let info = mock_info("owner", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    let msg = ExecuteMsg::RegisterAsset {
-        asset_token: "asset2".to_string(),
-        staking_token: "staking2".to_string(),
+    // cap each staker at half of the pool rewards
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        premium_min_update_interval: None,
+        short_reward_contract: None,
+        distribution_paused: None,
+        unbond_period: None,
+        max_reward_share_per_staker: Some(Decimal::percent(50)),
+        claim_cooldown: None,
+        reward_vesting_period: None,
+        max_oracle_price_age: None,
+        max_price_deviation: None,
     };
-
     let info = mock_info("owner", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    // store 3% premium rate
-    let token_raw = deps.api.addr_canonicalize("asset").unwrap();
-    let pool_info = read_pool_info(&deps.storage, &token_raw).unwrap();
-    store_pool_info(
-        &mut deps.storage,
-        &token_raw,
-        &PoolInfo {
-            premium_rate: Decimal::percent(2),
-            short_reward_weight: Decimal::percent(20),
-            ..pool_info
-        },
-    )
-    .unwrap();
-
-    // store 3% premium rate for asset2
-    let token_raw = deps.api.addr_canonicalize("asset2").unwrap();
-    let pool_info = read_pool_info(&deps.storage, &token_raw).unwrap();
-    store_pool_info(
-        &mut deps.storage,
-        &token_raw,
-        &PoolInfo {
-            premium_rate: Decimal::percent(2),
-            short_reward_weight: Decimal::percent(20),
-            ..pool_info
-        },
-    )
-    .unwrap();
-
-    // bond 100 tokens
-    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-        sender: "addr".to_string(),
-        amount: Uint128::new(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {
-            asset_token: "asset".to_string(),
-        })
-        .unwrap(),
-    });
-    let info = mock_info("staking", &[]);
-    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    // whale bonds 900 tokens, small staker bonds 100 tokens
+    for (staker, amount) in [("whale", 900u128), ("small", 100u128)] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                asset_token: "asset".to_string(),
+            })
+            .unwrap(),
+        });
+        let info = mock_info("staking", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
 
-    // bond second 1000 tokens
-    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-        sender: "addr".to_string(),
+    let deposit_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
         amount: Uint128::new(1000u128),
-        msg: to_binary(&Cw20HookMsg::Bond {
-            asset_token: "asset2".to_string(),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(1000u128))],
         })
         .unwrap(),
     });
-    let info = mock_info("staking2", &[]);
+    let info = mock_info("reward", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, deposit_msg.clone()).unwrap();
+
+    let withdraw_msg = ExecuteMsg::Withdraw {
+        asset_token: Some("asset".to_string()),
+    };
+
+    // whale only earns on 500 of its 900 tokens, the excess 400 goes to the small staker
+    let info = mock_info("whale", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, withdraw_msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "withdraw"), attr("amount", "500")]
+    );
+
+    let pool_info =
+        read_pool_info(&deps.storage, &deps.api.addr_canonicalize("asset").unwrap()).unwrap();
+    assert_eq!(pool_info.pending_reward, Uint128::zero());
+
+    // none of the excess comes back to the whale on the next deposit
+    let info = mock_info("reward", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, deposit_msg).unwrap();
+
+    let info = mock_info("whale", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, withdraw_msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "withdraw"), attr("amount", "500")]
+    );
+
+    // the small staker earns 100 + 400 of each deposit
+    let info = mock_info("small", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, withdraw_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "withdraw"), attr("amount", "1000")]
+    );
+}
+
+#[test]
+fn test_max_reward_share_per_staker_uses_settlement_total() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset".to_string(),
+        staking_token: "staking".to_string(),
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        premium_min_update_interval: None,
+        short_reward_contract: None,
+        distribution_paused: None,
+        unbond_period: None,
+        max_reward_share_per_staker: Some(Decimal::percent(50)),
+        claim_cooldown: None,
+        reward_vesting_period: None,
+        max_oracle_price_age: None,
+        max_price_deviation: None,
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let bond_msg = |staker: &str, amount: u128| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                asset_token: "asset".to_string(),
+            })
+            .unwrap(),
+        })
+    };
+    for (staker, amount) in [("whale", 900u128), ("small", 100u128)] {
+        let info = mock_info("staking", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, bond_msg(staker, amount)).unwrap();
+    }
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(1000u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(1000u128))],
+        })
+        .unwrap(),
+    });
+    let info = mock_info("reward", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the small staker bonds 800 more before the whale settles, the cap is measured
+    // against the pool total of 1800 at settlement, covering the whole 900 bond
+    let info = mock_info("staking", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, bond_msg("small", 800)).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        asset_token: Some("asset".to_string()),
+    };
+    let info = mock_info("whale", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "withdraw"), attr("amount", "900")]
+    );
+
+    // the small staker was settled on its bond, against the pool total of 1000
+    let info = mock_info("small", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "withdraw"), attr("amount", "100")]
+    );
+
+    let pool_info =
+        read_pool_info(&deps.storage, &deps.api.addr_canonicalize("asset").unwrap()).unwrap();
+    assert_eq!(pool_info.pending_reward, Uint128::zero());
+}
+
+#[test]
+fn test_withdraw_claim_cooldown() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset".to_string(),
+        staking_token: "staking".to_string(),
+    };
+
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        premium_min_update_interval: None,
+        short_reward_contract: None,
+        distribution_paused: None,
+        unbond_period: None,
+        max_reward_share_per_staker: None,
+        claim_cooldown: Some(100),
+        reward_vesting_period: None,
+        max_oracle_price_age: None,
+        max_price_deviation: None,
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            asset_token: "asset".to_string(),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("staking", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let deposit_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(100u128))],
+        })
+        .unwrap(),
+    });
+    let info = mock_info("reward", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, deposit_msg.clone()).unwrap();
+
+    let withdraw_msg = ExecuteMsg::Withdraw { asset_token: None };
+    let info = mock_info("addr", &[]);
+    let mut env = mock_env();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        withdraw_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "withdraw"), attr("amount", "100")]
+    );
+
+    let _res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("reward", &[]),
+        deposit_msg,
+    )
+    .unwrap();
+
+    // a second claim within the cooldown is rejected
+    env.block.time = env.block.time.plus_seconds(99);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        withdraw_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err(format!(
+            "Rewards can only be claimed after {}",
+            mock_env().block.time.seconds() + 100
+        ))
+    );
+
+    // allowed once the cooldown has passed
+    env.block.time = env.block.time.plus_seconds(1);
+    let res = execute(deps.as_mut(), env, info, withdraw_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "withdraw"), attr("amount", "100")]
+    );
+}
+
+#[test]
+fn test_claim_cooldown_per_asset() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        premium_min_update_interval: None,
+        short_reward_contract: None,
+        distribution_paused: None,
+        unbond_period: None,
+        max_reward_share_per_staker: None,
+        claim_cooldown: Some(100),
+        reward_vesting_period: None,
+        max_oracle_price_age: None,
+        max_price_deviation: None,
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for (asset_token, staking_token) in [("asset", "staking"), ("asset2", "staking2")] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            staking_token: staking_token.to_string(),
+        };
+        let info = mock_info("owner", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr".to_string(),
+            amount: Uint128::new(100u128),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                asset_token: asset_token.to_string(),
+            })
+            .unwrap(),
+        });
+        let info = mock_info(staking_token, &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(300u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![
+                ("asset".to_string(), Uint128::new(100u128)),
+                ("asset2".to_string(), Uint128::new(200u128)),
+            ],
+        })
+        .unwrap(),
+    });
+    let info = mock_info("reward", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let withdraw_msg = |asset_token: Option<&str>| ExecuteMsg::Withdraw {
+        asset_token: asset_token.map(|a| a.to_string()),
+    };
+    let cooldown_err = StdError::generic_err(format!(
+        "Rewards can only be claimed after {}",
+        mock_env().block.time.seconds() + 100
+    ));
+
+    // claiming the rewards of an asset starts the cooldown of that asset only
+    let info = mock_info("addr", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        withdraw_msg(Some("asset")),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "withdraw"), attr("amount", "100")]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        withdraw_msg(Some("asset")),
+    )
+    .unwrap_err();
+    assert_eq!(res, cooldown_err);
+
+    // the rewards of the other asset are still claimable, the ones in cooldown are left pending
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), withdraw_msg(None)).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "withdraw"), attr("amount", "200")]
+    );
+
+    // all rewards are in cooldown
+    let res = execute(deps.as_mut(), mock_env(), info, withdraw_msg(None)).unwrap_err();
+    assert_eq!(res, cooldown_err);
+}
+
+#[test]
+fn test_claim_vested_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset".to_string(),
+        staking_token: "staking".to_string(),
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // rewards vest over 100 seconds
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        premium_min_update_interval: None,
+        short_reward_contract: None,
+        distribution_paused: None,
+        unbond_period: None,
+        max_reward_share_per_staker: None,
+        claim_cooldown: None,
+        reward_vesting_period: Some(100),
+        max_oracle_price_age: None,
+        max_price_deviation: None,
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            asset_token: "asset".to_string(),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("staking", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let deposit_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(100u128))],
+        })
+        .unwrap(),
+    });
+    let info = mock_info("reward", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, deposit_msg.clone()).unwrap();
+
+    // the withdrawn rewards enter the vesting schedule instead of being paid
+    let mut env = mock_env();
+    let start_time = env.block.time.seconds();
+    let withdraw_msg = ExecuteMsg::Withdraw { asset_token: None };
+    let info = mock_info("addr", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        withdraw_msg.clone(),
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw"),
+            attr("amount", "100"),
+            attr("vesting_end_time", (start_time + 100).to_string()),
+        ]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::ClaimVested {},
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("No vested rewards to claim"));
+
+    let reward_transfer = |amount: u128| {
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "reward".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr".to_string(),
+                amount: Uint128::new(amount),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    };
+
+    // a quarter of the rewards vested
+    env.block.time = env.block.time.plus_seconds(25);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::ClaimVested {},
+    )
+    .unwrap();
+    assert_eq!(res.messages, reward_transfer(25));
+
+    // a second withdraw starts a new schedule
+    env.block.time = env.block.time.plus_seconds(25);
+    let _res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("reward", &[]),
+        deposit_msg,
+    )
+    .unwrap();
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), withdraw_msg).unwrap();
+
+    // the first schedule is fully vested, the second one halfway
+    env.block.time = env.block.time.plus_seconds(50);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::ClaimVested {},
+    )
+    .unwrap();
+    assert_eq!(res.messages, reward_transfer(125));
+
+    env.block.time = env.block.time.plus_seconds(100);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::ClaimVested {},
+    )
+    .unwrap();
+    assert_eq!(res.messages, reward_transfer(50));
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim_vested"),
+            attr("staker_addr", "addr"),
+            attr("amount", "50"),
+        ]
+    );
+
+    // everything was released
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::ClaimVested {}).unwrap_err();
+    assert_eq!(res, StdError::generic_err("No vested rewards to claim"));
+}
+
+#[test]
+fn test_withdraw_custom_reward_token() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "mirror".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: Some("reward".to_string()),
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset".to_string(),
+        staking_token: "staking".to_string(),
+    };
+
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            asset_token: "asset".to_string(),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("staking", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // MIR is not accepted as reward
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(100u128))],
+        })
+        .unwrap(),
+    });
+    let info = mock_info("mirror", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    // deposit 100 reward tokens
+    let info = mock_info("reward", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        asset_token: Some("asset".to_string()),
+    };
+    let info = mock_info("addr", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "reward".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr".to_string(),
+                amount: Uint128::new(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn withdraw_multiple_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset".to_string(),
+        staking_token: "staking".to_string(),
+    };
+
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset2".to_string(),
+        staking_token: "staking2".to_string(),
+    };
+
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // store 3% premium rate
+    let token_raw = deps.api.addr_canonicalize("asset").unwrap();
+    let pool_info = read_pool_info(&deps.storage, &token_raw).unwrap();
+    store_pool_info(
+        &mut deps.storage,
+        &token_raw,
+        &PoolInfo {
+            premium_rate: Decimal::percent(2),
+            short_reward_weight: Decimal::percent(20),
+            ..pool_info
+        },
+    )
+    .unwrap();
+
+    // store 3% premium rate for asset2
+    let token_raw = deps.api.addr_canonicalize("asset2").unwrap();
+    let pool_info = read_pool_info(&deps.storage, &token_raw).unwrap();
+    store_pool_info(
+        &mut deps.storage,
+        &token_raw,
+        &PoolInfo {
+            premium_rate: Decimal::percent(2),
+            short_reward_weight: Decimal::percent(20),
+            ..pool_info
+        },
+    )
+    .unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            asset_token: "asset".to_string(),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("staking", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond second 1000 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::new(1000u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            asset_token: "asset2".to_string(),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("staking2", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // bond 50 short token
@@ -709,19 +1697,170 @@ fn withdraw_multiple_rewards() {
 }
 
 #[test]
-fn test_adjust_premium() {
+fn test_adjust_premium() {
+    let mut deps = mock_dependencies_with_querier(&[]);
+
+    // terraswap price 100
+    // oracle price 100
+    // premium zero
+    deps.querier.with_pair_info(Addr::unchecked("pair"));
+    deps.querier.with_pool_assets([
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(100u128),
+        },
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset".to_string(),
+            },
+            amount: Uint128::from(1u128),
+        },
+    ]);
+    deps.querier
+        .with_oracle_price(Decimal::from_ratio(100u128, 1u128));
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset".to_string(),
+        staking_token: "staking".to_string(),
+    };
+
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::AdjustPremium {
+        asset_tokens: vec!["asset".to_string()],
+    };
+    let mut env = mock_env();
+    let info = mock_info("addr", &[]);
+    let _ = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+    // Check pool state
+    let res: PoolInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PoolInfo {
+                asset_token: "asset".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.premium_rate, Decimal::zero());
+    assert_eq!(res.premium_updated_time, env.block.time.seconds());
+
+    // terraswap price = 90
+    // premium rate = 0
+    deps.querier.with_pool_assets([
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(90u128),
+        },
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset".to_string(),
+            },
+            amount: Uint128::from(1u128),
+        },
+    ]);
+
+    // assert premium update interval
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "cannot adjust premium before premium_min_update_interval passed"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    env.block.time = env.block.time.plus_seconds(3600);
+    let _ = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+    // Check pool state
+    let res: PoolInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PoolInfo {
+                asset_token: "asset".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.premium_rate, Decimal::zero());
+    assert_eq!(res.premium_updated_time, env.block.time.seconds());
+
+    // terraswap price = 105
+    // premium rate = 5%
+    deps.querier.with_pool_assets([
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(105u128),
+        },
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset".to_string(),
+            },
+            amount: Uint128::from(1u128),
+        },
+    ]);
+
+    env.block.time = env.block.time.plus_seconds(3600);
+    let _ = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // Check pool state
+    let res: PoolInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PoolInfo {
+                asset_token: "asset".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.premium_rate, Decimal::percent(5));
+    assert_eq!(res.premium_updated_time, env.block.time.seconds());
+}
+
+#[test]
+fn test_adjust_premium_price_guard() {
     let mut deps = mock_dependencies_with_querier(&[]);
 
-    // terraswap price 100
+    // terraswap price 105
     // oracle price 100
-    // premium zero
+    // premium 5%
     deps.querier.with_pair_info(Addr::unchecked("pair"));
     deps.querier.with_pool_assets([
         Asset {
             info: AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
-            amount: Uint128::from(100u128),
+            amount: Uint128::from(105u128),
         },
         Asset {
             info: AssetInfo::Token {
@@ -742,6 +1881,7 @@ fn test_adjust_premium() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -751,109 +1891,379 @@ fn test_adjust_premium() {
         asset_token: "asset".to_string(),
         staking_token: "staking".to_string(),
     };
-
     let info = mock_info("owner", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    let msg = ExecuteMsg::AdjustPremium {
-        asset_tokens: vec!["asset".to_string()],
+    // oracle prices older than 60 seconds are stale
+    let update_config_msg = |max_oracle_price_age, max_price_deviation| ExecuteMsg::UpdateConfig {
+        owner: None,
+        premium_min_update_interval: None,
+        short_reward_contract: None,
+        distribution_paused: None,
+        unbond_period: None,
+        max_reward_share_per_staker: None,
+        claim_cooldown: None,
+        reward_vesting_period: None,
+        max_oracle_price_age,
+        max_price_deviation,
     };
+    let info = mock_info("owner", &[]);
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        update_config_msg(Some(60), None),
+    )
+    .unwrap();
+
     let mut env = mock_env();
-    let info = mock_info("addr", &[]);
-    let _ = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    deps.querier
+        .with_oracle_price_last_updated(env.block.time.seconds() - 61);
 
-    // Check pool state
-    let res: PoolInfoResponse = from_binary(
-        &query(
-            deps.as_ref(),
-            mock_env(),
-            QueryMsg::PoolInfo {
-                asset_token: "asset".to_string(),
-            },
+    let adjust_msg = ExecuteMsg::AdjustPremium {
+        asset_tokens: vec!["asset".to_string()],
+    };
+    let _res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        adjust_msg.clone(),
+    )
+    .unwrap();
+
+    let pool_info = |deps: &OwnedDeps<_, _, _>| -> PoolInfoResponse {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PoolInfo {
+                    asset_token: "asset".to_string(),
+                },
+            )
+            .unwrap(),
         )
+        .unwrap()
+    };
+    let res = pool_info(&deps);
+    assert_eq!(res.premium_rate, Decimal::zero());
+    assert_eq!(res.short_reward_weight, Decimal::zero());
+
+    // the stale oracle suppresses the short rewards
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(100u128))],
+        })
         .unwrap(),
+    });
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("reward", &[]), msg).unwrap();
+    let res = pool_info(&deps);
+    assert_eq!(res.pending_reward, Uint128::new(100u128));
+    assert_eq!(res.short_pending_reward, Uint128::zero());
+
+    // a fresh price restores them
+    env.block.time = env.block.time.plus_seconds(3600);
+    deps.querier
+        .with_oracle_price_last_updated(env.block.time.seconds());
+    let _res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr", &[]),
+        adjust_msg.clone(),
     )
     .unwrap();
+    let res = pool_info(&deps);
+    assert_eq!(res.premium_rate, Decimal::percent(5));
+    assert_eq!(res.short_reward_weight, Decimal::percent(20));
+
+    // the 5% premium deviates beyond the 4% bound
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        update_config_msg(None, Some(Decimal::percent(4))),
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_seconds(3600);
+    deps.querier
+        .with_oracle_price_last_updated(env.block.time.seconds());
+    let _res = execute(deps.as_mut(), env, mock_info("addr", &[]), adjust_msg).unwrap();
+    let res = pool_info(&deps);
     assert_eq!(res.premium_rate, Decimal::zero());
-    assert_eq!(res.premium_updated_time, env.block.time.seconds());
+    assert_eq!(res.short_reward_weight, Decimal::zero());
+}
 
-    // terraswap price = 90
-    // premium rate = 0
+#[test]
+fn test_query_pool_apr() {
+    let mut deps = mock_dependencies_with_querier(&[]);
+
+    // 1000 uusd and 100 asset in the pool, 500 LP tokens
+    deps.querier.with_pair_info(Addr::unchecked("pair"));
     deps.querier.with_pool_assets([
         Asset {
             info: AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
-            amount: Uint128::from(90u128),
+            amount: Uint128::from(1000u128),
         },
         Asset {
             info: AssetInfo::Token {
                 contract_addr: "asset".to_string(),
             },
-            amount: Uint128::from(1u128),
+            amount: Uint128::from(100u128),
         },
     ]);
+    deps.querier.with_pool_total_share(Uint128::from(500u128));
 
-    // assert premium update interval
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
-    match res {
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
-            msg,
-            "cannot adjust premium before premium_min_update_interval passed"
-        ),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
 
-    env.block.time = env.block.time.plus_seconds(3600);
-    let _ = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    // Check pool state
-    let res: PoolInfoResponse = from_binary(
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset".to_string(),
+        staking_token: "staking".to_string(),
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 LP tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            asset_token: "asset".to_string(),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("staking", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // factory deposits 100 reward tokens, and 200 more after 60 seconds
+    let info = mock_info("reward", &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(100u128))],
+        })
+        .unwrap(),
+    });
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(60);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(200u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(200u128))],
+        })
+        .unwrap(),
+    });
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let res: PoolAprResponse = from_binary(
         &query(
             deps.as_ref(),
             mock_env(),
-            QueryMsg::PoolInfo {
+            QueryMsg::PoolApr {
                 asset_token: "asset".to_string(),
             },
         )
         .unwrap(),
     )
     .unwrap();
-    assert_eq!(res.premium_rate, Decimal::zero());
-    assert_eq!(res.premium_updated_time, env.block.time.seconds());
+    assert_eq!(
+        res,
+        PoolAprResponse {
+            asset_token: "asset".to_string(),
+            reward_amount: Uint128::new(200u128),
+            reward_interval: 60u64,
+            total_bond_amount: Uint128::new(100u128),
+            // 100 / 500 of the pool, 2 * 1000uusd * 0.2
+            total_bond_value: Uint128::new(400u128),
+        }
+    );
+}
 
-    // terraswap price = 105
-    // premium rate = 5%
+#[test]
+fn test_query_total_bonded() {
+    let mut deps = mock_dependencies_with_querier(&[]);
+
+    // 1000 uusd in each pair, 500 LP tokens
+    deps.querier.with_pair_info(Addr::unchecked("pair"));
     deps.querier.with_pool_assets([
         Asset {
             info: AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
-            amount: Uint128::from(105u128),
+            amount: Uint128::from(1000u128),
         },
         Asset {
             info: AssetInfo::Token {
                 contract_addr: "asset".to_string(),
             },
-            amount: Uint128::from(1u128),
+            amount: Uint128::from(100u128),
         },
     ]);
+    deps.querier.with_pool_total_share(Uint128::from(500u128));
 
-    env.block.time = env.block.time.plus_seconds(3600);
-    let _ = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
 
-    // Check pool state
-    let res: PoolInfoResponse = from_binary(
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for (asset_token, staking_token, amount) in [
+        ("asset", "staking", 100u128),
+        ("asset2", "staking2", 50u128),
+    ] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            staking_token: staking_token.to_string(),
+        };
+        let info = mock_info("owner", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr".to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                asset_token: asset_token.to_string(),
+            })
+            .unwrap(),
+        });
+        let info = mock_info(staking_token, &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let res: TotalBondedResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::TotalBonded {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        TotalBondedResponse {
+            pools: vec![
+                TotalBondedResponseItem {
+                    asset_token: "asset".to_string(),
+                    total_bond_amount: Uint128::new(100u128),
+                    total_bond_value: Some(Uint128::new(400u128)),
+                },
+                TotalBondedResponseItem {
+                    asset_token: "asset2".to_string(),
+                    total_bond_amount: Uint128::new(50u128),
+                    total_bond_value: Some(Uint128::new(200u128)),
+                },
+            ],
+            total_bond_value: Uint128::new(600u128),
+        }
+    );
+}
+
+#[test]
+fn test_query_bond_simulation() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset".to_string(),
+        staking_token: "staking".to_string(),
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 300 LP tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::new(300u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            asset_token: "asset".to_string(),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("staking", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // factory deposits 100 reward tokens, and 200 more after 60 seconds
+    let info = mock_info("reward", &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(100u128))],
+        })
+        .unwrap(),
+    });
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(60);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(200u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(200u128))],
+        })
+        .unwrap(),
+    });
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let res: BondSimulationResponse = from_binary(
         &query(
             deps.as_ref(),
             mock_env(),
-            QueryMsg::PoolInfo {
+            QueryMsg::BondSimulation {
                 asset_token: "asset".to_string(),
+                amount: Uint128::new(100u128),
             },
         )
         .unwrap(),
     )
     .unwrap();
-    assert_eq!(res.premium_rate, Decimal::percent(5));
-    assert_eq!(res.premium_updated_time, env.block.time.seconds());
+    assert_eq!(
+        res,
+        BondSimulationResponse {
+            asset_token: "asset".to_string(),
+            bond_amount: Uint128::new(100u128),
+            total_bond_amount: Uint128::new(400u128),
+            // 100 / 400 of the pool, 200 * 0.25 per 60 seconds
+            share: Decimal::percent(25),
+            reward_amount: Uint128::new(50u128),
+            reward_interval: 60u64,
+        }
+    );
 }
@@ -1,4 +1,5 @@
 use crate::contract::{execute, instantiate, query};
+use crate::state::store_reward_info_version;
 use crate::testing::mock_querier::mock_dependencies_with_querier;
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
@@ -26,6 +27,7 @@ fn test_bond_tokens() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -113,7 +115,18 @@ fn test_bond_tokens() {
         .unwrap(),
     });
     let info = mock_info("staking", &[]);
-    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "bond"),
+            attr("staker_addr", "addr2"),
+            attr("asset_token", "asset"),
+            attr("amount", "100"),
+            attr("total_bond_amount", "200"),
+            attr("bond_amount", "100"),
+        ]
+    );
 
     let data = query(
         deps.as_ref(),
@@ -174,6 +187,7 @@ fn test_unbond() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -215,10 +229,31 @@ fn test_unbond() {
         _ => panic!("Must return generic error"),
     };
 
+    // partial unbond
+    let msg = ExecuteMsg::Unbond {
+        asset_token: "asset".to_string(),
+        amount: Uint128::new(40u128),
+    };
+
+    let info = mock_info("addr", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "unbond"),
+            attr("staker_addr", "addr"),
+            attr("asset_token", "asset"),
+            attr("amount", "40"),
+            attr("staking_token", "staking"),
+            attr("total_bond_amount", "60"),
+            attr("bond_amount", "60"),
+        ]
+    );
+
     // normal unbond
     let msg = ExecuteMsg::Unbond {
         asset_token: "asset".to_string(),
-        amount: Uint128::new(100u128),
+        amount: Uint128::new(60u128),
     };
 
     let info = mock_info("addr", &[]);
@@ -229,7 +264,7 @@ fn test_unbond() {
             contract_addr: "staking".to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
                 recipient: "addr".to_string(),
-                amount: Uint128::new(100u128),
+                amount: Uint128::new(60u128),
             })
             .unwrap(),
             funds: vec![],
@@ -283,6 +318,328 @@ fn test_unbond() {
     );
 }
 
+#[test]
+fn test_unbond_with_unbond_period() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // register asset
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset".to_string(),
+        staking_token: "staking".to_string(),
+    };
+
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // set unbond period
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        premium_min_update_interval: None,
+        short_reward_contract: None,
+        distribution_paused: None,
+        unbond_period: Some(100u64),
+        max_reward_share_per_staker: None,
+        claim_cooldown: None,
+        reward_vesting_period: None,
+        max_oracle_price_age: None,
+        max_price_deviation: None,
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            asset_token: "asset".to_string(),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("staking", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // unbond is queued, no tokens are returned
+    let msg = ExecuteMsg::Unbond {
+        asset_token: "asset".to_string(),
+        amount: Uint128::new(100u128),
+    };
+    let env = mock_env();
+    let info = mock_info("addr", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "unbond"),
+            attr("staker_addr", "addr"),
+            attr("asset_token", "asset"),
+            attr("amount", "100"),
+            attr("staking_token", "staking"),
+            attr(
+                "release_time",
+                (env.block.time.seconds() + 100u64).to_string()
+            ),
+            attr("total_bond_amount", "0"),
+            attr("bond_amount", "0"),
+        ]
+    );
+
+    // unbonded tokens do not accrue rewards
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(100u128))],
+        })
+        .unwrap(),
+    });
+    let info = mock_info("reward", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let data = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardInfo {
+            asset_token: Some("asset".to_string()),
+            staker_addr: "addr".to_string(),
+        },
+    )
+    .unwrap();
+    let res: RewardInfoResponse = from_binary(&data).unwrap();
+    assert_eq!(res.reward_infos, vec![]);
+
+    // claim before the unbond period is over
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(99);
+    let info = mock_info("addr", &[]);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::ClaimUnbonded {}).unwrap_err();
+    assert_eq!(res, StdError::generic_err("No unbonded tokens to claim"));
+
+    // claim after the unbond period
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(100);
+    let info = mock_info("addr", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::ClaimUnbonded {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "staking".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr".to_string(),
+                amount: Uint128::new(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // nothing left to claim
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::ClaimUnbonded {}).unwrap_err();
+    assert_eq!(res, StdError::generic_err("No unbonded tokens to claim"));
+}
+
+#[test]
+fn test_force_unbond_pool() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        mirror_token: "reward".to_string(),
+        mint_contract: "mint".to_string(),
+        oracle_contract: "oracle".to_string(),
+        terraswap_factory: "terraswap_factory".to_string(),
+        base_denom: "uusd".to_string(),
+        premium_min_update_interval: 3600,
+        short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset".to_string(),
+        staking_token: "staking".to_string(),
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for (staker, amount) in [("addr", 100u128), ("addr2", 300u128)] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&Cw20HookMsg::Bond {
+                asset_token: "asset".to_string(),
+            })
+            .unwrap(),
+        });
+        let info = mock_info("staking", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let deposit_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "factory".to_string(),
+        amount: Uint128::new(400u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {
+            rewards: vec![("asset".to_string(), Uint128::new(400u128))],
+        })
+        .unwrap(),
+    });
+    let info = mock_info("reward", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, deposit_msg.clone()).unwrap();
+
+    // addr claims its rewards, starting its claim cooldown
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        premium_min_update_interval: None,
+        short_reward_contract: None,
+        distribution_paused: None,
+        unbond_period: None,
+        max_reward_share_per_staker: None,
+        claim_cooldown: Some(100),
+        reward_vesting_period: None,
+        max_oracle_price_age: None,
+        max_price_deviation: None,
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Withdraw { asset_token: None };
+    let info = mock_info("addr", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("reward", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, deposit_msg).unwrap();
+
+    let msg = ExecuteMsg::ForceUnbondPool {
+        asset_token: "asset".to_string(),
+        limit: Some(1),
+    };
+    let info = mock_info("addr", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, StdError::generic_err("unauthorized"));
+
+    // the pool stakers are indexed by the reward infos migration
+    store_reward_info_version(deps.as_mut().storage, 0).unwrap();
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err("Reward infos must be migrated first")
+    );
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::MigrateRewardInfos { limit: None },
+    )
+    .unwrap();
+
+    let transfer = |contract_addr: &str, recipient: &str, amount: u128| {
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: Uint128::new(amount),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))
+    };
+
+    // one staker per call, the second one is left for the next call
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(res.messages, vec![transfer("staking", "addr2", 300)]);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "force_unbond_pool"),
+            attr("asset_token", "asset"),
+            attr("unbonded_count", "1"),
+            attr("total_bond_amount", "100"),
+            attr("has_more", "true"),
+        ]
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(res.messages, vec![transfer("staking", "addr", 100)]);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "force_unbond_pool"),
+            attr("asset_token", "asset"),
+            attr("unbonded_count", "1"),
+            attr("total_bond_amount", "0"),
+            attr("has_more", "false"),
+        ]
+    );
+
+    // nothing is left to unbond
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert!(res.messages.is_empty());
+
+    // the rewards are left pending and the claim cooldowns untouched:
+    // addr2 never claimed, addr is still in cooldown
+    let msg = ExecuteMsg::Withdraw { asset_token: None };
+    let info = mock_info("addr2", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+    assert_eq!(res.messages, vec![transfer("reward", "addr2", 600)]);
+
+    let info = mock_info("addr", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        StdError::generic_err(format!(
+            "Rewards can only be claimed after {}",
+            mock_env().block.time.seconds() + 100
+        ))
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardInfo {
+            staker_addr: "addr".to_string(),
+            asset_token: None,
+        },
+    )
+    .unwrap();
+    let res: RewardInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        res.reward_infos,
+        vec![RewardInfoResponseItem {
+            asset_token: "asset".to_string(),
+            bond_amount: Uint128::zero(),
+            pending_reward: Uint128::new(100u128),
+            is_short: false,
+            should_migrate: None,
+        }]
+    );
+}
+
 #[test]
 fn test_increase_short_token() {
     let mut deps = mock_dependencies(&[]);
@@ -296,6 +653,7 @@ fn test_increase_short_token() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -401,6 +759,7 @@ fn test_decrease_short_token() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -515,6 +874,7 @@ fn test_auto_stake() {
         base_denom: "uusd".to_string(),
         premium_min_update_interval: 3600,
         short_reward_contract: "short_reward".to_string(),
+        reward_token: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -712,6 +1072,8 @@ fn test_auto_stake() {
             attr("staker_addr", "addr0000"),
             attr("asset_token", "asset"),
             attr("amount", "100"),
+            attr("total_bond_amount", "100"),
+            attr("bond_amount", "100"),
         ]
     );
 
@@ -1,4 +1,4 @@
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -35,6 +35,12 @@ pub enum ExecuteMsg {
         asset: AssetInfo,
         multiplier: Decimal,
     },
+    SuspendCollateralAsset {
+        asset: AssetInfo,
+    },
+    ResumeCollateralAsset {
+        asset: AssetInfo,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -49,6 +55,14 @@ pub enum QueryMsg {
         asset: String,
     },
     CollateralAssetInfos {},
+    CollateralValue {
+        collateral: AssetInfo,
+        amount: Uint128,
+    },
+    Collaterals {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -61,10 +75,23 @@ pub struct ConfigResponse {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct CollateralPriceResponse {
     pub asset: String,
+    pub base_denom: String, // denom the rate is quoted in
     pub rate: Decimal,
     pub last_updated: u64,
     pub multiplier: Decimal,
     pub is_revoked: bool,
+    pub is_suspended: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollateralValueResponse {
+    pub asset: String,
+    pub base_denom: String,
+    pub amount: Uint128,
+    pub rate: Decimal,
+    pub multiplier: Decimal,
+    pub value: Uint128,          // amount * rate
+    pub adjusted_value: Uint128, // value / multiplier, as weighted by mint collateral ratios
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -73,6 +100,7 @@ pub struct CollateralInfoResponse {
     pub multiplier: Decimal,
     pub source_type: String,
     pub is_revoked: bool,
+    pub is_suspended: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -80,6 +108,23 @@ pub struct CollateralInfosResponse {
     pub collaterals: Vec<CollateralInfoResponse>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollateralStatusResponse {
+    pub asset: String,
+    pub source_type: String,
+    pub multiplier: Decimal,
+    pub is_revoked: bool,
+    pub is_suspended: bool,
+    pub rate: Option<Decimal>, // None when the price source can not be queried
+    pub last_updated: Option<u64>, // u64::MAX for sources without an update time
+    pub staleness: Option<u64>, // seconds since last_updated
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollateralsResponse {
+    pub collaterals: Vec<CollateralStatusResponse>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {
     pub mirror_tefi_oracle_addr: String,
@@ -1,5 +1,7 @@
+use cosmwasm_std::Uint128;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use terraswap::asset::{Asset, AssetInfo};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -17,6 +19,10 @@ pub struct InstantiateMsg {
     pub lunax_token: String,
     // when set, use this address instead of querying from terraswap
     pub mir_ust_pair: Option<String>,
+    // Distribute is skipped below this MIR balance
+    pub min_distribute_amount: Uint128,
+    // ConvertAndDistribute converts at most this many assets per call
+    pub max_convert_assets: u32,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -24,21 +30,39 @@ pub struct InstantiateMsg {
 #[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     UpdateConfig {
+        /// Only proposes the owner, which takes over once it accepts the ownership
         owner: Option<String>,
         distribution_contract: Option<String>,
         terraswap_factory: Option<String>,
         mirror_token: Option<String>,
+        /// Token the collected fees are converted into, distributed as gov reward
+        /// when it is the mirror_token and transferred otherwise
+        target_token: Option<String>,
         base_denom: Option<String>,
         aust_token: Option<String>,
         anchor_market: Option<String>,
         bluna_token: Option<String>,
         mir_ust_pair: Option<String>,
         lunax_token: Option<String>,
+        min_distribute_amount: Option<Uint128>,
+        max_convert_assets: Option<u32>,
     },
+    /// Propose a new owner, which only takes over once it accepts the ownership
+    ProposeOwner {
+        owner: String,
+    },
+    /// Accept the ownership as the proposed owner
+    AcceptOwnership {},
     Convert {
         asset_token: String,
     },
     Distribute {},
+    /// Converts the listed assets into the target token and distributes only the converted amount.
+    /// Long lists are processed in pages, continuing after start_after
+    ConvertAndDistribute {
+        assets: Vec<AssetInfo>,
+        start_after: Option<AssetInfo>,
+    },
     /// Internal operation to swap Luna for UST
     LunaSwapHook {},
 }
@@ -47,6 +71,13 @@ pub enum ExecuteMsg {
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    /// Returns the current owner, see `common::OwnerInfo`
+    Owner {},
+    /// Returns the collector balance of base_denom, the tokens in its config and the
+    /// listed fee assets, such as the mAssets registered in mint
+    ConvertibleBalances {
+        assets: Vec<AssetInfo>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -61,15 +92,25 @@ pub enum MoneyMarketCw20HookMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub owner: String,
+    pub pending_owner: Option<String>,
     pub distribution_contract: String, // collected rewards receiver
     pub terraswap_factory: String,
     pub mirror_token: String,
+    pub target_token: String,
     pub base_denom: String,
     pub aust_token: String,
     pub anchor_market: String,
     pub bluna_token: String,
     pub lunax_token: String,
     pub mir_ust_pair: Option<String>,
+    pub min_distribute_amount: Uint128,
+    pub max_convert_assets: u32,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConvertibleBalancesResponse {
+    pub balances: Vec<Asset>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -10,6 +10,43 @@ pub enum OrderBy {
     Desc,
 }
 
+/// Paginated queries return the newest entries first unless asked otherwise
+impl Default for OrderBy {
+    fn default() -> Self {
+        OrderBy::Desc
+    }
+}
+
+impl OrderBy {
+    /// Resolves an optional query parameter, falling back to the default ordering
+    pub fn from_opt(order_by: Option<OrderBy>) -> OrderBy {
+        order_by.unwrap_or_default()
+    }
+}
+
+// settings for pagination
+pub const MAX_LIMIT: u32 = 30;
+pub const DEFAULT_LIMIT: u32 = 10;
+
+/// Number of items a paginated query returns, capped at MAX_LIMIT
+pub fn calc_limit(limit: Option<u32>) -> usize {
+    limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize
+}
+
+// this will set the first key after the provided key, by appending a 1 byte
+pub fn calc_range_start<T: AsRef<[u8]>>(start_after: Option<T>) -> Option<Vec<u8>> {
+    start_after.map(|key| {
+        let mut v = key.as_ref().to_vec();
+        v.push(1);
+        v
+    })
+}
+
+// range ends are exclusive, so the provided key itself bounds descending queries
+pub fn calc_range_end<T: AsRef<[u8]>>(start_after: Option<T>) -> Option<Vec<u8>> {
+    start_after.map(|key| key.as_ref().to_vec())
+}
+
 // impl Into<Order> for OrderBy {
 //     fn into(self) -> Order {
 //         if self == OrderBy::Asc {
@@ -30,9 +67,51 @@ impl From<OrderBy> for Order {
     }
 }
 
+/// Shared response of the `Owner {}` query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerInfo {
+    pub owner: String,
+    /// Set while an ownership transfer is waiting to be accepted
+    pub pending_owner: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Network {
     Mainnet,
     Testnet,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order_by_default() {
+        assert_eq!(OrderBy::default(), OrderBy::Desc);
+        assert_eq!(OrderBy::from_opt(None), OrderBy::Desc);
+        assert_eq!(OrderBy::from_opt(Some(OrderBy::Asc)), OrderBy::Asc);
+        assert!(matches!(
+            Order::from(OrderBy::from_opt(None)),
+            Order::Descending
+        ));
+    }
+
+    #[test]
+    fn pagination_bounds() {
+        assert_eq!(calc_limit(None), DEFAULT_LIMIT as usize);
+        assert_eq!(calc_limit(Some(5)), 5);
+        assert_eq!(calc_limit(Some(MAX_LIMIT + 1)), MAX_LIMIT as usize);
+        assert_eq!(calc_limit(Some(u32::MAX)), MAX_LIMIT as usize);
+
+        assert_eq!(
+            calc_range_start(Some(7u64.to_be_bytes())),
+            Some(vec![0, 0, 0, 0, 0, 0, 0, 7, 1])
+        );
+        assert_eq!(
+            calc_range_end(Some(7u64.to_be_bytes())),
+            Some(vec![0, 0, 0, 0, 0, 0, 0, 7])
+        );
+        assert_eq!(calc_range_start::<&[u8]>(None), None);
+    }
+}
@@ -26,9 +26,22 @@ pub enum ExecuteMsg {
         commission_collector: String,
     },
     UpdateConfig {
+        /// Only proposes the owner, which takes over once it accepts the ownership
         owner: Option<String>,
         token_code_id: Option<u64>,
         distribution_schedule: Option<Vec<(u64, u64, Uint128)>>, // [[start_time, end_time, distribution_amount], [], ...]
+        genesis_time: Option<u64>, // schedule times are relative to it
+    },
+    /// Propose a new owner, which only takes over once it accepts the ownership
+    ProposeOwner {
+        owner: String,
+    },
+    /// Accept the ownership as the proposed owner
+    AcceptOwnership {},
+    /// Replace a single tranche of the distribution schedule
+    UpdateScheduleTranche {
+        index: u32,
+        schedule: (u64, u64, Uint128), // [start_time, end_time, distribution_amount]
     },
     UpdateWeight {
         asset_token: String,
@@ -53,6 +66,11 @@ pub enum ExecuteMsg {
     RevokeAsset {
         asset_token: String,
     },
+    /// Provide factory-held funds as liquidity to the asset pair
+    ProvideProtocolLiquidity {
+        asset_token: String,
+        amounts: (Uint128, Uint128), // [asset_amount, base_denom_amount]
+    },
     /// Migrate asset to new asset by registering
     /// end_price to mint contract and add
     /// the new asset to MIR rewards pool
@@ -74,12 +92,14 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     Config {},
     DistributionInfo {},
+    DistributionSchedule {},
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub owner: String,
+    pub pending_owner: Option<String>,
     pub mirror_token: String,
     pub mint_contract: String,
     pub staking_contract: String,
@@ -99,6 +119,14 @@ pub struct DistributionInfoResponse {
     pub last_distributed: u64,
 }
 
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DistributionScheduleResponse {
+    pub genesis_time: u64,
+    pub distribution_schedule: Vec<(u64, u64, Uint128)>, // [[start_time, end_time, distribution_amount], [], ...]
+    pub active_schedule: Option<(u64, u64, Uint128)>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {
     pub tefi_oracle_contract: String,
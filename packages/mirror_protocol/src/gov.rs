@@ -9,14 +9,32 @@ use crate::common::OrderBy;
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub mirror_token: String,
+    /// Token used for proposal deposits, defaults to mirror_token. Any other token
+    /// requires a reward_distributor, which receives the deposits of polls failing quorum
+    pub deposit_token: Option<String>,
     pub effective_delay: u64,
     pub default_poll_config: PollConfig,
     pub migration_poll_config: PollConfig,
     pub auth_admin_poll_config: PollConfig,
     pub voter_weight: Decimal,
     pub snapshot_period: u64,
+    /// When set, overrides snapshot_period with a fraction of the poll's voting period
+    pub snapshot_ratio: Option<Decimal>,
     pub admin_manager: String,
     pub poll_gas_limit: u64,
+    /// Minimum amount per vote, zero disables the check
+    pub min_vote_amount: Uint128,
+    /// Only sender allowed to DepositReward, anyone when unset
+    pub reward_distributor: Option<String>,
+    /// Whether abstain votes count toward quorum, defaults to true
+    pub abstain_in_quorum: Option<bool>,
+    /// Non-refundable fee in deposit_token sent to the reward_distributor on CreatePoll
+    pub poll_creation_fee: Uint128,
+    /// Seconds after the effective delay a passed poll can be executed, zero disables expiry
+    pub execution_window: u64,
+    /// CreatePoll is rejected once more than this many polls are in progress, defaults
+    /// to 50. Admin polls get 10 extra slots
+    pub max_active_polls: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -32,8 +50,20 @@ pub enum ExecuteMsg {
         auth_admin_poll_config: Option<PollConfig>,
         voter_weight: Option<Decimal>,
         snapshot_period: Option<u64>,
+        snapshot_ratio: Option<Decimal>,
         admin_manager: Option<String>,
         poll_gas_limit: Option<u64>,
+        min_vote_amount: Option<Uint128>,
+        reward_distributor: Option<String>,
+        abstain_in_quorum: Option<bool>,
+        poll_creation_fee: Option<Uint128>,
+        execution_window: Option<u64>,
+        max_active_polls: Option<u64>,
+    },
+    /// Migrates up to `limit` polls created before the contract migration,
+    /// call again until `has_more` is false
+    MigratePolls {
+        limit: Option<u32>,
     },
     CastVote {
         poll_id: u64,
@@ -74,11 +104,23 @@ pub enum Cw20HookMsg {
         link: Option<String>,
         execute_msg: Option<PollExecuteMsg>,
         admin_action: Option<PollAdminAction>,
+        /// Contract notified with `PollEndHookMsg::PollEnded` when the poll ends, regardless of the outcome.
+        /// A failing hook does not prevent the poll from ending
+        end_hook: Option<String>,
     },
     /// Deposit rewards to be distributed among stakers and voters
     DepositReward {},
 }
 
+/// Notification gov sends to a poll's end hook contract. The message is fixed rather than
+/// a per-poll `PollExecuteMsg`, so a poll creator can not make gov send arbitrary messages
+/// to the hook contract when the poll ends
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollEndHookMsg {
+    PollEnded { poll_id: u64, status: PollStatus },
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct PollExecuteMsg {
@@ -116,6 +158,7 @@ pub enum PollAdminAction {
         auth_admin_poll_config: Option<PollConfig>,
         voter_weight: Option<Decimal>,
         snapshot_period: Option<u64>,
+        snapshot_ratio: Option<Decimal>,
         admin_manager: Option<String>,
     },
 }
@@ -124,6 +167,8 @@ pub enum PollAdminAction {
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    /// Returns the current owner, see `common::OwnerInfo`
+    Owner {},
     State {},
     Staker {
         address: String,
@@ -131,6 +176,15 @@ pub enum QueryMsg {
     Poll {
         poll_id: u64,
     },
+    /// Absolute timestamps of the poll phases: end of voting, effective time and
+    /// expiration, if the config sets an execution window
+    PollTimeline {
+        poll_id: u64,
+    },
+    /// Voting rewards allocated to the poll and how much voters have withdrawn
+    PollRewardStats {
+        poll_id: u64,
+    },
     Polls {
         filter: Option<PollStatus>,
         start_after: Option<u64>,
@@ -141,8 +195,14 @@ pub enum QueryMsg {
         poll_id: u64,
         address: String,
     },
+    /// Vote of a single address, none when the address has not voted
+    HasVoted {
+        poll_id: u64,
+        address: String,
+    },
     Voters {
         poll_id: u64,
+        vote: Option<VoteOption>,
         start_after: Option<String>,
         limit: Option<u32>,
         order_by: Option<OrderBy>,
@@ -152,20 +212,32 @@ pub enum QueryMsg {
         limit: Option<u32>,
         order_by: Option<OrderBy>,
     },
+    /// Breakdown of the staker balance and voting rewards per poll
+    StakerRewardDebug {
+        address: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub owner: String,
     pub mirror_token: String,
+    pub deposit_token: String,
     pub effective_delay: u64,
     pub default_poll_config: PollConfig,
     pub migration_poll_config: PollConfig,
     pub auth_admin_poll_config: PollConfig,
     pub voter_weight: Decimal,
     pub snapshot_period: u64,
+    pub snapshot_ratio: Option<Decimal>,
     pub admin_manager: String,
     pub poll_gas_limit: u64,
+    pub min_vote_amount: Uint128,
+    pub reward_distributor: Option<String>,
+    pub abstain_in_quorum: bool,
+    pub poll_creation_fee: Uint128,
+    pub execution_window: u64,
+    pub max_active_polls: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -174,6 +246,7 @@ pub struct StateResponse {
     pub total_share: Uint128,
     pub total_deposit: Uint128,
     pub pending_voting_rewards: Uint128,
+    pub reward_dust: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -194,6 +267,22 @@ pub struct PollResponse {
     pub voters_reward: Uint128,
     pub staked_amount: Option<Uint128>,
     pub admin_action: Option<PollAdminAction>,
+    pub end_hook: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct PollTimelineResponse {
+    pub voting_ends_at: u64,
+    pub effective_at: u64, // voting_ends_at + effective_delay, fast track polls have no delay
+    pub expires_at: Option<u64>, // effective_at + execution_window, when a window is set
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct PollRewardStatsResponse {
+    pub poll_id: u64,
+    pub voters_reward: Uint128,
+    pub withdrawn_reward: Uint128,
+    pub remaining_reward: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -215,6 +304,27 @@ pub struct StakerResponse {
     pub pending_voting_rewards: Uint128,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct StakerRewardDebugResponse {
+    pub share: Uint128,
+    pub total_share: Uint128,
+    pub total_balance: Uint128,
+    pub balance: Uint128,
+    pub pending_voting_rewards: Uint128,
+    pub poll_rewards: Vec<PollRewardDebugItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct PollRewardDebugItem {
+    pub poll_id: u64,
+    pub status: PollStatus,
+    pub vote_balance: Uint128,
+    pub total_votes: Uint128,
+    pub voters_reward: Uint128,
+    pub reward_amount: Uint128,
+    pub withdrawable: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 pub struct SharesResponseItem {
     pub staker: String,
@@ -238,6 +348,11 @@ pub struct VotersResponse {
     pub voters: Vec<VotersResponseItem>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct HasVotedResponse {
+    pub voter_info: Option<VoterInfo>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {
     pub migration_poll_config: PollConfig,
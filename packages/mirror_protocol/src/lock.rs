@@ -23,9 +23,11 @@ pub enum ExecuteMsg {
     LockPositionFundsHook {
         position_idx: Uint128,
         receiver: String,
+        lock_period: Option<u64>,
     },
     UnlockPositionFunds {
         positions_idx: Vec<Uint128>,
+        recipient: Option<String>,
     },
     ReleasePositionFunds {
         position_idx: Uint128,
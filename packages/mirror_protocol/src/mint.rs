@@ -19,6 +19,11 @@ pub struct InstantiateMsg {
     pub base_denom: String,
     pub token_code_id: u64,
     pub protocol_fee_rate: Decimal,
+    pub max_positions_per_owner: Option<u32>,
+    /// Flat amount of collateral paid to liquidators on top of the discounted collateral
+    #[serde(default)]
+    pub liquidation_incentive: Option<Uint128>,
+    pub open_fee_rate: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -32,6 +37,7 @@ pub enum ExecuteMsg {
 
     /// Update config; only owner is allowed to execute it
     UpdateConfig {
+        /// Only proposes the owner, which takes over once it accepts the ownership
         owner: Option<String>,
         oracle: Option<String>,
         collector: Option<String>,
@@ -41,7 +47,20 @@ pub enum ExecuteMsg {
         token_code_id: Option<u64>,
         protocol_fee_rate: Option<Decimal>,
         staking: Option<String>,
+        max_positions_per_owner: Option<u32>,
+        /// Zero removes the incentive
+        liquidation_incentive: Option<Uint128>,
+        open_fee_rate: Option<Decimal>,
+        market_close_grace: Option<u64>,
+        /// Whether positions opened from now on can hold several collaterals
+        multi_collateral: Option<bool>,
     },
+    /// Propose a new owner, which only takes over once it accepts the ownership
+    ProposeOwner {
+        owner: String,
+    },
+    /// Accept the ownership as the proposed owner
+    AcceptOwnership {},
     /// Update asset related parameters
     UpdateAsset {
         asset_token: String,
@@ -60,10 +79,32 @@ pub enum ExecuteMsg {
         asset_token: String,
         end_price: Decimal,
     },
+    /// Freeze the price used for the asset in ratio checks; None resumes the oracle price.
+    /// The end_price of a migrated asset and the pre-IPO price take precedence over it
+    SetPriceOverride {
+        asset_token: String,
+        price_override: Option<Decimal>,
+    },
+    /// Cap the loan-to-value of opens and mints on the asset; None removes the cap
+    SetMaxInitialLtv {
+        asset_token: String,
+        max_initial_ltv: Option<Decimal>,
+    },
+    /// Lock period of the short sale proceeds of the asset's positions; None uses the
+    /// lock contract default
+    SetLockPeriod {
+        asset_token: String,
+        lock_period: Option<u64>,
+    },
     /// Asset feeder is allowed to trigger IPO event on preIPO assets
     TriggerIPO {
         asset_token: String,
     },
+    /// Index up to `limit` positions opened before the contract migration by collateral,
+    /// call again until `has_more` is false
+    MigratePositionsCollateralIndex {
+        limit: Option<u32>,
+    },
 
     //////////////////////
     /// User Operations
@@ -80,6 +121,10 @@ pub enum ExecuteMsg {
         position_idx: Uint128,
         collateral: Asset,
     },
+    /// Top up the position with the native collateral attached to the message
+    AddCollateral {
+        position_idx: Uint128,
+    },
     /// Withdraw collateral
     Withdraw {
         position_idx: Uint128,
@@ -91,6 +136,11 @@ pub enum ExecuteMsg {
         asset: Asset,
         short_params: Option<ShortParams>,
     },
+    /// Record a collateral ratio for off-chain alerts on the position; None removes it
+    SetPositionAlert {
+        position_idx: Uint128,
+        ratio: Option<Decimal>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -118,6 +168,8 @@ pub enum Cw20HookMsg {
     },
     /// Deposit more collateral
     Deposit { position_idx: Uint128 },
+    /// Top up the position with the sent token collateral
+    AddCollateral { position_idx: Uint128 },
     /// Convert specified asset amount and send back to user
     Burn { position_idx: Uint128 },
     /// Buy discounted collateral from the contract with their asset tokens
@@ -128,6 +180,8 @@ pub enum Cw20HookMsg {
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    /// Returns the current owner, see `common::OwnerInfo`
+    Owner {},
     AssetConfig {
         asset_token: String,
     },
@@ -137,17 +191,38 @@ pub enum QueryMsg {
     Positions {
         owner_addr: Option<String>,
         asset_token: Option<String>,
+        collateral_info: Option<AssetInfo>,
         start_after: Option<Uint128>,
         limit: Option<u32>,
         order_by: Option<OrderBy>,
     },
     NextPositionIdx {},
+    PositionCount {
+        owner_addr: String,
+    },
+    ShortPositions {
+        owner_addr: String,
+        start_after: Option<Uint128>,
+        limit: Option<u32>,
+        order_by: Option<OrderBy>,
+    },
+    /// Compares the asset oracle and collateral oracle feeds of an asset
+    PriceConsistency {
+        asset_token: String,
+    },
+    /// Positions of an asset with their current collateral ratio, using a single price fetch
+    LiquidationScan {
+        asset_token: String,
+        start_after: Option<Uint128>,
+        limit: Option<u32>,
+    },
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub owner: String,
+    pub pending_owner: Option<String>,
     pub oracle: String,
     pub collector: String,
     pub collateral_oracle: String,
@@ -157,6 +232,11 @@ pub struct ConfigResponse {
     pub base_denom: String,
     pub token_code_id: u64,
     pub protocol_fee_rate: Decimal,
+    pub max_positions_per_owner: Option<u32>,
+    pub liquidation_incentive: Option<Uint128>,
+    pub open_fee_rate: Decimal,
+    pub market_close_grace: u64,
+    pub multi_collateral: bool,
 }
 
 // We define a custom struct for each query response
@@ -167,6 +247,9 @@ pub struct AssetConfigResponse {
     pub min_collateral_ratio: Decimal,
     pub end_price: Option<Decimal>,
     pub ipo_params: Option<IPOParams>,
+    pub price_override: Option<Decimal>,
+    pub max_initial_ltv: Option<Decimal>,
+    pub lock_period: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -174,8 +257,12 @@ pub struct PositionResponse {
     pub idx: Uint128,
     pub owner: String,
     pub collateral: Asset,
+    /// Collaterals held besides `collateral`, always empty for single-collateral positions
+    pub extra_collaterals: Vec<Asset>,
     pub asset: Asset,
     pub is_short: bool,
+    pub liquidatable: bool,
+    pub alert_ratio: Option<Decimal>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
@@ -183,11 +270,49 @@ pub struct PositionsResponse {
     pub positions: Vec<PositionResponse>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidationScanResponse {
+    pub asset_token: String,
+    /// None if the asset price is not available (e.g. market closed)
+    pub asset_price: Option<Decimal>,
+    pub positions: Vec<LiquidationScanItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidationScanItem {
+    pub idx: Uint128,
+    pub owner: String,
+    pub collateral: Asset,
+    pub asset: Asset,
+    /// Collateral value over asset value, None if either price is not available
+    pub collateral_ratio: Option<Decimal>,
+    pub liquidatable: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
 pub struct NextPositionIdxResponse {
     pub next_position_idx: Uint128,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct PositionCountResponse {
+    pub owner_addr: String,
+    pub count: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceConsistencyResponse {
+    pub asset_token: String,
+    pub oracle_price: Decimal,
+    /// Seconds since the oracle price was updated
+    pub oracle_price_age: u64,
+    pub collateral_oracle_price: Decimal,
+    /// Seconds since the collateral oracle price was updated
+    pub collateral_oracle_price_age: u64,
+    pub oracle_price_stale: bool,
+    pub collateral_oracle_price_stale: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {
     pub tefi_oracle_contract: String,
@@ -8,18 +8,29 @@ use cosmwasm_std::Decimal;
 pub struct InstantiateMsg {
     pub owner: String,
     pub base_asset: String,
+    pub min_feed_interval: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     UpdateConfig {
+        /// Only proposes the owner, which takes over once it accepts the ownership
         owner: Option<String>,
+        min_feed_interval: Option<u64>,
     },
+    /// Propose a new owner, which only takes over once it accepts the ownership
+    ProposeOwner {
+        owner: String,
+    },
+    /// Accept the ownership as the proposed owner
+    AcceptOwnership {},
     /// Used to register new asset or to update feeder
     RegisterAsset {
         asset_token: String,
         feeder: String,
+        /// Price served for a new asset until its first feed
+        initial_price: Option<Decimal>,
     },
     FeedPrice {
         prices: Vec<(String, Decimal)>,
@@ -30,6 +41,8 @@ pub enum ExecuteMsg {
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    /// Returns the current owner, see `common::OwnerInfo`
+    Owner {},
     Feeder {
         asset_token: String,
     },
@@ -42,13 +55,23 @@ pub enum QueryMsg {
         limit: Option<u32>,
         order_by: Option<OrderBy>,
     },
+    /// Returns the last price of each asset without combining them into a rate
+    RawPrices {
+        assets: Vec<String>,
+    },
+    /// Returns the feeder of the asset and whether its feed is up to date
+    FeederStatus {
+        asset_token: String,
+    },
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub owner: String,
+    pub pending_owner: Option<String>,
     pub base_asset: String,
+    pub min_feed_interval: u64,
 }
 
 // We define a custom struct for each query response
@@ -72,6 +95,7 @@ pub struct PricesResponseElem {
     pub asset_token: String,
     pub price: Decimal,
     pub last_updated_time: u64,
+    pub is_initial: bool, // true until the first feed replaces the registration price
 }
 
 // We define a custom struct for each query response
@@ -80,6 +104,22 @@ pub struct PricesResponse {
     pub prices: Vec<PricesResponseElem>,
 }
 
-/// We currently take no arguments for migrations
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RawPricesResponse {
+    pub prices: Vec<PricesResponseElem>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeederStatusResponse {
+    pub asset_token: String,
+    pub feeder: String,
+    pub last_updated_time: u64, // 0 when no price was fed yet
+    pub healthy: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    pub min_feed_interval: u64,
+}
@@ -15,6 +15,8 @@ pub struct InstantiateMsg {
     pub base_denom: String,
     pub premium_min_update_interval: u64,
     pub short_reward_contract: String,
+    /// Token distributed as staking reward, defaults to `mirror_token`
+    pub reward_token: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -29,6 +31,17 @@ pub enum ExecuteMsg {
         owner: Option<String>,
         premium_min_update_interval: Option<u64>,
         short_reward_contract: Option<String>,
+        /// Rewards deposited while paused are held and refunded to their depositor on unpause
+        distribution_paused: Option<bool>,
+        unbond_period: Option<u64>,
+        /// Caps the bond accruing rewards to this share of the pool total,
+        /// measured when the staker rewards are settled
+        max_reward_share_per_staker: Option<Decimal>,
+        /// Rewards of an asset can be claimed once per cooldown, tracked per staker and asset
+        claim_cooldown: Option<u64>,
+        reward_vesting_period: Option<u64>,
+        max_oracle_price_age: Option<u64>,
+        max_price_deviation: Option<Decimal>,
     },
     RegisterAsset {
         asset_token: String,
@@ -38,6 +51,17 @@ pub enum ExecuteMsg {
         asset_token: String,
         new_staking_token: String,
     },
+    /// Merges duplicated reward infos of up to `limit` entries and recomputes the pool
+    /// totals once all of them are merged, call again until `has_more` is false
+    MigrateRewardInfos {
+        limit: Option<u32>,
+    },
+    /// Returns the LP tokens of up to `limit` stakers of the pool, their rewards are left pending.
+    /// Call again until `has_more` is false. Requires MigrateRewardInfos to have run
+    ForceUnbondPool {
+        asset_token: String,
+        limit: Option<u32>,
+    },
 
     ////////////////////////
     /// User operations ///
@@ -46,11 +70,15 @@ pub enum ExecuteMsg {
         asset_token: String,
         amount: Uint128,
     },
+    /// Withdraw the unbonded LP tokens whose unbond period is over
+    ClaimUnbonded {},
     /// Withdraw pending rewards
     Withdraw {
         // If the asset token is not given, then all rewards are withdrawn
         asset_token: Option<String>,
     },
+    /// Withdraw the vested part of the rewards withdrawn while vesting is enabled
+    ClaimVested {},
     /// Provides liquidity and automatically stakes the LP tokens
     AutoStake {
         assets: [Asset; 2],
@@ -103,6 +131,8 @@ pub struct MigrateMsg {
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    /// Returns the current owner, see `common::OwnerInfo`
+    Owner {},
     PoolInfo {
         asset_token: String,
     },
@@ -110,6 +140,20 @@ pub enum QueryMsg {
         staker_addr: String,
         asset_token: Option<String>,
     },
+    PoolApr {
+        asset_token: String,
+    },
+    /// Projects the reward share of a new bond against the current pool totals
+    BondSimulation {
+        asset_token: String,
+        amount: Uint128,
+    },
+    /// Resolves the pool of a current or deprecated LP token
+    PoolByStakingToken {
+        staking_token: String,
+    },
+    /// Bonded LP tokens of every pool, valued in base denom where a pair is available
+    TotalBonded {},
 }
 
 // We define a custom struct for each query response
@@ -123,6 +167,14 @@ pub struct ConfigResponse {
     pub base_denom: String,
     pub premium_min_update_interval: u64,
     pub short_reward_contract: String,
+    pub distribution_paused: bool,
+    pub reward_token: String,
+    pub unbond_period: u64,
+    pub max_reward_share_per_staker: Decimal,
+    pub claim_cooldown: u64,
+    pub reward_vesting_period: u64,
+    pub max_oracle_price_age: u64,
+    pub max_price_deviation: Decimal,
 }
 
 // We define a custom struct for each query response
@@ -143,6 +195,48 @@ pub struct PoolInfoResponse {
     pub migration_deprecated_staking_token: Option<String>,
 }
 
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolAprResponse {
+    pub asset_token: String,
+    /// Normal reward distributed to the pool on the last deposit
+    pub reward_amount: Uint128,
+    /// Seconds elapsed between the last two reward deposits
+    pub reward_interval: u64,
+    pub total_bond_amount: Uint128,
+    /// Value of the bonded LP tokens in base denom
+    pub total_bond_value: Uint128,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BondSimulationResponse {
+    pub asset_token: String,
+    pub bond_amount: Uint128,
+    /// Pool total bond amount after bonding
+    pub total_bond_amount: Uint128,
+    /// Share of the pool rewards accrued by the bond, capped by the max reward share
+    pub share: Decimal,
+    /// Estimated reward per reward interval, based on the last deposit
+    pub reward_amount: Uint128,
+    pub reward_interval: u64,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalBondedResponse {
+    pub pools: Vec<TotalBondedResponseItem>,
+    /// Sum of the pool values that could be priced
+    pub total_bond_value: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalBondedResponseItem {
+    pub asset_token: String,
+    pub total_bond_amount: Uint128,
+    pub total_bond_value: Option<Uint128>,
+}
+
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RewardInfoResponse {